@@ -148,6 +148,51 @@ pub fn handle_program_log(
                     decode_event::<CollectPersonalFeeEvent>(&mut slice)?
                 );
             }
+            CollectPersonalRewardEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<CollectPersonalRewardEvent>(&mut slice)?
+                );
+            }
+            RebalancePositionEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<RebalancePositionEvent>(&mut slice)?);
+            }
+            SplitPositionEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<SplitPositionEvent>(&mut slice)?);
+            }
+            MergePositionsEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<MergePositionsEvent>(&mut slice)?);
+            }
+            LockPositionEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<LockPositionEvent>(&mut slice)?);
+            }
+            UnlockPositionEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<UnlockPositionEvent>(&mut slice)?);
+            }
+            CreateShareVaultEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<CreateShareVaultEvent>(&mut slice)?);
+            }
+            DepositShareVaultEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<DepositShareVaultEvent>(&mut slice)?);
+            }
+            WithdrawShareVaultEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<WithdrawShareVaultEvent>(&mut slice)?
+                );
+            }
+            LockPositionForeverEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<LockPositionForeverEvent>(&mut slice)?
+                );
+            }
+            SetPositionDelegateEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<SetPositionDelegateEvent>(&mut slice)?
+                );
+            }
             CollectProtocolFeeEvent::DISCRIMINATOR => {
                 println!(
                     "{:#?}",
@@ -163,6 +208,12 @@ pub fn handle_program_log(
             DecreaseLiquidityEvent::DISCRIMINATOR => {
                 println!("{:#?}", decode_event::<DecreaseLiquidityEvent>(&mut slice)?);
             }
+            DecreaseLiquidityToSingleEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<DecreaseLiquidityToSingleEvent>(&mut slice)?
+                );
+            }
             IncreaseLiquidityEvent::DISCRIMINATOR => {
                 println!("{:#?}", decode_event::<IncreaseLiquidityEvent>(&mut slice)?);
             }
@@ -181,9 +232,36 @@ pub fn handle_program_log(
             SwapEvent::DISCRIMINATOR => {
                 println!("{:#?}", decode_event::<SwapEvent>(&mut slice)?);
             }
+            FlashEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<FlashEvent>(&mut slice)?);
+            }
             PoolCreatedEvent::DISCRIMINATOR => {
                 println!("{:#?}", decode_event::<PoolCreatedEvent>(&mut slice)?);
             }
+            PoolFeeChangeEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<PoolFeeChangeEvent>(&mut slice)?);
+            }
+            PoolAccessModeChangeEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<PoolAccessModeChangeEvent>(&mut slice)?
+                );
+            }
+            PoolSwapHookChangeEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<PoolSwapHookChangeEvent>(&mut slice)?
+                );
+            }
+            PoolStatusChangeEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<PoolStatusChangeEvent>(&mut slice)?);
+            }
+            PoolEmergencyExitEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<PoolEmergencyExitEvent>(&mut slice)?);
+            }
+            EmergencyWithdrawEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<EmergencyWithdrawEvent>(&mut slice)?);
+            }
             _ => {
                 println!("unknow event: {}", l);
             }
@@ -420,6 +498,121 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", UpdatePoolStatus::from(ix));
         }
+        instruction::UpdatePoolStatusV2::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::UpdatePoolStatusV2>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct UpdatePoolStatusV2 {
+                pub status: u8,
+                pub reason: u8,
+                pub resume_time: u64,
+            }
+            impl From<instruction::UpdatePoolStatusV2> for UpdatePoolStatusV2 {
+                fn from(instr: instruction::UpdatePoolStatusV2) -> UpdatePoolStatusV2 {
+                    UpdatePoolStatusV2 {
+                        status: instr.status,
+                        reason: instr.reason,
+                        resume_time: instr.resume_time,
+                    }
+                }
+            }
+            println!("{:#?}", UpdatePoolStatusV2::from(ix));
+        }
+        instruction::UpdatePoolEmergencyExit::DISCRIMINATOR => {
+            let ix =
+                decode_instruction::<instruction::UpdatePoolEmergencyExit>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct UpdatePoolEmergencyExit {
+                pub emergency_exit: bool,
+                pub owed_0: u64,
+                pub owed_1: u64,
+            }
+            impl From<instruction::UpdatePoolEmergencyExit> for UpdatePoolEmergencyExit {
+                fn from(instr: instruction::UpdatePoolEmergencyExit) -> UpdatePoolEmergencyExit {
+                    UpdatePoolEmergencyExit {
+                        emergency_exit: instr.emergency_exit,
+                        owed_0: instr.owed_0,
+                        owed_1: instr.owed_1,
+                    }
+                }
+            }
+            println!("{:#?}", UpdatePoolEmergencyExit::from(ix));
+        }
+        instruction::UpdatePoolTradeFeeRate::DISCRIMINATOR => {
+            let ix =
+                decode_instruction::<instruction::UpdatePoolTradeFeeRate>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct UpdatePoolTradeFeeRate {
+                pub trade_fee_rate: Option<u32>,
+            }
+            impl From<instruction::UpdatePoolTradeFeeRate> for UpdatePoolTradeFeeRate {
+                fn from(instr: instruction::UpdatePoolTradeFeeRate) -> UpdatePoolTradeFeeRate {
+                    UpdatePoolTradeFeeRate {
+                        trade_fee_rate: instr.trade_fee_rate,
+                    }
+                }
+            }
+            println!("{:#?}", UpdatePoolTradeFeeRate::from(ix));
+        }
+        instruction::UpdatePoolSwapHook::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::UpdatePoolSwapHook>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct UpdatePoolSwapHook {
+                pub swap_hook_program: Pubkey,
+                pub swap_hook_state: Pubkey,
+            }
+            impl From<instruction::UpdatePoolSwapHook> for UpdatePoolSwapHook {
+                fn from(instr: instruction::UpdatePoolSwapHook) -> UpdatePoolSwapHook {
+                    UpdatePoolSwapHook {
+                        swap_hook_program: instr.swap_hook_program,
+                        swap_hook_state: instr.swap_hook_state,
+                    }
+                }
+            }
+            println!("{:#?}", UpdatePoolSwapHook::from(ix));
+        }
+        instruction::CreatePoolAllowList::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::CreatePoolAllowList>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct CreatePoolAllowList;
+            impl From<instruction::CreatePoolAllowList> for CreatePoolAllowList {
+                fn from(_instr: instruction::CreatePoolAllowList) -> CreatePoolAllowList {
+                    CreatePoolAllowList
+                }
+            }
+            println!("{:#?}", CreatePoolAllowList::from(ix));
+        }
+        instruction::UpdatePoolAllowList::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::UpdatePoolAllowList>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct UpdatePoolAllowList {
+                pub param: u8,
+                pub keys: Vec<Pubkey>,
+            }
+            impl From<instruction::UpdatePoolAllowList> for UpdatePoolAllowList {
+                fn from(instr: instruction::UpdatePoolAllowList) -> UpdatePoolAllowList {
+                    UpdatePoolAllowList {
+                        param: instr.param,
+                        keys: instr.keys,
+                    }
+                }
+            }
+            println!("{:#?}", UpdatePoolAllowList::from(ix));
+        }
+        instruction::UpdatePoolAccessMode::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::UpdatePoolAccessMode>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct UpdatePoolAccessMode {
+                pub access_mode: u8,
+            }
+            impl From<instruction::UpdatePoolAccessMode> for UpdatePoolAccessMode {
+                fn from(instr: instruction::UpdatePoolAccessMode) -> UpdatePoolAccessMode {
+                    UpdatePoolAccessMode {
+                        access_mode: instr.access_mode,
+                    }
+                }
+            }
+            println!("{:#?}", UpdatePoolAccessMode::from(ix));
+        }
         instruction::CreateOperationAccount::DISCRIMINATOR => {
             let ix =
                 decode_instruction::<instruction::CreateOperationAccount>(&mut ix_data).unwrap();
@@ -618,6 +811,37 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", OpenPositionV2::from(ix));
         }
+        instruction::OpenLimitOrder::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::OpenLimitOrder>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct OpenLimitOrder {
+                pub tick_lower_index: i32,
+                pub tick_upper_index: i32,
+                pub tick_array_lower_start_index: i32,
+                pub tick_array_upper_start_index: i32,
+                pub liquidity: u128,
+                pub amount_0_max: u64,
+                pub amount_1_max: u64,
+                pub base_flag: Option<bool>,
+                pub with_metadata: bool,
+            }
+            impl From<instruction::OpenLimitOrder> for OpenLimitOrder {
+                fn from(instr: instruction::OpenLimitOrder) -> OpenLimitOrder {
+                    OpenLimitOrder {
+                        tick_lower_index: instr.tick_lower_index,
+                        tick_upper_index: instr.tick_upper_index,
+                        tick_array_lower_start_index: instr.tick_array_lower_start_index,
+                        tick_array_upper_start_index: instr.tick_array_upper_start_index,
+                        liquidity: instr.liquidity,
+                        amount_0_max: instr.amount_0_max,
+                        amount_1_max: instr.amount_1_max,
+                        base_flag: instr.base_flag,
+                        with_metadata: instr.with_metadata,
+                    }
+                }
+            }
+            println!("{:#?}", OpenLimitOrder::from(ix));
+        }
         instruction::ClosePosition::DISCRIMINATOR => {
             let ix = decode_instruction::<instruction::ClosePosition>(&mut ix_data).unwrap();
             #[derive(Debug)]
@@ -707,6 +931,307 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", DecreaseLiquidityV2::from(ix));
         }
+        instruction::DecreaseLiquidityToSingle::DISCRIMINATOR => {
+            let ix =
+                decode_instruction::<instruction::DecreaseLiquidityToSingle>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct DecreaseLiquidityToSingle {
+                pub liquidity: u128,
+                pub zero_for_one: bool,
+                pub min_amount_out: u64,
+            }
+            impl From<instruction::DecreaseLiquidityToSingle> for DecreaseLiquidityToSingle {
+                fn from(
+                    instr: instruction::DecreaseLiquidityToSingle,
+                ) -> DecreaseLiquidityToSingle {
+                    DecreaseLiquidityToSingle {
+                        liquidity: instr.liquidity,
+                        zero_for_one: instr.zero_for_one,
+                        min_amount_out: instr.min_amount_out,
+                    }
+                }
+            }
+            println!("{:#?}", DecreaseLiquidityToSingle::from(ix));
+        }
+        instruction::EmergencyWithdraw::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::EmergencyWithdraw>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct EmergencyWithdraw;
+            impl From<instruction::EmergencyWithdraw> for EmergencyWithdraw {
+                fn from(_instr: instruction::EmergencyWithdraw) -> EmergencyWithdraw {
+                    EmergencyWithdraw
+                }
+            }
+            println!("{:#?}", EmergencyWithdraw::from(ix));
+        }
+        instruction::CollectFees::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::CollectFees>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct CollectFees {
+                pub amount_0_requested: u64,
+                pub amount_1_requested: u64,
+            }
+            impl From<instruction::CollectFees> for CollectFees {
+                fn from(instr: instruction::CollectFees) -> CollectFees {
+                    CollectFees {
+                        amount_0_requested: instr.amount_0_requested,
+                        amount_1_requested: instr.amount_1_requested,
+                    }
+                }
+            }
+            println!("{:#?}", CollectFees::from(ix));
+        }
+        instruction::CollectRewards::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::CollectRewards>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct CollectRewards {
+                pub reward_index: u8,
+                pub amount_requested: u64,
+            }
+            impl From<instruction::CollectRewards> for CollectRewards {
+                fn from(instr: instruction::CollectRewards) -> CollectRewards {
+                    CollectRewards {
+                        reward_index: instr.reward_index,
+                        amount_requested: instr.amount_requested,
+                    }
+                }
+            }
+            println!("{:#?}", CollectRewards::from(ix));
+        }
+        instruction::CompoundPosition::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::CompoundPosition>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct CompoundPosition;
+            impl From<instruction::CompoundPosition> for CompoundPosition {
+                fn from(_instr: instruction::CompoundPosition) -> CompoundPosition {
+                    CompoundPosition
+                }
+            }
+            println!("{:#?}", CompoundPosition::from(ix));
+        }
+        instruction::RebalancePosition::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::RebalancePosition>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct RebalancePosition {
+                pub tick_lower_index: i32,
+                pub tick_upper_index: i32,
+                pub tick_array_lower_start_index: i32,
+                pub tick_array_upper_start_index: i32,
+                pub amount_0_min: u64,
+                pub amount_1_min: u64,
+                pub swap_amount: u64,
+                pub zero_for_one: bool,
+                pub other_amount_threshold: u64,
+            }
+            impl From<instruction::RebalancePosition> for RebalancePosition {
+                fn from(instr: instruction::RebalancePosition) -> RebalancePosition {
+                    RebalancePosition {
+                        tick_lower_index: instr.tick_lower_index,
+                        tick_upper_index: instr.tick_upper_index,
+                        tick_array_lower_start_index: instr.tick_array_lower_start_index,
+                        tick_array_upper_start_index: instr.tick_array_upper_start_index,
+                        amount_0_min: instr.amount_0_min,
+                        amount_1_min: instr.amount_1_min,
+                        swap_amount: instr.swap_amount,
+                        zero_for_one: instr.zero_for_one,
+                        other_amount_threshold: instr.other_amount_threshold,
+                    }
+                }
+            }
+            println!("{:#?}", RebalancePosition::from(ix));
+        }
+        instruction::SplitPosition::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::SplitPosition>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct SplitPosition {
+                pub liquidity: u128,
+                pub with_metadata: bool,
+            }
+            impl From<instruction::SplitPosition> for SplitPosition {
+                fn from(instr: instruction::SplitPosition) -> SplitPosition {
+                    SplitPosition {
+                        liquidity: instr.liquidity,
+                        with_metadata: instr.with_metadata,
+                    }
+                }
+            }
+            println!("{:#?}", SplitPosition::from(ix));
+        }
+        instruction::OpenPositionZap::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::OpenPositionZap>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct OpenPositionZap {
+                pub tick_lower_index: i32,
+                pub tick_upper_index: i32,
+                pub tick_array_lower_start_index: i32,
+                pub tick_array_upper_start_index: i32,
+                pub amount_in: u64,
+                pub zero_for_one: bool,
+                pub liquidity_min: u128,
+                pub with_metadata: bool,
+            }
+            impl From<instruction::OpenPositionZap> for OpenPositionZap {
+                fn from(instr: instruction::OpenPositionZap) -> OpenPositionZap {
+                    OpenPositionZap {
+                        tick_lower_index: instr.tick_lower_index,
+                        tick_upper_index: instr.tick_upper_index,
+                        tick_array_lower_start_index: instr.tick_array_lower_start_index,
+                        tick_array_upper_start_index: instr.tick_array_upper_start_index,
+                        amount_in: instr.amount_in,
+                        zero_for_one: instr.zero_for_one,
+                        liquidity_min: instr.liquidity_min,
+                        with_metadata: instr.with_metadata,
+                    }
+                }
+            }
+            println!("{:#?}", OpenPositionZap::from(ix));
+        }
+        instruction::MergePositions::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::MergePositions>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct MergePositions;
+            impl From<instruction::MergePositions> for MergePositions {
+                fn from(_instr: instruction::MergePositions) -> MergePositions {
+                    MergePositions
+                }
+            }
+            println!("{:#?}", MergePositions::from(ix));
+        }
+        instruction::SetPositionDelegate::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::SetPositionDelegate>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct SetPositionDelegate {
+                pub delegate: Pubkey,
+                pub permissions: u8,
+            }
+            impl From<instruction::SetPositionDelegate> for SetPositionDelegate {
+                fn from(instr: instruction::SetPositionDelegate) -> SetPositionDelegate {
+                    SetPositionDelegate {
+                        delegate: instr.delegate,
+                        permissions: instr.permissions,
+                    }
+                }
+            }
+            println!("{:#?}", SetPositionDelegate::from(ix));
+        }
+        instruction::LockPosition::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::LockPosition>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct LockPosition {
+                pub locked_until: u64,
+            }
+            impl From<instruction::LockPosition> for LockPosition {
+                fn from(instr: instruction::LockPosition) -> LockPosition {
+                    LockPosition {
+                        locked_until: instr.locked_until,
+                    }
+                }
+            }
+            println!("{:#?}", LockPosition::from(ix));
+        }
+        instruction::UnlockPosition::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::UnlockPosition>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct UnlockPosition;
+            impl From<instruction::UnlockPosition> for UnlockPosition {
+                fn from(_instr: instruction::UnlockPosition) -> UnlockPosition {
+                    UnlockPosition
+                }
+            }
+            println!("{:#?}", UnlockPosition::from(ix));
+        }
+        instruction::LockPositionForever::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::LockPositionForever>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct LockPositionForever {
+                pub with_metadata: bool,
+            }
+            impl From<instruction::LockPositionForever> for LockPositionForever {
+                fn from(instr: instruction::LockPositionForever) -> LockPositionForever {
+                    LockPositionForever {
+                        with_metadata: instr.with_metadata,
+                    }
+                }
+            }
+            println!("{:#?}", LockPositionForever::from(ix));
+        }
+        instruction::CollectLockedPosition::DISCRIMINATOR => {
+            let ix =
+                decode_instruction::<instruction::CollectLockedPosition>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct CollectLockedPosition {
+                pub amount_0_requested: u64,
+                pub amount_1_requested: u64,
+            }
+            impl From<instruction::CollectLockedPosition> for CollectLockedPosition {
+                fn from(instr: instruction::CollectLockedPosition) -> CollectLockedPosition {
+                    CollectLockedPosition {
+                        amount_0_requested: instr.amount_0_requested,
+                        amount_1_requested: instr.amount_1_requested,
+                    }
+                }
+            }
+            println!("{:#?}", CollectLockedPosition::from(ix));
+        }
+        instruction::CreateShareVault::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::CreateShareVault>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct CreateShareVault {
+                pub tick_lower_index: i32,
+                pub tick_upper_index: i32,
+                pub tick_array_lower_start_index: i32,
+                pub tick_array_upper_start_index: i32,
+            }
+            impl From<instruction::CreateShareVault> for CreateShareVault {
+                fn from(instr: instruction::CreateShareVault) -> CreateShareVault {
+                    CreateShareVault {
+                        tick_lower_index: instr.tick_lower_index,
+                        tick_upper_index: instr.tick_upper_index,
+                        tick_array_lower_start_index: instr.tick_array_lower_start_index,
+                        tick_array_upper_start_index: instr.tick_array_upper_start_index,
+                    }
+                }
+            }
+            println!("{:#?}", CreateShareVault::from(ix));
+        }
+        instruction::DepositShareVault::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::DepositShareVault>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct DepositShareVault {
+                pub liquidity: u128,
+                pub amount_0_max: u64,
+                pub amount_1_max: u64,
+            }
+            impl From<instruction::DepositShareVault> for DepositShareVault {
+                fn from(instr: instruction::DepositShareVault) -> DepositShareVault {
+                    DepositShareVault {
+                        liquidity: instr.liquidity,
+                        amount_0_max: instr.amount_0_max,
+                        amount_1_max: instr.amount_1_max,
+                    }
+                }
+            }
+            println!("{:#?}", DepositShareVault::from(ix));
+        }
+        instruction::WithdrawShareVault::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::WithdrawShareVault>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct WithdrawShareVault {
+                pub shares: u64,
+                pub amount_0_min: u64,
+                pub amount_1_min: u64,
+            }
+            impl From<instruction::WithdrawShareVault> for WithdrawShareVault {
+                fn from(instr: instruction::WithdrawShareVault) -> WithdrawShareVault {
+                    WithdrawShareVault {
+                        shares: instr.shares,
+                        amount_0_min: instr.amount_0_min,
+                        amount_1_min: instr.amount_1_min,
+                    }
+                }
+            }
+            println!("{:#?}", WithdrawShareVault::from(ix));
+        }
         instruction::Swap::DISCRIMINATOR => {
             let ix = decode_instruction::<instruction::Swap>(&mut ix_data).unwrap();
             #[derive(Debug)]
@@ -728,6 +1253,29 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", Swap::from(ix));
         }
+        instruction::SwapWithImpactLimit::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::SwapWithImpactLimit>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct SwapWithImpactLimit {
+                pub amount: u64,
+                pub other_amount_threshold: u64,
+                pub sqrt_price_limit_x64: u128,
+                pub is_base_input: bool,
+                pub max_price_impact_bps: u32,
+            }
+            impl From<instruction::SwapWithImpactLimit> for SwapWithImpactLimit {
+                fn from(instr: instruction::SwapWithImpactLimit) -> SwapWithImpactLimit {
+                    SwapWithImpactLimit {
+                        amount: instr.amount,
+                        other_amount_threshold: instr.other_amount_threshold,
+                        sqrt_price_limit_x64: instr.sqrt_price_limit_x64,
+                        is_base_input: instr.is_base_input,
+                        max_price_impact_bps: instr.max_price_impact_bps,
+                    }
+                }
+            }
+            println!("{:#?}", SwapWithImpactLimit::from(ix));
+        }
         instruction::SwapV2::DISCRIMINATOR => {
             let ix = decode_instruction::<instruction::SwapV2>(&mut ix_data).unwrap();
             #[derive(Debug)]
@@ -749,6 +1297,30 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", SwapV2::from(ix));
         }
+        instruction::SwapV2WithImpactLimit::DISCRIMINATOR => {
+            let ix =
+                decode_instruction::<instruction::SwapV2WithImpactLimit>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct SwapV2WithImpactLimit {
+                pub amount: u64,
+                pub other_amount_threshold: u64,
+                pub sqrt_price_limit_x64: u128,
+                pub is_base_input: bool,
+                pub max_price_impact_bps: u32,
+            }
+            impl From<instruction::SwapV2WithImpactLimit> for SwapV2WithImpactLimit {
+                fn from(instr: instruction::SwapV2WithImpactLimit) -> SwapV2WithImpactLimit {
+                    SwapV2WithImpactLimit {
+                        amount: instr.amount,
+                        other_amount_threshold: instr.other_amount_threshold,
+                        sqrt_price_limit_x64: instr.sqrt_price_limit_x64,
+                        is_base_input: instr.is_base_input,
+                        max_price_impact_bps: instr.max_price_impact_bps,
+                    }
+                }
+            }
+            println!("{:#?}", SwapV2WithImpactLimit::from(ix));
+        }
         instruction::SwapRouterBaseIn::DISCRIMINATOR => {
             let ix = decode_instruction::<instruction::SwapRouterBaseIn>(&mut ix_data).unwrap();
             #[derive(Debug)]
@@ -766,6 +1338,42 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", SwapRouterBaseIn::from(ix));
         }
+        instruction::SwapRouterBaseOut::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::SwapRouterBaseOut>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct SwapRouterBaseOut {
+                pub amount_out: u64,
+                pub amount_in_maximum: u64,
+            }
+            impl From<instruction::SwapRouterBaseOut> for SwapRouterBaseOut {
+                fn from(instr: instruction::SwapRouterBaseOut) -> SwapRouterBaseOut {
+                    SwapRouterBaseOut {
+                        amount_out: instr.amount_out,
+                        amount_in_maximum: instr.amount_in_maximum,
+                    }
+                }
+            }
+            println!("{:#?}", SwapRouterBaseOut::from(ix));
+        }
+        instruction::Flash::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::Flash>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct Flash {
+                pub amount_0: u64,
+                pub amount_1: u64,
+                pub data: Vec<u8>,
+            }
+            impl From<instruction::Flash> for Flash {
+                fn from(instr: instruction::Flash) -> Flash {
+                    Flash {
+                        amount_0: instr.amount_0,
+                        amount_1: instr.amount_1,
+                        data: instr.data,
+                    }
+                }
+            }
+            println!("{:#?}", Flash::from(ix));
+        }
         _ => {
             println!("unknow instruction: {}", instr_data);
         }
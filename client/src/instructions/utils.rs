@@ -22,13 +22,22 @@ use spl_token_2022::{
     state::Mint,
 };
 use std::collections::VecDeque;
-use std::ops::{DerefMut, Mul, Neg};
+use std::ops::{Mul, Neg};
 
 pub fn deserialize_anchor_account<T: AccountDeserialize>(account: &Account) -> Result<T> {
     let mut data: &[u8] = &account.data;
     T::try_deserialize(&mut data).map_err(Into::into)
 }
 
+/// Deserializes a tick array, leaving out the limit order fills stored after the TickArrayState
+pub fn deserialize_tick_array(account: &Account) -> Result<TickArrayState> {
+    let mut data: &[u8] = account
+        .data
+        .get(..TickArrayState::LEN)
+        .ok_or_else(|| anyhow::format_err!("invalid tick array account"))?;
+    TickArrayState::try_deserialize(&mut data).map_err(Into::into)
+}
+
 #[derive(Debug)]
 pub enum ExtensionStruct {
     ConfidentialTransferAccount(ConfidentialTransferAccount),
@@ -440,11 +449,11 @@ fn swap_compute(
                 return Result::Err("tick array start tick index does not match");
             }
             tick_array_start_index_vec.push_back(tick_array_current.start_tick_index);
-            let mut first_initialized_tick = tick_array_current
+            let first_initialized_tick = tick_array_current
                 .first_initialized_tick(zero_for_one)
                 .unwrap();
 
-            next_initialized_tick = Box::new(*first_initialized_tick.deref_mut());
+            next_initialized_tick = Box::new(*first_initialized_tick);
         }
         step.tick_next = next_initialized_tick.tick;
         step.initialized = next_initialized_tick.is_initialized();
@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 use anchor_client::{Client, Cluster};
 use anchor_lang::prelude::AccountMeta;
+use anchor_lang::Discriminator;
 use anyhow::{format_err, Result};
 use arrayref::array_ref;
 use clap::Parser;
@@ -239,11 +240,7 @@ fn load_cur_and_next_five_tick_array(
     let tick_array_rsps = rpc_client.get_multiple_accounts(&tick_array_keys).unwrap();
     let mut tick_arrays = VecDeque::new();
     for tick_array in tick_array_rsps {
-        let tick_array_state =
-            deserialize_anchor_account::<swap_io_clmm::states::TickArrayState>(
-                &tick_array.unwrap(),
-            )
-            .unwrap();
+        let tick_array_state = deserialize_tick_array(&tick_array.unwrap()).unwrap();
         tick_arrays.push_back(tick_array_state);
     }
     tick_arrays
@@ -470,6 +467,8 @@ pub enum CommandsName {
         simulate: bool,
         amount: u64,
         limit_price: Option<f64>,
+        #[arg(long)]
+        max_price_impact_bps: Option<u32>,
     },
     PPositionByOwner {
         user_wallet: Pubkey,
@@ -1773,6 +1772,7 @@ fn main() -> Result<()> {
             simulate,
             amount,
             limit_price,
+            max_price_impact_bps,
         } => {
             // load mult account
             let load_accounts = vec![
@@ -1899,40 +1899,78 @@ fn main() -> Result<()> {
             let mut instructions = Vec::new();
             let request_inits_instr = ComputeBudgetInstruction::set_compute_unit_limit(1400_000u32);
             instructions.push(request_inits_instr);
-            let swap_instr = swap_v2_instr(
-                &pool_config.clone(),
-                pool_state.amm_config,
-                pool_config.pool_id_account.unwrap(),
-                if zero_for_one {
-                    pool_state.token_vault_0
-                } else {
-                    pool_state.token_vault_1
-                },
-                if zero_for_one {
-                    pool_state.token_vault_1
-                } else {
-                    pool_state.token_vault_0
-                },
-                pool_state.observation_key,
-                input_token,
-                output_token,
-                if zero_for_one {
-                    pool_state.token_mint_0
-                } else {
-                    pool_state.token_mint_1
-                },
-                if zero_for_one {
-                    pool_state.token_mint_1
-                } else {
-                    pool_state.token_mint_0
-                },
-                remaining_accounts,
-                amount,
-                other_amount_threshold,
-                sqrt_price_limit_x64,
-                base_in,
-            )
-            .unwrap();
+            let swap_instr = if let Some(max_price_impact_bps) = max_price_impact_bps {
+                swap_v2_with_impact_limit_instr(
+                    &pool_config.clone(),
+                    pool_state.amm_config,
+                    pool_config.pool_id_account.unwrap(),
+                    if zero_for_one {
+                        pool_state.token_vault_0
+                    } else {
+                        pool_state.token_vault_1
+                    },
+                    if zero_for_one {
+                        pool_state.token_vault_1
+                    } else {
+                        pool_state.token_vault_0
+                    },
+                    pool_state.observation_key,
+                    input_token,
+                    output_token,
+                    if zero_for_one {
+                        pool_state.token_mint_0
+                    } else {
+                        pool_state.token_mint_1
+                    },
+                    if zero_for_one {
+                        pool_state.token_mint_1
+                    } else {
+                        pool_state.token_mint_0
+                    },
+                    remaining_accounts,
+                    amount,
+                    other_amount_threshold,
+                    sqrt_price_limit_x64,
+                    base_in,
+                    max_price_impact_bps,
+                )
+                .unwrap()
+            } else {
+                swap_v2_instr(
+                    &pool_config.clone(),
+                    pool_state.amm_config,
+                    pool_config.pool_id_account.unwrap(),
+                    if zero_for_one {
+                        pool_state.token_vault_0
+                    } else {
+                        pool_state.token_vault_1
+                    },
+                    if zero_for_one {
+                        pool_state.token_vault_1
+                    } else {
+                        pool_state.token_vault_0
+                    },
+                    pool_state.observation_key,
+                    input_token,
+                    output_token,
+                    if zero_for_one {
+                        pool_state.token_mint_0
+                    } else {
+                        pool_state.token_mint_1
+                    },
+                    if zero_for_one {
+                        pool_state.token_mint_1
+                    } else {
+                        pool_state.token_mint_0
+                    },
+                    remaining_accounts,
+                    amount,
+                    other_amount_threshold,
+                    sqrt_price_limit_x64,
+                    base_in,
+                )
+                .unwrap()
+            };
             instructions.extend(swap_instr);
             // send
             let signers = vec![&payer];
@@ -2218,7 +2256,11 @@ fn main() -> Result<()> {
                 RpcProgramAccountsConfig {
                     filters: Some(vec![
                         RpcFilterType::Memcmp(Memcmp::new_base58_encoded(8, &pool_id.to_bytes())),
-                        RpcFilterType::DataSize(swap_io_clmm::states::TickArrayState::LEN as u64),
+                        // tick arrays holding limit orders are longer than TickArrayState::LEN
+                        RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                            0,
+                            &swap_io_clmm::states::TickArrayState::discriminator(),
+                        )),
                     ]),
                     account_config: RpcAccountInfoConfig {
                         encoding: Some(UiAccountEncoding::Base64Zstd),
@@ -2229,9 +2271,7 @@ fn main() -> Result<()> {
             )?;
 
             for tick_array in tick_arrays_by_pool {
-                let tick_array_state = deserialize_anchor_account::<
-                    swap_io_clmm::states::TickArrayState,
-                >(&tick_array.1)?;
+                let tick_array_state = deserialize_tick_array(&tick_array.1)?;
                 if tick_array_state.pool_id == pool_id {
                     println!(
                         "tick_array:{}, {}, {}",
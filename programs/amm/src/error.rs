@@ -70,6 +70,8 @@ pub enum ErrorCode {
     NotEnoughTickArrayAccount,
     #[msg("Invaild first tick array account")]
     InvalidFirstTickArrayAccount,
+    #[msg("The pool can not fill the whole output of the hop")]
+    HopPartiallyFilled,
 
     /// reward errors
     #[msg("Invalid reward index")]
@@ -105,4 +107,98 @@ pub enum ErrorCode {
     MaxTokenOverflow,
     #[msg("calculate overflow")]
     CalculateOverflow,
+
+    /// oracle errors
+    #[msg("The oracle has no observation yet")]
+    OracleNotInitialized,
+    #[msg("The target time is older than the oldest observation")]
+    OracleObservationTooOld,
+    #[msg("Invalid seconds agos")]
+    InvalidSecondsAgos,
+    #[msg("Observation cardinality exceeds the max of the amm config")]
+    InvalidObservationCardinality,
+    #[msg("The tick is not initialized")]
+    TickNotInitialized,
+    #[msg("The pool is not open for swapping yet")]
+    PoolNotOpen,
+
+    /// limit order errors
+    #[msg("Limit order must span one tick spacing in one tick array, entirely above or below the current price")]
+    InvalidLimitOrder,
+    #[msg("The limit order has been filled")]
+    LimitOrderFilled,
+    #[msg("The fill account of the limit orders is missing")]
+    LimitOrderFillAccountMissing,
+    #[msg("Price impact exceeds the max price impact")]
+    PriceImpactTooHigh,
+    #[msg("The rebalance range must differ from the current range of the position")]
+    InvalidRebalanceRange,
+    #[msg("Limit order can not be rebalanced")]
+    LimitOrderNotRebalanceable,
+    #[msg("Split liquidity must be less than the liquidity of the position")]
+    InvalidSplitLiquidity,
+    #[msg("Positions to merge must share the pool and range, and not be limit orders")]
+    InvalidMergePositions,
+
+    /// position delegate errors
+    #[msg("Invalid position delegate permissions")]
+    InvalidDelegatePermissions,
+    #[msg("The recipient must be owned by the position NFT holder when a delegate acts on the position")]
+    RecipientNotPositionOwner,
+
+    /// position lock errors
+    #[msg("The liquidity of the position is locked")]
+    PositionLocked,
+    #[msg("The lock must end later than the current time and the current lock")]
+    InvalidLockTime,
+    #[msg("Limit order can not be locked forever")]
+    LimitOrderNotLockable,
+
+    /// share vault errors
+    #[msg("Invalid share amount")]
+    InvalidShareAmount,
+    #[msg("Share vaults can not be created on pools with rewards")]
+    ShareVaultRewardsNotSupported,
+    #[msg("The share vault is frozen since its pool has rewards, only withdrawals are allowed")]
+    ShareVaultFrozen,
+
+    /// flash errors
+    #[msg("Flash amounts can not be both zero")]
+    InvalidFlashAmount,
+    #[msg("Flash requires liquidity in range to pay the fee to")]
+    FlashNoLiquidity,
+    #[msg("The flash amount and fee were not paid back to the vault")]
+    FlashNotRepaid,
+    #[msg("Flash receiver can not be this program")]
+    InvalidFlashReceiver,
+
+    /// swap hook errors
+    #[msg("The swap hook program and state accounts of the pool are missing")]
+    SwapHookAccountsMissing,
+    #[msg("The swap hook state must be set with the swap hook program")]
+    InvalidSwapHookState,
+    #[msg("Swaps of a pool with a swap hook are only supported by the swap instructions")]
+    SwapHookNotSupported,
+
+    /// pool allow list errors
+    #[msg("The allow list is full")]
+    AllowListFull,
+    #[msg("The allow list of the pool is missing")]
+    PoolAllowListMissing,
+    #[msg("The wallet is not in the allow list of the pool")]
+    NotAllowListed,
+
+    /// pool status errors
+    #[msg("The resume time must be in the future and set with a status")]
+    InvalidStatusResumeTime,
+
+    /// emergency exit errors
+    #[msg("The pool is not in emergency exit")]
+    NotEmergencyExit,
+    #[msg("The emergency exit of a pool can not be undone or recorded again")]
+    EmergencyExitIrreversible,
+    #[msg("Limit order can not be withdrawn in emergency exit")]
+    LimitOrderNotEmergencyWithdrawable,
+    #[msg("The vault can not pay the emergency withdraw")]
+    EmergencyWithdrawVaultShortfall,
 }
@@ -72,6 +72,9 @@ pub fn collect_protocol_fee(
     let amount_1: u64;
     {
         let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        if !pool_state.get_status_by_bit(PoolStatusBitIndex::CollectProtocolFee)? {
+            return err!(ErrorCode::NotApproved);
+        }
 
         amount_0 = amount_0_requested.min(pool_state.protocol_fees_token_0);
         amount_1 = amount_1_requested.min(pool_state.protocol_fees_token_1);
@@ -0,0 +1,52 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CreatePoolAllowList<'info> {
+    /// The admin or an operation owner, pays to create the allow list
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The pool of the allow list
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Initialize the allow list account of the pool
+    #[account(
+        init,
+        seeds = [
+            POOL_ALLOW_LIST_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = authority,
+        space = PoolAllowListState::LEN
+    )]
+    pub pool_allow_list: AccountLoader<'info, PoolAllowListState>,
+
+    /// load info from the account to judge the permission
+    #[account(
+        seeds = [
+            OPERATION_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub operation_state: AccountLoader<'info, OperationState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_pool_allow_list(ctx: Context<CreatePoolAllowList>) -> Result<()> {
+    require!(
+        ctx.accounts.authority.key() == crate::admin::id()
+            || ctx
+                .accounts
+                .operation_state
+                .load()?
+                .validate_operation_owner(ctx.accounts.authority.key()),
+        ErrorCode::NotApproved
+    );
+    let mut pool_allow_list = ctx.accounts.pool_allow_list.load_init()?;
+    pool_allow_list.initialize(ctx.bumps.pool_allow_list, ctx.accounts.pool_state.key());
+    Ok(())
+}
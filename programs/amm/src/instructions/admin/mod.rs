@@ -22,6 +22,27 @@ pub use transfer_reward_owner::*;
 pub mod update_pool_status;
 pub use update_pool_status::*;
 
+pub mod update_pool_status_v2;
+pub use update_pool_status_v2::*;
+
+pub mod update_pool_emergency_exit;
+pub use update_pool_emergency_exit::*;
+
+pub mod update_pool_trade_fee_rate;
+pub use update_pool_trade_fee_rate::*;
+
+pub mod update_pool_swap_hook;
+pub use update_pool_swap_hook::*;
+
+pub mod create_pool_allow_list;
+pub use create_pool_allow_list::*;
+
+pub mod update_pool_allow_list;
+pub use update_pool_allow_list::*;
+
+pub mod update_pool_access_mode;
+pub use update_pool_access_mode::*;
+
 pub mod create_support_mint_associated;
 pub use create_support_mint_associated::*;
 
@@ -28,6 +28,10 @@ pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u8, value: u32) -
             let new_fund_owner = *ctx.remaining_accounts.iter().next().unwrap().key;
             set_new_fund_owner(amm_config, new_fund_owner);
         }
+        Some(5) => update_max_observation_cardinality(amm_config, value),
+        Some(6) => update_dynamic_fee_max_rate(amm_config, value),
+        Some(7) => update_dynamic_fee_control(amm_config, value),
+        Some(8) => update_dynamic_fee_decay_period(amm_config, value),
         _ => return err!(ErrorCode::InvalidUpdateConfigFlag),
     }
 
@@ -61,6 +65,35 @@ fn update_fund_fee_rate(amm_config: &mut Account<AmmConfig>, fund_fee_rate: u32)
     amm_config.fund_fee_rate = fund_fee_rate;
 }
 
+fn update_max_observation_cardinality(
+    amm_config: &mut Account<AmmConfig>,
+    max_observation_cardinality: u32,
+) {
+    assert!(max_observation_cardinality >= OBSERVATION_NUM as u32);
+    assert!(max_observation_cardinality <= u16::MAX as u32);
+    amm_config.max_observation_cardinality = max_observation_cardinality as u16;
+}
+
+fn update_dynamic_fee_max_rate(amm_config: &mut Account<AmmConfig>, dynamic_fee_max_rate: u32) {
+    assert!(dynamic_fee_max_rate < FEE_RATE_DENOMINATOR_VALUE);
+    assert!(dynamic_fee_max_rate == 0 || dynamic_fee_max_rate >= amm_config.trade_fee_rate);
+    amm_config.dynamic_fee_max_rate = dynamic_fee_max_rate;
+}
+
+fn update_dynamic_fee_control(amm_config: &mut Account<AmmConfig>, dynamic_fee_control: u32) {
+    assert!(dynamic_fee_control < FEE_RATE_DENOMINATOR_VALUE);
+    amm_config.dynamic_fee_control = dynamic_fee_control;
+}
+
+fn update_dynamic_fee_decay_period(
+    amm_config: &mut Account<AmmConfig>,
+    dynamic_fee_decay_period: u32,
+) {
+    assert!(dynamic_fee_decay_period > 0);
+    assert!(dynamic_fee_decay_period <= DYNAMIC_FEE_DECAY_PERIOD_MAX);
+    amm_config.dynamic_fee_decay_period = dynamic_fee_decay_period;
+}
+
 fn set_new_owner(amm_config: &mut Account<AmmConfig>, new_owner: Pubkey) {
     #[cfg(feature = "enable-log")]
     msg!(
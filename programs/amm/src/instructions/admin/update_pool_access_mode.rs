@@ -0,0 +1,47 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolAccessMode<'info> {
+    /// The admin or an operation owner
+    pub authority: Signer<'info>,
+
+    /// The pool whose access mode is changed
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// load info from the account to judge the permission
+    #[account(
+        seeds = [
+            OPERATION_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub operation_state: AccountLoader<'info, OperationState>,
+}
+
+pub fn update_pool_access_mode(ctx: Context<UpdatePoolAccessMode>, access_mode: u8) -> Result<()> {
+    require!(
+        ctx.accounts.authority.key() == crate::admin::id()
+            || ctx
+                .accounts
+                .operation_state
+                .load()?
+                .validate_operation_owner(ctx.accounts.authority.key()),
+        ErrorCode::NotApproved
+    );
+    require_gte!(POOL_ACCESS_MODE_MASK, access_mode);
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let access_mode_before = pool_state.access_mode;
+    pool_state.access_mode = access_mode;
+
+    emit!(PoolAccessModeChangeEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        access_mode_before,
+        access_mode,
+    });
+
+    Ok(())
+}
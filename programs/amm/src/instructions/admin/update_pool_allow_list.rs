@@ -0,0 +1,48 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolAllowList<'info> {
+    /// The admin or an operation owner
+    pub authority: Signer<'info>,
+
+    /// The allow list to update
+    #[account(mut)]
+    pub pool_allow_list: AccountLoader<'info, PoolAllowListState>,
+
+    /// load info from the account to judge the permission
+    #[account(
+        seeds = [
+            OPERATION_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub operation_state: AccountLoader<'info, OperationState>,
+}
+
+pub fn update_pool_allow_list(
+    ctx: Context<UpdatePoolAllowList>,
+    param: u8,
+    keys: Vec<Pubkey>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.authority.key() == crate::admin::id()
+            || ctx
+                .accounts
+                .operation_state
+                .load()?
+                .validate_operation_owner(ctx.accounts.authority.key()),
+        ErrorCode::NotApproved
+    );
+    let mut pool_allow_list = ctx.accounts.pool_allow_list.load_mut()?;
+    let match_param = Some(param);
+    match match_param {
+        Some(0) => pool_allow_list.update_traders(keys)?,
+        Some(1) => pool_allow_list.remove_traders(keys),
+        Some(2) => pool_allow_list.update_liquidity_providers(keys)?,
+        Some(3) => pool_allow_list.remove_liquidity_providers(keys),
+        _ => return err!(ErrorCode::InvalidUpdateConfigFlag),
+    }
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct UpdatePoolEmergencyExit<'info> {
+    #[account(
+        address = crate::admin::id()
+    )]
+    pub authority: Signer<'info>,
+
+    /// The pool put in emergency exit
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The vault of token_0 of the pool
+    #[account(
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault of token_1 of the pool
+    #[account(
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+pub fn update_pool_emergency_exit(
+    ctx: Context<UpdatePoolEmergencyExit>,
+    emergency_exit: bool,
+    owed_0: u64,
+    owed_1: u64,
+) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    // positions may already be withdrawn at the emergency price, the pool can not resume,
+    // and the amounts recorded for the withdrawals can not change
+    require!(
+        emergency_exit && !pool_state.is_emergency_exit(),
+        ErrorCode::EmergencyExitIrreversible
+    );
+    pool_state.set_emergency_exit(
+        ctx.accounts.token_vault_0.amount,
+        ctx.accounts.token_vault_1.amount,
+        owed_0,
+        owed_1,
+    );
+
+    emit!(PoolEmergencyExitEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        emergency_exit,
+        emergency_available_0: pool_state.emergency_available_0,
+        emergency_available_1: pool_state.emergency_available_1,
+        emergency_owed_0: pool_state.emergency_owed_0,
+        emergency_owed_1: pool_state.emergency_owed_1,
+    });
+
+    Ok(())
+}
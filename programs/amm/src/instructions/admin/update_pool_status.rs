@@ -15,6 +15,16 @@ pub struct UpdatePoolStatus<'info> {
 pub fn update_pool_status(ctx: Context<UpdatePoolStatus>, status: u8) -> Result<()> {
     require_gte!(255, status);
     let mut pool_state = ctx.accounts.pool_state.load_mut()?;
-    pool_state.set_status(status);
+    let status_before = pool_state.status;
+    pool_state.set_status_with_reason(status, 0, 0);
+
+    emit!(PoolStatusChangeEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        authority: ctx.accounts.authority.key(),
+        status_before,
+        status,
+        reason: 0,
+        resume_time: 0,
+    });
     Ok(())
 }
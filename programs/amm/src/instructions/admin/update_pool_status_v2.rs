@@ -0,0 +1,63 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolStatusV2<'info> {
+    /// The admin or an operation owner
+    pub authority: Signer<'info>,
+
+    /// The pool whose status is changed
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// load info from the account to judge the permission
+    #[account(
+        seeds = [
+            OPERATION_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub operation_state: AccountLoader<'info, OperationState>,
+}
+
+/// Sets the status of the pool with a reason code for the audit trail. A non zero
+/// `resume_time` lifts the status once the block time reaches it
+pub fn update_pool_status_v2(
+    ctx: Context<UpdatePoolStatusV2>,
+    status: u8,
+    reason: u8,
+    resume_time: u64,
+) -> Result<()> {
+    require!(
+        ctx.accounts.authority.key() == crate::admin::id()
+            || ctx
+                .accounts
+                .operation_state
+                .load()?
+                .validate_operation_owner(ctx.accounts.authority.key()),
+        ErrorCode::NotApproved
+    );
+    if resume_time != 0 {
+        let block_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
+        require!(
+            status != 0 && resume_time > block_timestamp,
+            ErrorCode::InvalidStatusResumeTime
+        );
+    }
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let status_before = pool_state.status;
+    pool_state.set_status_with_reason(status, reason, resume_time);
+
+    emit!(PoolStatusChangeEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        authority: ctx.accounts.authority.key(),
+        status_before,
+        status,
+        reason,
+        resume_time,
+    });
+
+    Ok(())
+}
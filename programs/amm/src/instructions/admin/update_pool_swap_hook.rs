@@ -0,0 +1,58 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolSwapHook<'info> {
+    /// The admin or an operation owner
+    pub authority: Signer<'info>,
+
+    /// The pool whose swap hook is changed
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// load info from the account to judge the permission
+    #[account(
+        seeds = [
+            OPERATION_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub operation_state: AccountLoader<'info, OperationState>,
+}
+
+pub fn update_pool_swap_hook(
+    ctx: Context<UpdatePoolSwapHook>,
+    swap_hook_program: Pubkey,
+    swap_hook_state: Pubkey,
+) -> Result<()> {
+    require!(
+        ctx.accounts.authority.key() == crate::admin::id()
+            || ctx
+                .accounts
+                .operation_state
+                .load()?
+                .validate_operation_owner(ctx.accounts.authority.key()),
+        ErrorCode::NotApproved
+    );
+    // a hook always has its state account, and none is kept when the hook is cleared
+    require!(
+        (swap_hook_program == Pubkey::default()) == (swap_hook_state == Pubkey::default())
+            && (swap_hook_program == Pubkey::default() || swap_hook_program != swap_hook_state),
+        ErrorCode::InvalidSwapHookState
+    );
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let swap_hook_program_before = pool_state.swap_hook_program;
+    pool_state.swap_hook_program = swap_hook_program;
+    pool_state.swap_hook_state = swap_hook_state;
+
+    emit!(PoolSwapHookChangeEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        swap_hook_program_before,
+        swap_hook_program,
+        swap_hook_state,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolTradeFeeRate<'info> {
+    /// The admin or an operation owner
+    pub authority: Signer<'info>,
+
+    /// The pool whose trade fee is changed
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// load info from the account to judge the permission
+    #[account(
+        seeds = [
+            OPERATION_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub operation_state: AccountLoader<'info, OperationState>,
+}
+
+pub fn update_pool_trade_fee_rate(
+    ctx: Context<UpdatePoolTradeFeeRate>,
+    trade_fee_rate: Option<u32>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.authority.key() == crate::admin::id()
+            || ctx
+                .accounts
+                .operation_state
+                .load()?
+                .validate_operation_owner(ctx.accounts.authority.key()),
+        ErrorCode::NotApproved
+    );
+    if let Some(trade_fee_rate) = trade_fee_rate {
+        require_gt!(FEE_RATE_DENOMINATOR_VALUE, trade_fee_rate);
+    }
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let trade_fee_rate_override_before = pool_state.get_trade_fee_rate_override();
+    pool_state.set_trade_fee_rate_override(trade_fee_rate);
+
+    emit!(PoolFeeChangeEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        trade_fee_rate_override_before,
+        trade_fee_rate_override: trade_fee_rate,
+    });
+
+    Ok(())
+}
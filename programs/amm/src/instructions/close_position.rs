@@ -47,6 +47,11 @@ pub struct ClosePosition<'info> {
 pub fn close_position<'a, 'b, 'c, 'info>(
     ctx: Context<'a, 'b, 'c, 'info, ClosePosition<'info>>,
 ) -> Result<()> {
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    require!(
+        !ctx.accounts.personal_position.is_locked(block_timestamp),
+        ErrorCode::PositionLocked
+    );
     if ctx.accounts.personal_position.liquidity != 0
         || ctx.accounts.personal_position.token_fees_owed_0 != 0
         || ctx.accounts.personal_position.token_fees_owed_1 != 0
@@ -0,0 +1,424 @@
+use super::calculate_latest_token_fees;
+use super::check_unclaimed_fees_and_vault;
+use super::modify_position;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::transfer_from_pool_vault_to_user;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+use std::cell::RefMut;
+
+#[derive(Accounts)]
+pub struct CollectFees<'info> {
+    /// The position NFT holder, or a delegate approved to collect
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint,
+        constraint = nft_account.amount == 1,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Collect fees for this position
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Token_0 vault
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_1 vault
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Stores init state for the lower tick
+    #[account(constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick
+    #[account(constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+
+    /// The destination token account for receive amount_0
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The destination token account for receive amount_1
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+pub fn collect_fees<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CollectFees<'info>>,
+    amount_0_requested: u64,
+    amount_1_requested: u64,
+) -> Result<()> {
+    let recipient_owner = ctx.accounts.personal_position.check_authority(
+        ctx.accounts.nft_owner.key(),
+        ctx.accounts.nft_account.owner,
+        PositionPermissionBitIndex::Collect,
+    )?;
+    check_recipient_owner(
+        recipient_owner,
+        ctx.accounts.recipient_token_account_0.owner,
+    )?;
+    check_recipient_owner(
+        recipient_owner,
+        ctx.accounts.recipient_token_account_1.owner,
+    )?;
+    collect_position_fees(
+        &ctx.accounts.pool_state,
+        &mut ctx.accounts.protocol_position,
+        &mut ctx.accounts.personal_position,
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.recipient_token_account_0.to_account_info(),
+        &ctx.accounts.recipient_token_account_1.to_account_info(),
+        &ctx.accounts.token_program,
+        &ctx.accounts.token_program_2022,
+        ctx.accounts.vault_0_mint.clone(),
+        ctx.accounts.vault_1_mint.clone(),
+        amount_0_requested,
+        amount_1_requested,
+        ctx.remaining_accounts,
+    )
+}
+
+/// Collect up to the requested fees owed to the position, after settling the fees it earned since its last update
+pub fn collect_position_fees<'info>(
+    pool_state_loader: &AccountLoader<'info, PoolState>,
+    protocol_position: &mut ProtocolPositionState,
+    personal_position: &mut PersonalPositionState,
+    tick_array_lower_loader: &AccountLoader<'info, TickArrayState>,
+    tick_array_upper_loader: &AccountLoader<'info, TickArrayState>,
+    token_vault_0: &AccountInfo<'info>,
+    token_vault_1: &AccountInfo<'info>,
+    recipient_token_account_0: &AccountInfo<'info>,
+    recipient_token_account_1: &AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+    token_program_2022: &Program<'info, Token2022>,
+    vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+    vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+    amount_0_requested: u64,
+    amount_1_requested: u64,
+    remaining_accounts: &[AccountInfo],
+) -> Result<()> {
+    let (amount_0, amount_1) = {
+        let mut pool_state = pool_state_loader.load_mut()?;
+        if !pool_state.get_status_by_bit(PoolStatusBitIndex::CollectFee)? {
+            return err!(ErrorCode::NotApproved);
+        }
+        update_position_fees_and_rewards(
+            &mut pool_state,
+            protocol_position,
+            personal_position,
+            tick_array_lower_loader,
+            tick_array_upper_loader,
+            remaining_accounts,
+        )?;
+
+        let amount_0 = amount_0_requested.min(personal_position.token_fees_owed_0);
+        let amount_1 = amount_1_requested.min(personal_position.token_fees_owed_1);
+
+        require_gte!(
+            pool_state.total_fees_token_0 - pool_state.total_fees_claimed_token_0,
+            amount_0
+        );
+        require_gte!(
+            pool_state.total_fees_token_1 - pool_state.total_fees_claimed_token_1,
+            amount_1
+        );
+
+        personal_position.token_fees_owed_0 = personal_position
+            .token_fees_owed_0
+            .checked_sub(amount_0)
+            .unwrap();
+        personal_position.token_fees_owed_1 = personal_position
+            .token_fees_owed_1
+            .checked_sub(amount_1)
+            .unwrap();
+
+        pool_state.total_fees_claimed_token_0 = pool_state
+            .total_fees_claimed_token_0
+            .checked_add(amount_0)
+            .unwrap();
+        pool_state.total_fees_claimed_token_1 = pool_state
+            .total_fees_claimed_token_1
+            .checked_add(amount_1)
+            .unwrap();
+        (amount_0, amount_1)
+    };
+
+    transfer_from_pool_vault_to_user(
+        pool_state_loader,
+        token_vault_0,
+        recipient_token_account_0,
+        Some(vault_0_mint),
+        token_program,
+        Some(token_program_2022.to_account_info()),
+        amount_0,
+    )?;
+
+    transfer_from_pool_vault_to_user(
+        pool_state_loader,
+        token_vault_1,
+        recipient_token_account_1,
+        Some(vault_1_mint),
+        token_program,
+        Some(token_program_2022.to_account_info()),
+        amount_1,
+    )?;
+
+    check_unclaimed_fees_and_vault(pool_state_loader, token_vault_0, token_vault_1)?;
+
+    emit!(CollectPersonalFeeEvent {
+        position_nft_mint: personal_position.nft_mint,
+        recipient_token_account_0: recipient_token_account_0.key(),
+        recipient_token_account_1: recipient_token_account_1.key(),
+        amount_0,
+        amount_1,
+    });
+
+    Ok(())
+}
+
+/// Settle the fees and rewards earned by the position since its last update, without changing its liquidity
+pub fn update_position_fees_and_rewards<'info>(
+    pool_state: &mut RefMut<PoolState>,
+    protocol_position: &mut ProtocolPositionState,
+    personal_position: &mut PersonalPositionState,
+    tick_array_lower_loader: &AccountLoader<'info, TickArrayState>,
+    tick_array_upper_loader: &AccountLoader<'info, TickArrayState>,
+    remaining_accounts: &[AccountInfo],
+) -> Result<()> {
+    // a filled limit order is settled at its price range only, it earns up to its fill
+    if personal_position.is_limit_order()
+        && personal_position.is_limit_order_filled(tick_array_lower_loader.load()?.get_tick_state(
+            personal_position.limit_order_fill_tick,
+            pool_state.tick_spacing,
+        )?)
+    {
+        return update_filled_limit_order_fees_and_rewards(
+            personal_position,
+            tick_array_lower_loader.as_ref(),
+            remaining_accounts,
+        );
+    }
+    let mut tick_lower_state = *tick_array_lower_loader
+        .load()?
+        .get_tick_state(protocol_position.tick_lower_index, pool_state.tick_spacing)?;
+    let mut tick_upper_state = *tick_array_upper_loader
+        .load()?
+        .get_tick_state(protocol_position.tick_upper_index, pool_state.tick_spacing)?;
+    settle_position_fees_and_rewards(
+        pool_state,
+        protocol_position,
+        personal_position,
+        &mut tick_lower_state,
+        &mut tick_upper_state,
+        Clock::get()?.unix_timestamp as u64,
+    )
+}
+
+/// Settle the fees and rewards earned by the position since its last update at `timestamp`,
+/// the tick states are read only
+pub fn settle_position_fees_and_rewards(
+    pool_state: &mut RefMut<PoolState>,
+    protocol_position: &mut ProtocolPositionState,
+    personal_position: &mut PersonalPositionState,
+    tick_lower_state: &mut TickState,
+    tick_upper_state: &mut TickState,
+    timestamp: u64,
+) -> Result<()> {
+    // a zero liquidity delta leaves the ticks untouched, only the position growth is refreshed
+    modify_position(
+        0,
+        pool_state,
+        protocol_position,
+        tick_lower_state,
+        tick_upper_state,
+        timestamp,
+    )?;
+
+    personal_position.token_fees_owed_0 = calculate_latest_token_fees(
+        personal_position.token_fees_owed_0,
+        personal_position.fee_growth_inside_0_last_x64,
+        protocol_position.fee_growth_inside_0_last_x64,
+        personal_position.liquidity,
+    );
+    personal_position.token_fees_owed_1 = calculate_latest_token_fees(
+        personal_position.token_fees_owed_1,
+        personal_position.fee_growth_inside_1_last_x64,
+        protocol_position.fee_growth_inside_1_last_x64,
+        personal_position.liquidity,
+    );
+    personal_position.fee_growth_inside_0_last_x64 = protocol_position.fee_growth_inside_0_last_x64;
+    personal_position.fee_growth_inside_1_last_x64 = protocol_position.fee_growth_inside_1_last_x64;
+
+    personal_position.update_rewards(protocol_position.reward_growth_inside, true)
+}
+
+/// Settle the fees and rewards a filled limit order earned up to its fill, the fill account of
+/// its batch must be in the remaining accounts. The first settle of the batch writes the fill kept
+/// in the tick array of the order to it.
+pub fn update_filled_limit_order_fees_and_rewards(
+    personal_position: &mut PersonalPositionState,
+    tick_array_info: &AccountInfo,
+    remaining_accounts: &[AccountInfo],
+) -> Result<()> {
+    let fill_state = LimitOrderFillState::load(
+        remaining_accounts,
+        tick_array_info,
+        personal_position.pool_id,
+        personal_position.limit_order_fill_tick,
+        personal_position.limit_order_fill_count,
+    )?;
+    personal_position.token_fees_owed_0 = calculate_latest_token_fees(
+        personal_position.token_fees_owed_0,
+        personal_position.fee_growth_inside_0_last_x64,
+        fill_state.fee_growth_inside_0_x64,
+        personal_position.liquidity,
+    );
+    personal_position.token_fees_owed_1 = calculate_latest_token_fees(
+        personal_position.token_fees_owed_1,
+        personal_position.fee_growth_inside_1_last_x64,
+        fill_state.fee_growth_inside_1_x64,
+        personal_position.liquidity,
+    );
+    personal_position.fee_growth_inside_0_last_x64 = fill_state.fee_growth_inside_0_x64;
+    personal_position.fee_growth_inside_1_last_x64 = fill_state.fee_growth_inside_1_x64;
+
+    personal_position.update_rewards(fill_state.reward_growths_inside_x64, true)
+}
+
+#[cfg(test)]
+mod collect_fees_test {
+    use super::*;
+    use crate::libraries::{fixed_point_64, tick_math};
+    use crate::states::oracle::block_timestamp_mock;
+    use crate::states::pool_test::build_pool;
+    use crate::states::tick_array_test::build_tick;
+
+    #[test]
+    fn settle_position_fees_and_rewards_test() {
+        let liquidity = 1024;
+        let timestamp = block_timestamp_mock();
+        let pool_state_ref = build_pool(1, 1, tick_math::get_sqrt_price_at_tick(1).unwrap(), 0);
+        let pool_state = &mut pool_state_ref.borrow_mut();
+        pool_state.fee_growth_global_0_x64 = 0;
+        pool_state.fee_growth_global_1_x64 = 0;
+        // one reward token per second
+        pool_state.reward_infos[0] = RewardInfo {
+            token_mint: Pubkey::new_unique(),
+            open_time: timestamp - 1,
+            end_time: timestamp + 1000,
+            last_update_time: timestamp,
+            emissions_per_second_x64: fixed_point_64::Q64,
+            ..Default::default()
+        };
+        let tick_lower_state = &mut build_tick(0, 0, 0).take();
+        let tick_upper_state = &mut build_tick(2, 0, 0).take();
+        let protocol_position = &mut ProtocolPositionState::default();
+        modify_position(
+            liquidity as i128,
+            pool_state,
+            protocol_position,
+            tick_lower_state,
+            tick_upper_state,
+            timestamp,
+        )
+        .unwrap();
+        let mut personal_position = PersonalPositionState {
+            liquidity,
+            fee_growth_inside_0_last_x64: protocol_position.fee_growth_inside_0_last_x64,
+            fee_growth_inside_1_last_x64: protocol_position.fee_growth_inside_1_last_x64,
+            ..Default::default()
+        };
+        personal_position
+            .update_rewards(protocol_position.reward_growth_inside, false)
+            .unwrap();
+
+        // swaps pay 1000 of token_0 and 500 of token_1 as fees to the range
+        pool_state.fee_growth_global_0_x64 = (1000 << 64) / liquidity;
+        pool_state.fee_growth_global_1_x64 = (500 << 64) / liquidity;
+        settle_position_fees_and_rewards(
+            pool_state,
+            protocol_position,
+            &mut personal_position,
+            tick_lower_state,
+            tick_upper_state,
+            timestamp + 100,
+        )
+        .unwrap();
+        assert_eq!(personal_position.token_fees_owed_0, 1000);
+        assert_eq!(personal_position.token_fees_owed_1, 500);
+        assert_eq!(personal_position.reward_infos[0].reward_amount_owed, 100);
+        assert_eq!(personal_position.reward_infos[1].reward_amount_owed, 0);
+
+        // settling again at the same time earns nothing more
+        settle_position_fees_and_rewards(
+            pool_state,
+            protocol_position,
+            &mut personal_position,
+            tick_lower_state,
+            tick_upper_state,
+            timestamp + 100,
+        )
+        .unwrap();
+        assert_eq!(personal_position.token_fees_owed_0, 1000);
+        assert_eq!(personal_position.token_fees_owed_1, 500);
+        assert_eq!(personal_position.reward_infos[0].reward_amount_owed, 100);
+        // and the ticks are not modified
+        assert_eq!({ tick_lower_state.liquidity_gross }, liquidity);
+        assert_eq!({ tick_upper_state.liquidity_gross }, liquidity);
+    }
+}
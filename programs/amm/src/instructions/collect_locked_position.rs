@@ -0,0 +1,151 @@
+use super::collect_position_fees;
+use super::decrease_liquidity::collect_rewards;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct CollectLockedPosition<'info> {
+    /// The receipt NFT holder
+    pub receipt_owner: Signer<'info>,
+
+    /// The token account for the receipt NFT
+    #[account(
+        constraint = receipt_nft_account.mint == locked_position.receipt_nft_mint,
+        constraint = receipt_nft_account.amount == 1,
+        token::authority = receipt_owner,
+    )]
+    pub receipt_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The custody of the locked position
+    pub locked_position: Box<Account<'info, LockedPositionState>>,
+
+    /// Collect fees and rewards for this position
+    #[account(
+        mut,
+        address = locked_position.personal_position,
+        constraint = personal_position.pool_id == pool_state.key()
+    )]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Token_0 vault
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_1 vault
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Stores init state for the lower tick
+    #[account(constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick
+    #[account(constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+
+    /// The destination token account for receive amount_0
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The destination token account for receive amount_1
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+    // remaining accounts, for each initialized reward:
+    // the reward token vault, the recipient token account and the reward vault mint
+}
+
+pub fn collect_locked_position<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CollectLockedPosition<'info>>,
+    amount_0_requested: u64,
+    amount_1_requested: u64,
+) -> Result<()> {
+    collect_position_fees(
+        &ctx.accounts.pool_state,
+        &mut ctx.accounts.protocol_position,
+        &mut ctx.accounts.personal_position,
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.recipient_token_account_0.to_account_info(),
+        &ctx.accounts.recipient_token_account_1.to_account_info(),
+        &ctx.accounts.token_program,
+        &ctx.accounts.token_program_2022,
+        ctx.accounts.vault_0_mint.clone(),
+        ctx.accounts.vault_1_mint.clone(),
+        amount_0_requested,
+        amount_1_requested,
+        &[],
+    )?;
+
+    // the rewards are settled with the fees above
+    let remaining_accounts: Vec<&AccountInfo> = ctx.remaining_accounts.iter().collect();
+    let reward_amounts = collect_rewards(
+        &ctx.accounts.pool_state,
+        remaining_accounts.as_slice(),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        &mut ctx.accounts.personal_position,
+        true,
+        None,
+    )?;
+    for (reward_index, amount) in reward_amounts.into_iter().enumerate() {
+        if amount > 0 {
+            emit!(CollectPersonalRewardEvent {
+                position_nft_mint: ctx.accounts.personal_position.nft_mint,
+                reward_index: reward_index as u8,
+                recipient_token_account: ctx.remaining_accounts[reward_index * 3 + 1].key(),
+                amount,
+            });
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use super::update_position_fees_and_rewards;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::transfer_from_pool_vault_to_user;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct CollectPositionRewards<'info> {
+    /// The position owner or delegated authority
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint,
+        constraint = nft_account.amount == 1,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Collect rewards for this position
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Stores init state for the lower tick
+    #[account(constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick
+    #[account(constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+
+    /// The reward vault to collect from
+    #[account(mut)]
+    pub reward_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The destination token account for receive reward
+    #[account(
+        mut,
+        token::mint = reward_token_vault.mint
+    )]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of reward token vault
+    #[account(
+        address = reward_token_vault.mint
+    )]
+    pub reward_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+}
+
+pub fn collect_position_rewards<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CollectPositionRewards<'info>>,
+    reward_index: u8,
+    amount_requested: u64,
+) -> Result<()> {
+    let recipient_owner = ctx.accounts.personal_position.check_authority(
+        ctx.accounts.nft_owner.key(),
+        ctx.accounts.nft_account.owner,
+        PositionPermissionBitIndex::Collect,
+    )?;
+    check_recipient_owner(recipient_owner, ctx.accounts.recipient_token_account.owner)?;
+    let index = reward_index as usize;
+    require_gt!(REWARD_NUM, index, ErrorCode::InvalidRewardIndex);
+    let personal_position = &mut ctx.accounts.personal_position;
+    let amount = {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        if !pool_state.get_status_by_bit(PoolStatusBitIndex::CollectReward)? {
+            return err!(ErrorCode::NotApproved);
+        }
+        require!(
+            pool_state.reward_infos[index].initialized(),
+            ErrorCode::InvalidRewardIndex
+        );
+        require_keys_eq!(
+            ctx.accounts.reward_token_vault.key(),
+            pool_state.reward_infos[index].token_vault
+        );
+        update_position_fees_and_rewards(
+            &mut pool_state,
+            &mut ctx.accounts.protocol_position,
+            personal_position,
+            &ctx.accounts.tick_array_lower,
+            &ctx.accounts.tick_array_upper,
+            ctx.remaining_accounts,
+        )?;
+
+        let reward_amount_owed = personal_position.reward_infos[index].reward_amount_owed;
+        pool_state.check_unclaimed_reward(index, reward_amount_owed)?;
+        let amount = amount_requested
+            .min(reward_amount_owed)
+            .min(ctx.accounts.reward_token_vault.amount);
+
+        personal_position.reward_infos[index].reward_amount_owed =
+            reward_amount_owed.checked_sub(amount).unwrap();
+        pool_state.add_reward_clamed(index, amount)?;
+        amount
+    };
+
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.reward_token_vault.to_account_info(),
+        &ctx.accounts.recipient_token_account.to_account_info(),
+        Some(ctx.accounts.reward_vault_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount,
+    )?;
+
+    emit!(CollectPersonalRewardEvent {
+        position_nft_mint: personal_position.nft_mint,
+        reward_index,
+        recipient_token_account: ctx.accounts.recipient_token_account.key(),
+        amount,
+    });
+
+    Ok(())
+}
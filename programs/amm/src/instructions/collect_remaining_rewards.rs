@@ -76,6 +76,9 @@ fn get_remaining_reward_amount(
 ) -> Result<u64> {
     let current_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
     let mut pool_state = pool_state_loader.load_mut()?;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::CollectReward)? {
+        return err!(ErrorCode::NotApproved);
+    }
     pool_state.update_reward_infos(current_timestamp)?;
 
     let reward_info = pool_state.reward_infos[reward_index as usize];
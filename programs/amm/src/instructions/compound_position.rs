@@ -0,0 +1,364 @@
+use super::mint_liquidity;
+use super::update_position_fees_and_rewards;
+use crate::error::ErrorCode;
+use crate::libraries::{liquidity_math, tick_math};
+use crate::states::*;
+use crate::util::AccountLoad;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use std::cell::RefMut;
+
+#[derive(Accounts)]
+pub struct CompoundPosition<'info> {
+    /// The position NFT holder, or a delegate approved to compound
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint,
+        constraint = nft_account.amount == 1,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Compound the fees of this position
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Stores init state for the lower tick
+    #[account(mut, constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick
+    #[account(mut, constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+    // remaining account
+    // #[account(
+    //     seeds = [
+    //         POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
+    //         pool_state.key().as_ref(),
+    //     ],
+    //     bump
+    // )]
+    // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+}
+
+pub fn compound_position<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CompoundPosition<'info>>,
+) -> Result<()> {
+    ctx.accounts.personal_position.check_authority(
+        ctx.accounts.nft_owner.key(),
+        ctx.accounts.nft_account.owner,
+        PositionPermissionBitIndex::Compound,
+    )?;
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)?
+        || !pool_state.get_status_by_bit(PoolStatusBitIndex::CollectFee)?
+    {
+        return err!(ErrorCode::NotApproved);
+    }
+    let personal_position = &mut ctx.accounts.personal_position;
+    let protocol_position = &mut ctx.accounts.protocol_position;
+    let tick_lower = personal_position.tick_lower_index;
+    let tick_upper = personal_position.tick_upper_index;
+    if personal_position.is_limit_order() {
+        let tick_array = ctx.accounts.tick_array_lower.load()?;
+        let fill_tick_state = tick_array.get_tick_state(
+            personal_position.limit_order_fill_tick,
+            pool_state.tick_spacing,
+        )?;
+        require!(
+            !personal_position.is_limit_order_filled(fill_tick_state),
+            ErrorCode::LimitOrderFilled
+        );
+    }
+
+    let use_tickarray_bitmap_extension =
+        pool_state.is_overflow_default_tickarray_bitmap(vec![tick_lower, tick_upper]);
+    let (liquidity, amount_0, amount_1) = compound_fees(
+        pool_state,
+        protocol_position,
+        personal_position,
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+        if use_tickarray_bitmap_extension {
+            require_keys_eq!(
+                ctx.remaining_accounts[0].key(),
+                TickArrayBitmapExtension::key(ctx.accounts.pool_state.key())
+            );
+            Some(&ctx.remaining_accounts[0])
+        } else {
+            None
+        },
+    )?;
+    if liquidity == 0 {
+        return Ok(());
+    }
+
+    if personal_position.is_limit_order() {
+        let mut tick_array = ctx.accounts.tick_array_lower.load_mut()?;
+        let fill_tick_state = tick_array.get_tick_state_mut(
+            personal_position.limit_order_fill_tick,
+            pool_state.tick_spacing,
+        )?;
+        fill_tick_state.order_liquidity = fill_tick_state
+            .order_liquidity
+            .checked_add(liquidity)
+            .unwrap();
+    }
+
+    emit!(IncreaseLiquidityEvent {
+        position_nft_mint: personal_position.nft_mint,
+        liquidity,
+        amount_0,
+        amount_1,
+        amount_0_transfer_fee: 0,
+        amount_1_transfer_fee: 0
+    });
+
+    Ok(())
+}
+
+/// Settle the fees earned by the position and reinvest the fees owed into its range.
+/// Returns the liquidity added and the amounts of the fees reinvested
+pub fn compound_fees<'c: 'info, 'info>(
+    pool_state: &mut RefMut<PoolState>,
+    protocol_position: &mut ProtocolPositionState,
+    personal_position: &mut PersonalPositionState,
+    tick_array_lower_loader: &AccountLoader<'info, TickArrayState>,
+    tick_array_upper_loader: &AccountLoader<'info, TickArrayState>,
+    tick_array_bitmap_extension: Option<&'c AccountInfo<'info>>,
+) -> Result<(u128, u64, u64)> {
+    update_position_fees_and_rewards(
+        pool_state,
+        protocol_position,
+        personal_position,
+        tick_array_lower_loader,
+        tick_array_upper_loader,
+        &[],
+    )?;
+
+    let tick_lower = personal_position.tick_lower_index;
+    let tick_upper = personal_position.tick_upper_index;
+    // the fees are already in the vaults, only the liquidity they support is minted,
+    // and the dust is left as fees owed
+    let liquidity = liquidity_math::get_liquidity_from_amounts(
+        pool_state.sqrt_price_x64,
+        tick_math::get_sqrt_price_at_tick(tick_lower)?,
+        tick_math::get_sqrt_price_at_tick(tick_upper)?,
+        personal_position.token_fees_owed_0,
+        personal_position.token_fees_owed_1,
+    );
+    if liquidity == 0 {
+        return Ok((0, 0, 0));
+    }
+
+    let (amount_0, amount_1) = mint_liquidity(
+        pool_state,
+        &AccountLoad::<TickArrayState>::try_from(&tick_array_lower_loader.to_account_info())?,
+        &AccountLoad::<TickArrayState>::try_from(&tick_array_upper_loader.to_account_info())?,
+        protocol_position,
+        tick_array_bitmap_extension,
+        liquidity,
+        tick_lower,
+        tick_upper,
+    )?;
+    require_gte!(
+        personal_position.token_fees_owed_0,
+        amount_0,
+        ErrorCode::PriceSlippageCheck
+    );
+    require_gte!(
+        personal_position.token_fees_owed_1,
+        amount_1,
+        ErrorCode::PriceSlippageCheck
+    );
+
+    // the reinvested fees are claimed from the pool fees and become position liquidity
+    personal_position.token_fees_owed_0 -= amount_0;
+    personal_position.token_fees_owed_1 -= amount_1;
+    pool_state.total_fees_claimed_token_0 = pool_state
+        .total_fees_claimed_token_0
+        .checked_add(amount_0)
+        .unwrap();
+    pool_state.total_fees_claimed_token_1 = pool_state
+        .total_fees_claimed_token_1
+        .checked_add(amount_1)
+        .unwrap();
+
+    personal_position.fee_growth_inside_0_last_x64 = protocol_position.fee_growth_inside_0_last_x64;
+    personal_position.fee_growth_inside_1_last_x64 = protocol_position.fee_growth_inside_1_last_x64;
+    personal_position.liquidity = personal_position.liquidity.checked_add(liquidity).unwrap();
+
+    Ok((liquidity, amount_0, amount_1))
+}
+
+#[cfg(test)]
+mod compound_position_test {
+    use super::*;
+    use crate::states::pool_test::build_pool;
+    use crate::states::tick_array_test::{
+        build_tick_array_account_info, build_tick_array_with_tick_states,
+    };
+    use crate::util::set_clock_sysvar_stub;
+    use std::cell::RefCell;
+
+    const TICK_SPACING: u16 = 10;
+    const TICK_LOWER: i32 = 100;
+    const TICK_UPPER: i32 = 500;
+
+    /// Opens a position of `liquidity` in `[TICK_LOWER, TICK_UPPER]` of a pool at `tick_current`,
+    /// which is owed `fees_owed` of both tokens
+    fn build_position(
+        tick_current: i32,
+        liquidity: u128,
+        fees_owed: u64,
+    ) -> (
+        RefCell<PoolState>,
+        ProtocolPositionState,
+        PersonalPositionState,
+        AccountLoader<'static, TickArrayState>,
+    ) {
+        set_clock_sysvar_stub();
+        let pool_state_ref = build_pool(
+            tick_current,
+            TICK_SPACING,
+            tick_math::get_sqrt_price_at_tick(tick_current).unwrap(),
+            0,
+        );
+        let tick_array_info = {
+            let mut pool_state = pool_state_ref.borrow_mut();
+            pool_state.fee_growth_global_0_x64 = 0;
+            pool_state.fee_growth_global_1_x64 = 0;
+            pool_state.total_fees_token_0 = fees_owed;
+            pool_state.total_fees_token_1 = fees_owed;
+            build_tick_array_account_info(
+                &build_tick_array_with_tick_states(
+                    pool_state.key(),
+                    TickArrayState::get_array_start_index(TICK_LOWER, TICK_SPACING),
+                    TICK_SPACING,
+                    vec![],
+                )
+                .borrow(),
+            )
+        };
+        let tick_array = AccountLoad::<TickArrayState>::try_from(tick_array_info).unwrap();
+        let mut protocol_position = ProtocolPositionState::default();
+        mint_liquidity(
+            &mut pool_state_ref.borrow_mut(),
+            &tick_array,
+            &tick_array,
+            &mut protocol_position,
+            None,
+            liquidity,
+            TICK_LOWER,
+            TICK_UPPER,
+        )
+        .unwrap();
+        let personal_position = PersonalPositionState {
+            tick_lower_index: TICK_LOWER,
+            tick_upper_index: TICK_UPPER,
+            liquidity,
+            fee_growth_inside_0_last_x64: protocol_position.fee_growth_inside_0_last_x64,
+            fee_growth_inside_1_last_x64: protocol_position.fee_growth_inside_1_last_x64,
+            token_fees_owed_0: fees_owed,
+            token_fees_owed_1: fees_owed,
+            ..Default::default()
+        };
+        (
+            pool_state_ref,
+            protocol_position,
+            personal_position,
+            AccountLoader::try_from(tick_array_info).unwrap(),
+        )
+    }
+
+    #[test]
+    fn compound_fees_in_range_test() {
+        let (pool_state_ref, mut protocol_position, mut personal_position, tick_array) =
+            build_position(300, 1_000_000, 10_000);
+        let pool_state = &mut pool_state_ref.borrow_mut();
+
+        let (liquidity, amount_0, amount_1) = compound_fees(
+            pool_state,
+            &mut protocol_position,
+            &mut personal_position,
+            &tick_array,
+            &tick_array,
+            None,
+        )
+        .unwrap();
+        assert!(liquidity > 0);
+        assert!(amount_0 > 0 && amount_0 <= 10_000);
+        assert!(amount_1 > 0 && amount_1 <= 10_000);
+        // the liquidity is bound by one of the tokens, the dust of the other stays owed
+        assert!(amount_0 > 9_990 || amount_1 > 9_990);
+        assert_eq!(personal_position.token_fees_owed_0, 10_000 - amount_0);
+        assert_eq!(personal_position.token_fees_owed_1, 10_000 - amount_1);
+        assert_eq!(personal_position.liquidity, 1_000_000 + liquidity);
+        assert_eq!(protocol_position.liquidity, 1_000_000 + liquidity);
+        assert_eq!({ pool_state.liquidity }, 1_000_000 + liquidity);
+        assert_eq!({ pool_state.total_fees_claimed_token_0 }, amount_0);
+        assert_eq!({ pool_state.total_fees_claimed_token_1 }, amount_1);
+    }
+
+    #[test]
+    fn compound_fees_above_range_test() {
+        let (pool_state_ref, mut protocol_position, mut personal_position, tick_array) =
+            build_position(600, 1_000_000, 10_000);
+        let pool_state = &mut pool_state_ref.borrow_mut();
+
+        let (liquidity, amount_0, amount_1) = compound_fees(
+            pool_state,
+            &mut protocol_position,
+            &mut personal_position,
+            &tick_array,
+            &tick_array,
+            None,
+        )
+        .unwrap();
+        // only token_1 can be added above the range, token_0 stays owed
+        assert!(liquidity > 0);
+        assert_eq!(amount_0, 0);
+        assert!(amount_1 > 0 && amount_1 <= 10_000);
+        assert_eq!(personal_position.token_fees_owed_0, 10_000);
+        assert_eq!(personal_position.token_fees_owed_1, 10_000 - amount_1);
+        assert_eq!(personal_position.liquidity, 1_000_000 + liquidity);
+        // the active liquidity is not changed out of range
+        assert_eq!({ pool_state.liquidity }, 0);
+    }
+
+    #[test]
+    fn compound_no_fees_test() {
+        let (pool_state_ref, mut protocol_position, mut personal_position, tick_array) =
+            build_position(300, 1_000_000, 0);
+        let pool_state = &mut pool_state_ref.borrow_mut();
+
+        let result = compound_fees(
+            pool_state,
+            &mut protocol_position,
+            &mut personal_position,
+            &tick_array,
+            &tick_array,
+            None,
+        )
+        .unwrap();
+        assert_eq!(result, (0, 0, 0));
+        assert_eq!(personal_position.liquidity, 1_000_000);
+    }
+}
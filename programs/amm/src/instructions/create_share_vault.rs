@@ -0,0 +1,202 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::AccountLoad;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token};
+
+#[derive(Accounts)]
+#[instruction(tick_lower_index: i32, tick_upper_index: i32, tick_array_lower_start_index: i32, tick_array_upper_start_index: i32)]
+pub struct CreateShareVault<'info> {
+    /// Pays to create the vault
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The pool of the vault
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The share vault of the range
+    #[account(
+        init,
+        seeds = [
+            SHARE_VAULT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_lower_index.to_be_bytes(),
+            &tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        payer = payer,
+        space = ShareVaultState::LEN
+    )]
+    pub share_vault: Box<Account<'info, ShareVaultState>>,
+
+    /// The share token mint, its decimals are the average of the pool token decimals as liquidity is
+    #[account(
+        init,
+        seeds = [SHARE_VAULT_MINT_SEED.as_bytes(), share_vault.key().as_ref()],
+        bump,
+        mint::decimals = get_share_mint_decimals(&*pool_state.load()?),
+        mint::authority = share_vault,
+        payer = payer,
+    )]
+    pub share_mint: Box<Account<'info, Mint>>,
+
+    /// The position owned by the vault
+    #[account(
+        init,
+        seeds = [POSITION_SEED.as_bytes(), share_vault.key().as_ref()],
+        bump,
+        payer = payer,
+        space = PersonalPositionState::LEN
+    )]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// Store the information of market marking in range
+    #[account(
+        init_if_needed,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_lower_index.to_be_bytes(),
+            &tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        payer = payer,
+        space = ProtocolPositionState::LEN
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// CHECK: Account to store data for the position's lower tick
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_array_lower_start_index.to_be_bytes(),
+        ],
+        bump,
+    )]
+    pub tick_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Account to store data for the position's upper tick
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_array_upper_start_index.to_be_bytes(),
+        ],
+        bump,
+    )]
+    pub tick_array_upper: UncheckedAccount<'info>,
+
+    /// Sysvar for token mint creation
+    pub rent: Sysvar<'info, Rent>,
+
+    /// Program to create the vault and position state accounts
+    pub system_program: Program<'info, System>,
+
+    /// Program to create the share mint
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn create_share_vault<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CreateShareVault<'info>>,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    tick_array_lower_start_index: i32,
+    tick_array_upper_start_index: i32,
+) -> Result<()> {
+    let pool_state = ctx.accounts.pool_state.load()?;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)? {
+        return err!(ErrorCode::NotApproved);
+    }
+    // the rewards owed to the vault position could not be shared fairly between depositors,
+    // a reward initialized later freezes the vault
+    require!(
+        !pool_state.has_rewards(),
+        ErrorCode::ShareVaultRewardsNotSupported
+    );
+    check_ticks_order(tick_lower_index, tick_upper_index)?;
+    check_tick_array_start_index(
+        tick_array_lower_start_index,
+        tick_lower_index,
+        pool_state.tick_spacing,
+    )?;
+    check_tick_array_start_index(
+        tick_array_upper_start_index,
+        tick_upper_index,
+        pool_state.tick_spacing,
+    )?;
+
+    // the tick arrays are created once here, deposits only load them
+    let tick_array_lower_loader = TickArrayState::get_or_create_tick_array(
+        ctx.accounts.payer.to_account_info(),
+        ctx.accounts.tick_array_lower.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.pool_state,
+        tick_array_lower_start_index,
+        pool_state.tick_spacing,
+    )?;
+    let tick_array_upper_loader = if tick_array_lower_start_index == tick_array_upper_start_index {
+        AccountLoad::<TickArrayState>::try_from(&ctx.accounts.tick_array_upper.to_account_info())?
+    } else {
+        TickArrayState::get_or_create_tick_array(
+            ctx.accounts.payer.to_account_info(),
+            ctx.accounts.tick_array_upper.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.pool_state,
+            tick_array_upper_start_index,
+            pool_state.tick_spacing,
+        )?
+    };
+
+    let protocol_position = &mut ctx.accounts.protocol_position;
+    if protocol_position.pool_id == Pubkey::default() {
+        protocol_position.bump = ctx.bumps.protocol_position;
+        protocol_position.pool_id = ctx.accounts.pool_state.key();
+        protocol_position.tick_lower_index = tick_lower_index;
+        protocol_position.tick_upper_index = tick_upper_index;
+        tick_array_lower_loader
+            .load_mut()?
+            .get_tick_state_mut(tick_lower_index, pool_state.tick_spacing)?
+            .tick = tick_lower_index;
+        tick_array_upper_loader
+            .load_mut()?
+            .get_tick_state_mut(tick_upper_index, pool_state.tick_spacing)?
+            .tick = tick_upper_index;
+    }
+
+    let share_vault = &mut ctx.accounts.share_vault;
+    share_vault.bump = [ctx.bumps.share_vault];
+    share_vault.pool_id = ctx.accounts.pool_state.key();
+    share_vault.tick_lower_index = tick_lower_index;
+    share_vault.tick_upper_index = tick_upper_index;
+    share_vault.share_mint = ctx.accounts.share_mint.key();
+    share_vault.personal_position = ctx.accounts.personal_position.key();
+
+    // the vault key stands in for the position NFT mint, no token account can ever hold it
+    let personal_position = &mut ctx.accounts.personal_position;
+    personal_position.bump = [ctx.bumps.personal_position];
+    personal_position.nft_mint = share_vault.key();
+    personal_position.pool_id = ctx.accounts.pool_state.key();
+    personal_position.tick_lower_index = tick_lower_index;
+    personal_position.tick_upper_index = tick_upper_index;
+    personal_position.fee_growth_inside_0_last_x64 = protocol_position.fee_growth_inside_0_last_x64;
+    personal_position.fee_growth_inside_1_last_x64 = protocol_position.fee_growth_inside_1_last_x64;
+    personal_position.update_rewards(protocol_position.reward_growth_inside, false)?;
+
+    emit!(CreateShareVaultEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        share_vault: share_vault.key(),
+        share_mint: share_vault.share_mint,
+        tick_lower_index,
+        tick_upper_index,
+    });
+
+    Ok(())
+}
+
+/// The decimals of the share mint, the average of the pool token decimals
+pub fn get_share_mint_decimals(pool_state: &PoolState) -> u8 {
+    ((u16::from(pool_state.mint_decimals_0) + u16::from(pool_state.mint_decimals_1)) / 2) as u8
+}
@@ -1,5 +1,7 @@
 use super::calculate_latest_token_fees;
 use super::modify_position;
+use super::settle_filled_limit_order;
+use super::update_filled_limit_order_fees_and_rewards;
 use crate::error::ErrorCode;
 use crate::states::*;
 use crate::util::{self, transfer_from_pool_vault_to_user};
@@ -21,7 +23,6 @@ pub struct DecreaseLiquidity<'info> {
     #[account(
         constraint = nft_account.mint == personal_position.nft_mint,
         constraint = nft_account.amount == 1,
-        token::authority = nft_owner
     )]
     pub nft_account: Box<Account<'info, TokenAccount>>,
 
@@ -100,6 +101,19 @@ pub fn decrease_liquidity_v1<'a, 'b, 'c: 'info, 'info>(
     amount_0_min: u64,
     amount_1_min: u64,
 ) -> Result<()> {
+    let recipient_owner = ctx.accounts.personal_position.check_authority(
+        ctx.accounts.nft_owner.key(),
+        ctx.accounts.nft_account.owner,
+        PositionPermissionBitIndex::DecreaseLiquidity,
+    )?;
+    check_recipient_owner(
+        recipient_owner,
+        ctx.accounts.recipient_token_account_0.owner,
+    )?;
+    check_recipient_owner(
+        recipient_owner,
+        ctx.accounts.recipient_token_account_1.owner,
+    )?;
     decrease_liquidity(
         &ctx.accounts.pool_state,
         &mut ctx.accounts.protocol_position,
@@ -116,6 +130,7 @@ pub fn decrease_liquidity_v1<'a, 'b, 'c: 'info, 'info>(
         None,
         None,
         &ctx.remaining_accounts,
+        recipient_owner,
         liquidity,
         amount_0_min,
         amount_1_min,
@@ -138,6 +153,7 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
     vault_0_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
     vault_1_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
     remaining_accounts: &'c [AccountInfo<'info>],
+    recipient_owner: Option<Pubkey>,
     liquidity: u128,
     amount_0_min: u64,
     amount_1_min: u64,
@@ -155,9 +171,9 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
     let remaining_collect_accounts = &mut Vec::new();
     {
         let pool_state = pool_state_loader.load()?;
-        if !pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity)
-            && !pool_state.get_status_by_bit(PoolStatusBitIndex::CollectFee)
-            && !pool_state.get_status_by_bit(PoolStatusBitIndex::CollectReward)
+        if !pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity)?
+            && !pool_state.get_status_by_bit(PoolStatusBitIndex::CollectFee)?
+            && !pool_state.get_status_by_bit(PoolStatusBitIndex::CollectReward)?
         {
             return err!(ErrorCode::NotApproved);
         }
@@ -178,6 +194,9 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
                 tickarray_bitmap_extension = Some(account_info);
                 continue;
             }
+            if LimitOrderFillState::is_limit_order_fill_account(account_info) {
+                continue;
+            }
             remaining_collect_accounts.push(account_info);
         }
         if use_tickarray_bitmap_extension {
@@ -196,6 +215,7 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
             tick_array_lower_loader,
             tick_array_upper_loader,
             tickarray_bitmap_extension,
+            remaining_accounts,
             liquidity,
         )?;
 
@@ -283,6 +303,7 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
         } else {
             true
         },
+        recipient_owner,
     )?;
     emit!(DecreaseLiquidityEvent {
         position_nft_mint: personal_position.nft_mint,
@@ -306,12 +327,24 @@ pub fn decrease_liquidity_and_update_position<'a, 'b, 'c: 'info, 'info>(
     tick_array_lower: &AccountLoader<'info, TickArrayState>,
     tick_array_upper: &AccountLoader<'info, TickArrayState>,
     tick_array_bitmap_extension: Option<&'c AccountInfo<'info>>,
+    remaining_accounts: &[AccountInfo],
     liquidity: u128,
 ) -> Result<(u64, u64, u64, u64)> {
+    if liquidity > 0 {
+        require!(
+            !personal_position.is_locked(Clock::get()?.unix_timestamp as u64),
+            ErrorCode::PositionLocked
+        );
+    }
     let mut pool_state = pool_state_loader.load_mut()?;
     let mut decrease_amount_0 = 0;
     let mut decrease_amount_1 = 0;
-    if pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity) {
+    if pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity)? {
+        let limit_order_filled = personal_position.is_limit_order()
+            && personal_position.is_limit_order_filled(tick_array_lower.load()?.get_tick_state(
+                personal_position.limit_order_fill_tick,
+                pool_state.tick_spacing,
+            )?);
         (decrease_amount_0, decrease_amount_1) = burn_liquidity(
             &mut pool_state,
             tick_array_lower,
@@ -319,35 +352,61 @@ pub fn decrease_liquidity_and_update_position<'a, 'b, 'c: 'info, 'info>(
             protocol_position,
             tick_array_bitmap_extension,
             liquidity,
+            if limit_order_filled {
+                Some(personal_position.limit_order_fill_tick)
+            } else {
+                None
+            },
         )?;
+        if personal_position.is_limit_order() && !limit_order_filled {
+            // cancel the pending order
+            let mut tick_array = tick_array_lower.load_mut()?;
+            let fill_tick_state = tick_array.get_tick_state_mut(
+                personal_position.limit_order_fill_tick,
+                pool_state.tick_spacing,
+            )?;
+            fill_tick_state.order_liquidity = fill_tick_state
+                .order_liquidity
+                .checked_sub(liquidity)
+                .unwrap();
+        }
 
-        personal_position.token_fees_owed_0 = calculate_latest_token_fees(
-            personal_position.token_fees_owed_0,
-            personal_position.fee_growth_inside_0_last_x64,
-            protocol_position.fee_growth_inside_0_last_x64,
-            personal_position.liquidity,
-        );
+        // a filled limit order is settled at its price range only, it earns up to its fill
+        if limit_order_filled {
+            update_filled_limit_order_fees_and_rewards(
+                personal_position,
+                tick_array_lower.as_ref(),
+                remaining_accounts,
+            )?;
+        } else {
+            personal_position.token_fees_owed_0 = calculate_latest_token_fees(
+                personal_position.token_fees_owed_0,
+                personal_position.fee_growth_inside_0_last_x64,
+                protocol_position.fee_growth_inside_0_last_x64,
+                personal_position.liquidity,
+            );
 
-        personal_position.token_fees_owed_1 = calculate_latest_token_fees(
-            personal_position.token_fees_owed_1,
-            personal_position.fee_growth_inside_1_last_x64,
-            protocol_position.fee_growth_inside_1_last_x64,
-            personal_position.liquidity,
-        );
+            personal_position.token_fees_owed_1 = calculate_latest_token_fees(
+                personal_position.token_fees_owed_1,
+                personal_position.fee_growth_inside_1_last_x64,
+                protocol_position.fee_growth_inside_1_last_x64,
+                personal_position.liquidity,
+            );
 
-        personal_position.fee_growth_inside_0_last_x64 =
-            protocol_position.fee_growth_inside_0_last_x64;
-        personal_position.fee_growth_inside_1_last_x64 =
-            protocol_position.fee_growth_inside_1_last_x64;
+            personal_position.fee_growth_inside_0_last_x64 =
+                protocol_position.fee_growth_inside_0_last_x64;
+            personal_position.fee_growth_inside_1_last_x64 =
+                protocol_position.fee_growth_inside_1_last_x64;
 
-        // update rewards, must update before decrease liquidity
-        personal_position.update_rewards(protocol_position.reward_growth_inside, true)?;
+            // update rewards, must update before decrease liquidity
+            personal_position.update_rewards(protocol_position.reward_growth_inside, true)?;
+        }
         personal_position.liquidity = personal_position.liquidity.checked_sub(liquidity).unwrap();
     }
 
     let mut latest_fees_owed_0 = 0;
     let mut latest_fees_owed_1 = 0;
-    if pool_state.get_status_by_bit(PoolStatusBitIndex::CollectFee) {
+    if pool_state.get_status_by_bit(PoolStatusBitIndex::CollectFee)? {
         latest_fees_owed_0 = personal_position.token_fees_owed_0;
         latest_fees_owed_1 = personal_position.token_fees_owed_1;
 
@@ -388,6 +447,7 @@ pub fn burn_liquidity<'c: 'info, 'info>(
     protocol_position: &mut ProtocolPositionState,
     tickarray_bitmap_extension: Option<&'c AccountInfo<'info>>,
     liquidity: u128,
+    filled_limit_order_fill_tick: Option<i32>,
 ) -> Result<(u64, u64)> {
     require_keys_eq!(tick_array_lower_loader.load()?.pool_id, pool_state.key());
     require_keys_eq!(tick_array_upper_loader.load()?.pool_id, pool_state.key());
@@ -400,14 +460,27 @@ pub fn burn_liquidity<'c: 'info, 'info>(
         .load_mut()?
         .get_tick_state_mut(protocol_position.tick_upper_index, pool_state.tick_spacing)?;
     let clock = Clock::get()?;
-    let (amount_0, amount_1, flip_tick_lower, flip_tick_upper) = modify_position(
-        -i128::try_from(liquidity).unwrap(),
-        pool_state,
-        protocol_position,
-        &mut tick_lower_state,
-        &mut tick_upper_state,
-        clock.unix_timestamp as u64,
-    )?;
+    let (amount_0, amount_1, flip_tick_lower, flip_tick_upper) =
+        if let Some(fill_tick) = filled_limit_order_fill_tick {
+            settle_filled_limit_order(
+                liquidity,
+                fill_tick,
+                pool_state,
+                protocol_position,
+                &mut tick_lower_state,
+                &mut tick_upper_state,
+                clock.unix_timestamp as u64,
+            )?
+        } else {
+            modify_position(
+                -i128::try_from(liquidity).unwrap(),
+                pool_state,
+                protocol_position,
+                &mut tick_lower_state,
+                &mut tick_upper_state,
+                clock.unix_timestamp as u64,
+            )?
+        };
 
     // update tick_state
     tick_array_lower_loader.load_mut()?.update_tick_state(
@@ -461,11 +534,12 @@ pub fn collect_rewards<'a, 'b, 'c, 'info>(
     token_program_2022: Option<AccountInfo<'info>>,
     personal_position_state: &mut PersonalPositionState,
     need_reward_mint: bool,
+    recipient_owner: Option<Pubkey>,
 ) -> Result<[u64; REWARD_NUM]> {
     let mut reward_amounts: [u64; REWARD_NUM] = [0, 0, 0];
     if !pool_state_loader
         .load()?
-        .get_status_by_bit(PoolStatusBitIndex::CollectReward)
+        .get_status_by_bit(PoolStatusBitIndex::CollectReward)?
     {
         return Ok(reward_amounts);
     }
@@ -496,6 +570,7 @@ pub fn collect_rewards<'a, 'b, 'c, 'info>(
             )?));
         }
         require_keys_eq!(reward_token_vault.mint, recipient_token_account.mint);
+        check_recipient_owner(recipient_owner, recipient_token_account.owner)?;
         require_keys_eq!(
             reward_token_vault.key(),
             pool_state_loader.load_mut()?.reward_infos[i].token_vault
@@ -594,7 +669,7 @@ pub fn check_unclaimed_fees_and_vault(
     if (unclaimed_fee_token_0 >= token_vault_0_amount && token_vault_0_amount != 0)
         || (unclaimed_fee_token_1 >= token_vault_1_amount && token_vault_1_amount != 0)
     {
-        pool_state.set_status_by_bit(PoolStatusBitIndex::CollectFee, PoolStatusBitFlag::Disable);
+        pool_state.set_status_by_bit(PoolStatusBitIndex::CollectFee, PoolStatusBitFlag::Disable)?;
     }
     Ok(())
 }
@@ -0,0 +1,285 @@
+use super::check_unclaimed_fees_and_vault;
+use super::decrease_liquidity_and_update_position;
+use crate::error::ErrorCode;
+use crate::libraries::tick_math;
+use crate::states::*;
+use crate::swap::swap_internal_with_summary;
+use crate::util::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+use std::collections::VecDeque;
+use std::ops::Deref;
+
+#[derive(Accounts)]
+pub struct DecreaseLiquidityToSingle<'info> {
+    /// The position owner or delegated authority
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint,
+        constraint = nft_account.amount == 1,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Decrease liquidity for this position
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Token_0 vault
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_1 vault
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Stores init state for the lower tick
+    #[account(mut, constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick
+    #[account(mut, constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+
+    /// The destination token account for receive the single token
+    #[account(mut)]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+    // remaining account
+    // #[account(
+    //     seeds = [
+    //         POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
+    //         pool_state.key().as_ref(),
+    //     ],
+    //     bump
+    // )]
+    // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+    // and the tick arrays to swap through
+}
+
+pub fn decrease_liquidity_to_single<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, DecreaseLiquidityToSingle<'info>>,
+    liquidity: u128,
+    zero_for_one: bool,
+    min_amount_out: u64,
+) -> Result<()> {
+    let recipient_owner = ctx.accounts.personal_position.check_authority(
+        ctx.accounts.nft_owner.key(),
+        ctx.accounts.nft_account.owner,
+        PositionPermissionBitIndex::DecreaseLiquidity,
+    )?;
+    check_recipient_owner(recipient_owner, ctx.accounts.recipient_token_account.owner)?;
+    require_gte!(ctx.accounts.personal_position.liquidity, liquidity);
+    let (output_vault, output_vault_mint) = if zero_for_one {
+        (&ctx.accounts.token_vault_1, &ctx.accounts.vault_1_mint)
+    } else {
+        (&ctx.accounts.token_vault_0, &ctx.accounts.vault_0_mint)
+    };
+    require_keys_eq!(ctx.accounts.recipient_token_account.mint, output_vault.mint);
+
+    let mut tickarray_bitmap_extension = None;
+    let mut swap_tick_array_accounts = Vec::new();
+    {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        if !pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity)? {
+            return err!(ErrorCode::NotApproved);
+        }
+        for account_info in ctx.remaining_accounts.iter() {
+            if PoolAllowListState::is_allow_list_account(account_info)
+                || LimitOrderFillState::is_limit_order_fill_account(account_info)
+            {
+                continue;
+            }
+            if account_info
+                .key()
+                .eq(&TickArrayBitmapExtension::key(pool_state.key()))
+            {
+                tickarray_bitmap_extension = Some(account_info);
+                continue;
+            }
+            swap_tick_array_accounts.push(account_info);
+        }
+        if pool_state.is_overflow_default_tickarray_bitmap(vec![
+            ctx.accounts.tick_array_lower.load()?.start_tick_index,
+            ctx.accounts.tick_array_upper.load()?.start_tick_index,
+        ]) {
+            require!(
+                tickarray_bitmap_extension.is_some(),
+                ErrorCode::MissingTickArrayBitmapExtensionAccount
+            );
+        }
+    }
+
+    let (decrease_amount_0, fees_owed_0, decrease_amount_1, fees_owed_1) =
+        decrease_liquidity_and_update_position(
+            &ctx.accounts.pool_state,
+            &mut ctx.accounts.protocol_position,
+            &mut ctx.accounts.personal_position,
+            &ctx.accounts.tick_array_lower,
+            &ctx.accounts.tick_array_upper,
+            tickarray_bitmap_extension,
+            ctx.remaining_accounts,
+            liquidity,
+        )?;
+
+    // swap the unwanted token through the pool, the tokens stay in the vaults
+    let (swap_amount_in, mut amount_out) = if zero_for_one {
+        (
+            decrease_amount_0 + fees_owed_0,
+            decrease_amount_1 + fees_owed_1,
+        )
+    } else {
+        (
+            decrease_amount_1 + fees_owed_1,
+            decrease_amount_0 + fees_owed_0,
+        )
+    };
+    if swap_amount_in > 0 {
+        let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+        pool_state.check_open(Clock::get()?.unix_timestamp as u64)?;
+        // the swap hook is only called by the swap instructions
+        require!(!pool_state.has_swap_hook(), ErrorCode::SwapHookNotSupported);
+        check_pool_access(
+            pool_state,
+            ctx.accounts.pool_state.key(),
+            ctx.remaining_accounts,
+            ctx.accounts.nft_owner.key(),
+            PoolAccessBitIndex::Swap,
+        )?;
+        let mut tick_array_states = VecDeque::new();
+        for account_info in swap_tick_array_accounts.into_iter() {
+            tick_array_states.push_back(AccountLoad::load_data_mut(account_info)?);
+        }
+        let swap_bitmap_extension = match tickarray_bitmap_extension {
+            Some(account_info) => Some(
+                *(AccountLoader::<TickArrayBitmapExtension>::try_from(account_info)?
+                    .load()?
+                    .deref()),
+            ),
+            None => None,
+        };
+        let (mut observation_state, mut observation_extension) =
+            load_observations_mut(&ctx.accounts.observation_state)?;
+        let summary = swap_internal_with_summary(
+            &ctx.accounts.amm_config,
+            pool_state,
+            &mut tick_array_states,
+            &mut observation_state,
+            &mut observation_extension,
+            &swap_bitmap_extension,
+            swap_amount_in,
+            if zero_for_one {
+                tick_math::MIN_SQRT_PRICE_X64 + 1
+            } else {
+                tick_math::MAX_SQRT_PRICE_X64 - 1
+            },
+            None,
+            zero_for_one,
+            true,
+            oracle::block_timestamp(),
+        )?;
+        drop(tick_array_states);
+        record_limit_order_fills(
+            ctx.accounts.pool_state.key(),
+            &summary.limit_order_fills,
+            ctx.remaining_accounts,
+        )?;
+        let (swap_amount_0, swap_amount_1) = (summary.amount_0, summary.amount_1);
+        // the whole unwanted side must be swapped, nothing else is sent back
+        let (amount_in_swapped, amount_out_swapped) = if zero_for_one {
+            (swap_amount_0, swap_amount_1)
+        } else {
+            (swap_amount_1, swap_amount_0)
+        };
+        require_eq!(
+            amount_in_swapped,
+            swap_amount_in,
+            ErrorCode::InsufficientLiquidityForDirection
+        );
+        amount_out = amount_out.checked_add(amount_out_swapped).unwrap();
+    }
+
+    let transfer_fee = get_transfer_fee(output_vault_mint.clone(), amount_out)?;
+    require_gte!(
+        amount_out - transfer_fee,
+        min_amount_out,
+        ErrorCode::TooLittleOutputReceived
+    );
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &output_vault.to_account_info(),
+        &ctx.accounts.recipient_token_account.to_account_info(),
+        Some(output_vault_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_out,
+    )?;
+    check_unclaimed_fees_and_vault(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+    )?;
+
+    emit!(DecreaseLiquidityToSingleEvent {
+        position_nft_mint: ctx.accounts.personal_position.nft_mint,
+        liquidity,
+        decrease_amount_0,
+        decrease_amount_1,
+        fee_amount_0: fees_owed_0,
+        fee_amount_1: fees_owed_1,
+        zero_for_one,
+        swap_amount_in,
+        amount_out,
+        transfer_fee,
+    });
+
+    Ok(())
+}
@@ -14,7 +14,6 @@ pub struct DecreaseLiquidityV2<'info> {
     #[account(
         constraint = nft_account.mint == personal_position.nft_mint,
         constraint = nft_account.amount == 1,
-        token::authority = nft_owner,
     )]
     pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -114,6 +113,19 @@ pub fn decrease_liquidity_v2<'a, 'b, 'c: 'info, 'info>(
     amount_0_min: u64,
     amount_1_min: u64,
 ) -> Result<()> {
+    let recipient_owner = ctx.accounts.personal_position.check_authority(
+        ctx.accounts.nft_owner.key(),
+        ctx.accounts.nft_account.owner,
+        PositionPermissionBitIndex::DecreaseLiquidity,
+    )?;
+    check_recipient_owner(
+        recipient_owner,
+        ctx.accounts.recipient_token_account_0.owner,
+    )?;
+    check_recipient_owner(
+        recipient_owner,
+        ctx.accounts.recipient_token_account_1.owner,
+    )?;
     decrease_liquidity(
         &ctx.accounts.pool_state,
         &mut ctx.accounts.protocol_position,
@@ -130,6 +142,7 @@ pub fn decrease_liquidity_v2<'a, 'b, 'c: 'info, 'info>(
         Some(ctx.accounts.vault_0_mint.clone()),
         Some(ctx.accounts.vault_1_mint.clone()),
         &ctx.remaining_accounts,
+        recipient_owner,
         liquidity,
         amount_0_min,
         amount_1_min,
@@ -0,0 +1,366 @@
+use super::add_liquidity;
+use super::calculate_latest_token_fees;
+use super::compound_fees;
+use crate::error::ErrorCode;
+use crate::libraries::{big_num::U256, full_math::MulDiv};
+use crate::states::*;
+use crate::util::AccountLoad;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token};
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+use std::cell::RefMut;
+
+#[derive(Accounts)]
+pub struct DepositShareVault<'info> {
+    /// Pays the tokens of the liquidity
+    pub owner: Signer<'info>,
+
+    /// The share vault to deposit into
+    #[account(mut, constraint = share_vault.pool_id == pool_state.key())]
+    pub share_vault: Box<Account<'info, ShareVaultState>>,
+
+    /// The share token mint of the vault
+    #[account(mut, address = share_vault.share_mint)]
+    pub share_mint: Box<Account<'info, token::Mint>>,
+
+    /// The token account receiving the minted shares
+    #[account(mut, token::mint = share_mint)]
+    pub share_token_account: Box<Account<'info, token::TokenAccount>>,
+
+    /// The position owned by the vault
+    #[account(mut, address = share_vault.personal_position)]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &share_vault.tick_lower_index.to_be_bytes(),
+            &share_vault.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Stores init state for the lower tick
+    #[account(mut, constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick
+    #[account(mut, constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+
+    /// The payer's token account for token_0
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The payer's token account for token_1
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Program to create mint account and mint tokens
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+    // remaining account
+    // #[account(
+    //     seeds = [
+    //         POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
+    //         pool_state.key().as_ref(),
+    //     ],
+    //     bump
+    // )]
+    // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+}
+
+pub fn deposit_share_vault<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, DepositShareVault<'info>>,
+    liquidity: u128,
+    amount_0_max: u64,
+    amount_1_max: u64,
+) -> Result<()> {
+    let mut liquidity = liquidity;
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)? {
+        return err!(ErrorCode::NotApproved);
+    }
+    ShareVaultState::check_deposit(pool_state)?;
+    check_pool_access(
+        pool_state,
+        ctx.accounts.pool_state.key(),
+        ctx.remaining_accounts,
+        ctx.accounts.owner.key(),
+        PoolAccessBitIndex::Liquidity,
+    )?;
+    let personal_position = &mut ctx.accounts.personal_position;
+    let protocol_position = &mut ctx.accounts.protocol_position;
+    let tick_lower = personal_position.tick_lower_index;
+    let tick_upper = personal_position.tick_upper_index;
+    let tick_array_bitmap_extension =
+        if pool_state.is_overflow_default_tickarray_bitmap(vec![tick_lower, tick_upper]) {
+            require_keys_eq!(
+                ctx.remaining_accounts[0].key(),
+                TickArrayBitmapExtension::key(ctx.accounts.pool_state.key())
+            );
+            Some(&ctx.remaining_accounts[0])
+        } else {
+            None
+        };
+
+    fold_share_vault_fees(
+        pool_state,
+        protocol_position,
+        personal_position,
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+        tick_array_bitmap_extension,
+    )?;
+
+    // the first deposit mints one share per unit of liquidity, less the locked shares
+    let share_vault = &mut ctx.accounts.share_vault;
+    let (shares, locked_shares) = get_shares_for_liquidity(
+        liquidity,
+        personal_position.liquidity,
+        share_vault.share_supply(ctx.accounts.share_mint.supply),
+    )?;
+    share_vault.locked_shares = share_vault
+        .locked_shares
+        .checked_add(locked_shares)
+        .unwrap();
+
+    let (amount_0, amount_1, amount_0_transfer_fee, amount_1_transfer_fee) = add_liquidity(
+        &ctx.accounts.owner,
+        &ctx.accounts.token_account_0.to_account_info(),
+        &ctx.accounts.token_account_1.to_account_info(),
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &AccountLoad::<TickArrayState>::try_from(&ctx.accounts.tick_array_lower.to_account_info())?,
+        &AccountLoad::<TickArrayState>::try_from(&ctx.accounts.tick_array_upper.to_account_info())?,
+        protocol_position,
+        Some(&ctx.accounts.token_program_2022),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.vault_0_mint.clone()),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        tick_array_bitmap_extension,
+        pool_state,
+        &mut liquidity,
+        amount_0_max,
+        amount_1_max,
+        tick_lower,
+        tick_upper,
+        None,
+    )?;
+
+    personal_position.token_fees_owed_0 = calculate_latest_token_fees(
+        personal_position.token_fees_owed_0,
+        personal_position.fee_growth_inside_0_last_x64,
+        protocol_position.fee_growth_inside_0_last_x64,
+        personal_position.liquidity,
+    );
+    personal_position.token_fees_owed_1 = calculate_latest_token_fees(
+        personal_position.token_fees_owed_1,
+        personal_position.fee_growth_inside_1_last_x64,
+        protocol_position.fee_growth_inside_1_last_x64,
+        personal_position.liquidity,
+    );
+    personal_position.fee_growth_inside_0_last_x64 = protocol_position.fee_growth_inside_0_last_x64;
+    personal_position.fee_growth_inside_1_last_x64 = protocol_position.fee_growth_inside_1_last_x64;
+    personal_position.update_rewards(protocol_position.reward_growth_inside, true)?;
+    personal_position.liquidity = personal_position.liquidity.checked_add(liquidity).unwrap();
+
+    let share_vault = &ctx.accounts.share_vault;
+    let pool_id = share_vault.pool_id;
+    let tick_lower_seed = share_vault.tick_lower_index.to_be_bytes();
+    let tick_upper_seed = share_vault.tick_upper_index.to_be_bytes();
+    token::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token::MintTo {
+                mint: ctx.accounts.share_mint.to_account_info(),
+                to: ctx.accounts.share_token_account.to_account_info(),
+                authority: share_vault.to_account_info(),
+            },
+            &[&[
+                SHARE_VAULT_SEED.as_bytes(),
+                pool_id.as_ref(),
+                &tick_lower_seed,
+                &tick_upper_seed,
+                share_vault.bump.as_ref(),
+            ]],
+        ),
+        shares,
+    )?;
+
+    emit!(DepositShareVaultEvent {
+        share_vault: share_vault.key(),
+        owner: ctx.accounts.owner.key(),
+        liquidity,
+        shares,
+        amount_0,
+        amount_1,
+        amount_0_transfer_fee,
+        amount_1_transfer_fee,
+    });
+
+    Ok(())
+}
+
+/// Reinvest the fees earned by the vault position into its range, so they accrue to the current shares.
+/// The fees stay owed while the pool does not allow collecting fees
+pub fn fold_share_vault_fees<'c: 'info, 'info>(
+    pool_state: &mut RefMut<PoolState>,
+    protocol_position: &mut ProtocolPositionState,
+    personal_position: &mut PersonalPositionState,
+    tick_array_lower_loader: &AccountLoader<'info, TickArrayState>,
+    tick_array_upper_loader: &AccountLoader<'info, TickArrayState>,
+    tick_array_bitmap_extension: Option<&'c AccountInfo<'info>>,
+) -> Result<()> {
+    if personal_position.liquidity == 0
+        || !pool_state.get_status_by_bit(PoolStatusBitIndex::CollectFee)?
+    {
+        return Ok(());
+    }
+    let (liquidity, amount_0, amount_1) = compound_fees(
+        pool_state,
+        protocol_position,
+        personal_position,
+        tick_array_lower_loader,
+        tick_array_upper_loader,
+        tick_array_bitmap_extension,
+    )?;
+    if liquidity > 0 {
+        emit!(IncreaseLiquidityEvent {
+            position_nft_mint: personal_position.nft_mint,
+            liquidity,
+            amount_0,
+            amount_1,
+            amount_0_transfer_fee: 0,
+            amount_1_transfer_fee: 0
+        });
+    }
+    Ok(())
+}
+
+/// The shares minted for `liquidity` added to a vault position of `liquidity_total` backing `share_supply` shares,
+/// and the shares locked. The first deposit locks `SHARE_VAULT_LOCKED_SHARES` of its shares
+pub fn get_shares_for_liquidity(
+    liquidity: u128,
+    liquidity_total: u128,
+    share_supply: u64,
+) -> Result<(u64, u64)> {
+    let shares = if share_supply == 0 || liquidity_total == 0 {
+        liquidity
+    } else {
+        U256::from(liquidity)
+            .mul_div_floor(U256::from(share_supply), U256::from(liquidity_total))
+            .unwrap()
+            .as_u128()
+    };
+    let shares = u64::try_from(shares).map_err(|_| ErrorCode::InvalidShareAmount)?;
+    let locked_shares = if share_supply == 0 {
+        SHARE_VAULT_LOCKED_SHARES
+    } else {
+        0
+    };
+    require_gt!(shares, locked_shares, ErrorCode::InvalidShareAmount);
+    Ok((shares - locked_shares, locked_shares))
+}
+
+#[cfg(test)]
+mod deposit_share_vault_test {
+    use super::*;
+
+    #[test]
+    fn first_deposit_locks_shares_test() {
+        let (shares, locked_shares) = get_shares_for_liquidity(1_000_000, 0, 0).unwrap();
+        assert_eq!(locked_shares, SHARE_VAULT_LOCKED_SHARES);
+        assert_eq!(shares, 1_000_000 - SHARE_VAULT_LOCKED_SHARES);
+        // the first deposit must leave some shares to its owner
+        assert!(get_shares_for_liquidity(u128::from(SHARE_VAULT_LOCKED_SHARES), 0, 0).is_err());
+        assert!(get_shares_for_liquidity(0, 0, 0).is_err());
+    }
+
+    #[test]
+    fn shares_for_liquidity_test() {
+        // the shares follow the liquidity backing each share, rounded down
+        assert_eq!(
+            get_shares_for_liquidity(500, 2_000, 1_000).unwrap(),
+            (250, 0)
+        );
+        assert_eq!(get_shares_for_liquidity(3, 2_000, 1_000).unwrap(), (1, 0));
+        assert!(get_shares_for_liquidity(1, 2_000, 1_000).is_err());
+    }
+
+    #[test]
+    fn reward_initialized_after_creation_test() {
+        let mut pool_state = PoolState::default();
+        ShareVaultState::check_deposit(&pool_state).unwrap();
+
+        // a reward initialized once the vault exists freezes the deposits
+        pool_state.reward_infos[1].token_mint = Pubkey::new_unique();
+        assert_eq!(
+            ShareVaultState::check_deposit(&pool_state).unwrap_err(),
+            error!(ErrorCode::ShareVaultFrozen)
+        );
+
+        // the withdrawals still split the rewards owed to the vault per share
+        let mut personal_position = PersonalPositionState::default();
+        personal_position.reward_infos[1].reward_amount_owed = 1_000;
+        assert_eq!(
+            super::super::get_share_rewards_owed(&personal_position, 250, 1_000),
+            [0, 250, 0]
+        );
+    }
+
+    #[test]
+    fn shares_overflow_test() {
+        // the shares do not fit the share mint
+        assert!(get_shares_for_liquidity(u128::from(u64::MAX) + 1, 0, 0).is_err());
+        assert!(get_shares_for_liquidity(u128::from(u64::MAX), 1, 2).is_err());
+        assert_eq!(
+            get_shares_for_liquidity(u128::from(u64::MAX), 0, 0).unwrap(),
+            (
+                u64::MAX - SHARE_VAULT_LOCKED_SHARES,
+                SHARE_VAULT_LOCKED_SHARES
+            )
+        );
+    }
+}
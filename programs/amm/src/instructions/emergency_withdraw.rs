@@ -0,0 +1,323 @@
+use crate::error::ErrorCode;
+use crate::libraries::liquidity_math;
+use crate::states::*;
+use crate::util::transfer_from_pool_vault_to_user;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct EmergencyWithdraw<'info> {
+    /// The position owner or delegated authority
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint,
+        constraint = nft_account.amount == 1,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Withdraw all the liquidity of this position
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// The pool in emergency exit
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Token_0 vault
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_1 vault
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The destination token account for receive amount_0
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The destination token account for receive amount_1
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+/// Withdraws all the liquidity of a position from a pool in emergency exit, together with
+/// the fees already owed to it. The liquidity is valued at the current price of the pool
+/// without crossing ticks, so no tick array is needed and the ticks of the position are
+/// left as they are. Fees and rewards not yet settled into the position are forfeited.
+/// When the vaults held less than the positions were owed as emergency exit was turned on,
+/// every position is paid the same proportion of what it is owed.
+///
+/// Limit orders are rejected, a filled order is no longer worth its liquidity at the current
+/// price and is settled with `decrease_liquidity_v2`. Locked positions, including the ones locked
+/// forever, have no emergency path and keep collecting fees with `collect_locked_position`.
+/// Positions held by a share vault are withdrawn with `withdraw_share_vault`. Both paths are
+/// disabled while the vaults are short, these positions then have no way out of the pool.
+pub fn emergency_withdraw<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, EmergencyWithdraw<'info>>,
+) -> Result<()> {
+    let recipient_owner = ctx.accounts.personal_position.check_authority(
+        ctx.accounts.nft_owner.key(),
+        ctx.accounts.nft_account.owner,
+        PositionPermissionBitIndex::DecreaseLiquidity,
+    )?;
+    check_recipient_owner(
+        recipient_owner,
+        ctx.accounts.recipient_token_account_0.owner,
+    )?;
+    check_recipient_owner(
+        recipient_owner,
+        ctx.accounts.recipient_token_account_1.owner,
+    )?;
+
+    let personal_position = &mut ctx.accounts.personal_position;
+    require!(
+        !personal_position.is_locked(Clock::get()?.unix_timestamp as u64),
+        ErrorCode::PositionLocked
+    );
+    require!(
+        !personal_position.is_limit_order(),
+        ErrorCode::LimitOrderNotEmergencyWithdrawable
+    );
+    let liquidity = personal_position.liquidity;
+    let (amount_0, amount_1) = {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        require!(pool_state.is_emergency_exit(), ErrorCode::NotEmergencyExit);
+
+        let (liquidity_amount_0, liquidity_amount_1) = get_emergency_withdraw_amounts(
+            &pool_state,
+            personal_position.tick_lower_index,
+            personal_position.tick_upper_index,
+            liquidity,
+        )?;
+        if pool_state.tick_current >= personal_position.tick_lower_index
+            && pool_state.tick_current < personal_position.tick_upper_index
+        {
+            pool_state.liquidity = pool_state.liquidity.checked_sub(liquidity).unwrap();
+        }
+        (
+            get_emergency_withdraw_payout(
+                &pool_state,
+                liquidity_amount_0
+                    .checked_add(personal_position.token_fees_owed_0)
+                    .unwrap(),
+                true,
+                ctx.accounts.token_vault_0.amount,
+            )?,
+            get_emergency_withdraw_payout(
+                &pool_state,
+                liquidity_amount_1
+                    .checked_add(personal_position.token_fees_owed_1)
+                    .unwrap(),
+                false,
+                ctx.accounts.token_vault_1.amount,
+            )?,
+        )
+    };
+
+    let protocol_position = &mut ctx.accounts.protocol_position;
+    protocol_position.liquidity = protocol_position.liquidity.checked_sub(liquidity).unwrap();
+    personal_position.liquidity = 0;
+    personal_position.token_fees_owed_0 = 0;
+    personal_position.token_fees_owed_1 = 0;
+
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.recipient_token_account_0.to_account_info(),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_0,
+    )?;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.recipient_token_account_1.to_account_info(),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_1,
+    )?;
+
+    emit!(EmergencyWithdrawEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        position_nft_mint: ctx.accounts.personal_position.nft_mint,
+        liquidity,
+        amount_0,
+        amount_1,
+    });
+
+    Ok(())
+}
+
+/// The amounts of `liquidity` over the range at the current price of the pool, rounded down
+pub fn get_emergency_withdraw_amounts(
+    pool_state: &PoolState,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    liquidity: u128,
+) -> Result<(u64, u64)> {
+    if liquidity == 0 {
+        return Ok((0, 0));
+    }
+    liquidity_math::get_delta_amounts_signed(
+        pool_state.tick_current,
+        pool_state.sqrt_price_x64,
+        tick_lower_index,
+        tick_upper_index,
+        -i128::try_from(liquidity).unwrap(),
+    )
+}
+
+/// The part of `amount` owed to the position paid out, the same proportion for every position
+/// when the vault is short, so the positions withdrawn first do not take the share of the others.
+/// Fails if the vault can not pay it, when the owed amounts recorded were too low
+pub fn get_emergency_withdraw_payout(
+    pool_state: &PoolState,
+    amount: u64,
+    zero: bool,
+    vault_amount: u64,
+) -> Result<u64> {
+    let payout = pool_state.get_emergency_payout(amount, zero);
+    require_gte!(
+        vault_amount,
+        payout,
+        ErrorCode::EmergencyWithdrawVaultShortfall
+    );
+    Ok(payout)
+}
+
+#[cfg(test)]
+mod emergency_withdraw_test {
+    use super::*;
+    use crate::libraries::tick_math;
+
+    #[test]
+    fn emergency_withdraw_amounts_test() {
+        let pool_state = PoolState {
+            tick_current: 0,
+            sqrt_price_x64: tick_math::get_sqrt_price_at_tick(0).unwrap(),
+            ..Default::default()
+        };
+
+        // in range, both tokens
+        let (amount_0, amount_1) =
+            get_emergency_withdraw_amounts(&pool_state, -100, 100, 1_000_000_000).unwrap();
+        assert!(amount_0 > 0 && amount_1 > 0);
+
+        // below the range, only token_0
+        let (amount_0, amount_1) =
+            get_emergency_withdraw_amounts(&pool_state, 100, 200, 1_000_000_000).unwrap();
+        assert!(amount_0 > 0);
+        assert_eq!(amount_1, 0);
+
+        // above the range, only token_1
+        let (amount_0, amount_1) =
+            get_emergency_withdraw_amounts(&pool_state, -200, -100, 1_000_000_000).unwrap();
+        assert_eq!(amount_0, 0);
+        assert!(amount_1 > 0);
+
+        assert_eq!(
+            get_emergency_withdraw_amounts(&pool_state, -100, 100, 0).unwrap(),
+            (0, 0)
+        );
+    }
+
+    #[test]
+    fn emergency_withdraw_payout_test() {
+        // vaults holding what the positions are owed pay in full
+        let mut pool_state = PoolState::default();
+        pool_state.set_emergency_exit(200, 100, 200, 100);
+        assert!(!pool_state.is_emergency_shortfall());
+        assert_eq!(
+            get_emergency_withdraw_payout(&pool_state, 110, true, 200).unwrap(),
+            110
+        );
+        assert_eq!(
+            get_emergency_withdraw_payout(&pool_state, 0, false, 0).unwrap(),
+            0
+        );
+        // the owed amounts recorded too low fail rather than overdraw the vault
+        assert_eq!(
+            get_emergency_withdraw_payout(&pool_state, 110, true, 109).unwrap_err(),
+            error!(ErrorCode::EmergencyWithdrawVaultShortfall)
+        );
+    }
+
+    #[test]
+    fn emergency_withdraw_short_vault_test() {
+        // two positions owed 100 and 300 of token_0, the vault holds 250 of which 50 are
+        // protocol and fund fees, so each position is paid half of what it is owed
+        let mut pool_state = PoolState {
+            protocol_fees_token_0: 30,
+            fund_fees_token_0: 20,
+            ..Default::default()
+        };
+        let mut vault_amount_0 = 250;
+        pool_state.set_emergency_exit(vault_amount_0, 0, 400, 0);
+        assert!(pool_state.is_emergency_shortfall());
+        // the exits paying in full are disabled
+        assert!(!pool_state
+            .get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity)
+            .unwrap());
+        assert!(!pool_state
+            .get_status_by_bit(PoolStatusBitIndex::CollectFee)
+            .unwrap());
+
+        // the order of the withdrawals does not matter
+        let payout = get_emergency_withdraw_payout(&pool_state, 300, true, vault_amount_0).unwrap();
+        assert_eq!(payout, 150);
+        vault_amount_0 -= payout;
+        let payout = get_emergency_withdraw_payout(&pool_state, 100, true, vault_amount_0).unwrap();
+        assert_eq!(payout, 50);
+        vault_amount_0 -= payout;
+        // the protocol and fund fees are left in the vault
+        assert_eq!(vault_amount_0, 50);
+    }
+}
@@ -0,0 +1,324 @@
+use crate::error::ErrorCode;
+use crate::libraries::{big_num::U128, fixed_point_64, full_math::MulDiv};
+use crate::states::*;
+use crate::util::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, program::invoke};
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct Flash<'info> {
+    /// The borrower
+    pub payer: Signer<'info>,
+
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The pool to borrow from
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The token account receiving the borrowed token_0
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token account receiving the borrowed token_1
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// CHECK: The program called back once the tokens are lent, it must repay the vaults
+    #[account(executable)]
+    pub receiver_program: UncheckedAccount<'info>,
+    // remaining accounts
+    // the accounts of the receiver program instruction, passed in order with their
+    // signer and writable flags
+    // pool_allow_list: must add account if swaps of the pool are restricted, it is passed to the
+    // receiver as well
+}
+
+/// Lends `amount_0` and `amount_1` out of the pool vaults and calls the receiver program with `data`.
+/// The vaults must hold their balance plus the flash fee once the receiver returns.
+/// The receiver can not be this program, and the pool can not be swapped against or have its
+/// liquidity changed until the repayment is checked, whatever the receiver calls.
+pub fn flash<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, Flash<'info>>,
+    amount_0: u64,
+    amount_1: u64,
+    data: Vec<u8>,
+) -> Result<()> {
+    require!(amount_0 > 0 || amount_1 > 0, ErrorCode::InvalidFlashAmount);
+    check_flash_receiver(ctx.accounts.receiver_program.key())?;
+    let (fee_0, fee_1) = {
+        let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+        if !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap)? {
+            return err!(ErrorCode::NotApproved);
+        }
+        check_pool_access(
+            pool_state,
+            ctx.accounts.pool_state.key(),
+            ctx.remaining_accounts,
+            ctx.accounts.payer.key(),
+            PoolAccessBitIndex::Swap,
+        )?;
+        pool_state.flash_in_progress = 1;
+        // the fee is paid to the liquidity in range, so there must be some
+        require_gt!(pool_state.liquidity, 0, ErrorCode::FlashNoLiquidity);
+        let trade_fee_rate = pool_state.trade_fee_rate(&ctx.accounts.amm_config);
+        (
+            get_flash_fee(amount_0, trade_fee_rate),
+            get_flash_fee(amount_1, trade_fee_rate),
+        )
+    };
+
+    let balance_0_before = ctx.accounts.token_vault_0.amount;
+    let balance_1_before = ctx.accounts.token_vault_1.amount;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.recipient_token_account_0.to_account_info(),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_0,
+    )?;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.recipient_token_account_1.to_account_info(),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_1,
+    )?;
+
+    let receiver_program = ctx.accounts.receiver_program.to_account_info();
+    let mut account_infos = ctx.remaining_accounts.to_vec();
+    account_infos.push(receiver_program.clone());
+    invoke(
+        &Instruction {
+            program_id: receiver_program.key(),
+            accounts: ctx
+                .remaining_accounts
+                .iter()
+                .map(|account| {
+                    if account.is_writable {
+                        AccountMeta::new(account.key(), account.is_signer)
+                    } else {
+                        AccountMeta::new_readonly(account.key(), account.is_signer)
+                    }
+                })
+                .collect(),
+            data,
+        },
+        &account_infos,
+    )?;
+
+    ctx.accounts.token_vault_0.reload()?;
+    ctx.accounts.token_vault_1.reload()?;
+    let balance_0_after = ctx.accounts.token_vault_0.amount;
+    let balance_1_after = ctx.accounts.token_vault_1.amount;
+    require_gte!(
+        balance_0_after,
+        balance_0_before.checked_add(fee_0).unwrap(),
+        ErrorCode::FlashNotRepaid
+    );
+    require_gte!(
+        balance_1_after,
+        balance_1_before.checked_add(fee_1).unwrap(),
+        ErrorCode::FlashNotRepaid
+    );
+    // anything paid above the balance before is charged as fee
+    let paid_0 = balance_0_after - balance_0_before;
+    let paid_1 = balance_1_after - balance_1_before;
+
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    pool_state.flash_in_progress = 0;
+    let amm_config = &ctx.accounts.amm_config;
+    if paid_0 > 0 {
+        let (fee_growth_global_0_x64, lp_fee_0, protocol_fee_0, fund_fee_0) = split_flash_fee(
+            paid_0,
+            pool_state.fee_growth_global_0_x64,
+            pool_state.liquidity,
+            amm_config,
+        );
+        pool_state.fee_growth_global_0_x64 = fee_growth_global_0_x64;
+        pool_state.total_fees_token_0 =
+            pool_state.total_fees_token_0.checked_add(lp_fee_0).unwrap();
+        pool_state.protocol_fees_token_0 = pool_state
+            .protocol_fees_token_0
+            .checked_add(protocol_fee_0)
+            .unwrap();
+        pool_state.fund_fees_token_0 = pool_state
+            .fund_fees_token_0
+            .checked_add(fund_fee_0)
+            .unwrap();
+    }
+    if paid_1 > 0 {
+        let (fee_growth_global_1_x64, lp_fee_1, protocol_fee_1, fund_fee_1) = split_flash_fee(
+            paid_1,
+            pool_state.fee_growth_global_1_x64,
+            pool_state.liquidity,
+            amm_config,
+        );
+        pool_state.fee_growth_global_1_x64 = fee_growth_global_1_x64;
+        pool_state.total_fees_token_1 =
+            pool_state.total_fees_token_1.checked_add(lp_fee_1).unwrap();
+        pool_state.protocol_fees_token_1 = pool_state
+            .protocol_fees_token_1
+            .checked_add(protocol_fee_1)
+            .unwrap();
+        pool_state.fund_fees_token_1 = pool_state
+            .fund_fees_token_1
+            .checked_add(fund_fee_1)
+            .unwrap();
+    }
+
+    emit!(FlashEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        sender: ctx.accounts.payer.key(),
+        receiver_program: receiver_program.key(),
+        amount_0,
+        amount_1,
+        fee_0,
+        fee_1,
+        paid_0,
+        paid_1,
+    });
+
+    Ok(())
+}
+
+/// Checks the receiver of a flash is not this program, which could otherwise call its own
+/// instructions with the lent tokens
+pub fn check_flash_receiver(receiver_program: Pubkey) -> Result<()> {
+    require_keys_neq!(
+        receiver_program,
+        crate::id(),
+        ErrorCode::InvalidFlashReceiver
+    );
+    Ok(())
+}
+
+/// The fee owed for borrowing `amount`, rounded up
+pub fn get_flash_fee(amount: u64, trade_fee_rate: u32) -> u64 {
+    U128::from(amount)
+        .mul_div_ceil(
+            U128::from(trade_fee_rate),
+            U128::from(FEE_RATE_DENOMINATOR_VALUE),
+        )
+        .unwrap()
+        .as_u64()
+}
+
+/// Splits a paid flash fee like a swap fee, returns the new global fee growth
+/// and the LP, protocol and fund parts
+fn split_flash_fee(
+    fee_amount: u64,
+    fee_growth_global_x64: u128,
+    liquidity: u128,
+    amm_config: &AmmConfig,
+) -> (u128, u64, u64, u64) {
+    let protocol_fee = U128::from(fee_amount)
+        .mul_div_floor(
+            U128::from(amm_config.protocol_fee_rate),
+            U128::from(FEE_RATE_DENOMINATOR_VALUE),
+        )
+        .unwrap()
+        .as_u64();
+    let fund_fee = U128::from(fee_amount)
+        .mul_div_floor(
+            U128::from(amm_config.fund_fee_rate),
+            U128::from(FEE_RATE_DENOMINATOR_VALUE),
+        )
+        .unwrap()
+        .as_u64();
+    let lp_fee = fee_amount - protocol_fee - fund_fee;
+    let fee_growth_global_x64_delta = U128::from(lp_fee)
+        .mul_div_floor(U128::from(fixed_point_64::Q64), U128::from(liquidity))
+        .unwrap()
+        .as_u128();
+    (
+        fee_growth_global_x64
+            .checked_add(fee_growth_global_x64_delta)
+            .unwrap(),
+        lp_fee,
+        protocol_fee,
+        fund_fee,
+    )
+}
+
+#[cfg(test)]
+mod flash_test {
+    use super::*;
+
+    #[test]
+    fn flash_self_receiver_test() {
+        // the program can not lend to itself and call its own instructions with the loan
+        assert!(check_flash_receiver(crate::id()).is_err());
+        check_flash_receiver(Pubkey::new_unique()).unwrap();
+    }
+
+    #[test]
+    fn flash_fee_rounds_up_test() {
+        assert_eq!(get_flash_fee(0, 2500), 0);
+        assert_eq!(get_flash_fee(1, 2500), 1);
+        assert_eq!(get_flash_fee(1_000_000, 2500), 2500);
+        assert_eq!(get_flash_fee(1_000_001, 2500), 2501);
+        assert_eq!(get_flash_fee(u64::MAX, 0), 0);
+    }
+
+    #[test]
+    fn split_flash_fee_test() {
+        let amm_config = AmmConfig {
+            protocol_fee_rate: 120_000,
+            fund_fee_rate: 40_000,
+            ..Default::default()
+        };
+        let (fee_growth_global_x64, lp_fee, protocol_fee, fund_fee) =
+            split_flash_fee(10_000, 5, 1 << 20, &amm_config);
+        assert_eq!(protocol_fee, 1_200);
+        assert_eq!(fund_fee, 400);
+        assert_eq!(lp_fee, 8_400);
+        assert_eq!(fee_growth_global_x64, 5 + ((8_400u128 << 64) >> 20));
+    }
+}
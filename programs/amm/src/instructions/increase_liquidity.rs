@@ -143,11 +143,29 @@ pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
 ) -> Result<()> {
     let mut liquidity = liquidity;
     let pool_state = &mut pool_state_loader.load_mut()?;
-    if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity) {
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)? {
         return err!(ErrorCode::NotApproved);
     }
+    check_pool_access(
+        pool_state,
+        pool_state_loader.key(),
+        remaining_accounts,
+        nft_owner.key(),
+        PoolAccessBitIndex::Liquidity,
+    )?;
     let tick_lower = personal_position.tick_lower_index;
     let tick_upper = personal_position.tick_upper_index;
+    if personal_position.is_limit_order() {
+        let tick_array = tick_array_lower_loader.load()?;
+        let fill_tick_state = tick_array.get_tick_state(
+            personal_position.limit_order_fill_tick,
+            pool_state.tick_spacing,
+        )?;
+        require!(
+            !personal_position.is_limit_order_filled(fill_tick_state),
+            ErrorCode::LimitOrderFilled
+        );
+    }
 
     let use_tickarray_bitmap_extension =
         pool_state.is_overflow_default_tickarray_bitmap(vec![tick_lower, tick_upper]);
@@ -183,6 +201,18 @@ pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
         base_flag,
     )?;
 
+    if personal_position.is_limit_order() {
+        let mut tick_array = tick_array_lower_loader.load_mut()?;
+        let fill_tick_state = tick_array.get_tick_state_mut(
+            personal_position.limit_order_fill_tick,
+            pool_state.tick_spacing,
+        )?;
+        fill_tick_state.order_liquidity = fill_tick_state
+            .order_liquidity
+            .checked_add(liquidity)
+            .unwrap();
+    }
+
     personal_position.token_fees_owed_0 = calculate_latest_token_fees(
         personal_position.token_fees_owed_0,
         personal_position.fee_growth_inside_0_last_x64,
@@ -0,0 +1,80 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::{prelude::*, system_program};
+use solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
+
+#[derive(Accounts)]
+pub struct IncreaseObservationCardinality<'info> {
+    /// Address paying the rent of the grown observation account. Can be anyone
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The amm config of the pool, holds the max observation cardinality
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The program account of the pool whose oracle grows
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The program account for the oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// To transfer the rent of the grown account
+    pub system_program: Program<'info, System>,
+}
+
+/// Grows the observation account so that the ring can hold `observation_cardinality_next` observations.
+/// An account grows by at most MAX_PERMITTED_DATA_INCREASE bytes per instruction, a larger
+/// cardinality needs more calls.
+pub fn increase_observation_cardinality(
+    ctx: Context<IncreaseObservationCardinality>,
+    observation_cardinality_next: u16,
+) -> Result<()> {
+    require_gte!(
+        ctx.accounts.amm_config.observation_cardinality_max(),
+        observation_cardinality_next,
+        ErrorCode::InvalidObservationCardinality
+    );
+
+    let observation_cardinality_next_old =
+        ctx.accounts.observation_state.load()?.cardinality_next();
+    let max_increase = (MAX_PERMITTED_DATA_INCREASE / Observation::LEN) as u16;
+    let observation_cardinality_next_new = observation_cardinality_next
+        .min(observation_cardinality_next_old.saturating_add(max_increase));
+    if observation_cardinality_next_new <= observation_cardinality_next_old {
+        return Ok(());
+    }
+
+    let observation_account_info = ctx.accounts.observation_state.to_account_info();
+    let space = ObservationState::account_len(observation_cardinality_next_new);
+    let required_lamports = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(observation_account_info.lamports());
+    if required_lamports > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: observation_account_info.clone(),
+                },
+            ),
+            required_lamports,
+        )?;
+    }
+    observation_account_info.realloc(space, true)?;
+
+    ctx.accounts
+        .observation_state
+        .load_mut()?
+        .observation_cardinality_next = observation_cardinality_next_new;
+
+    emit!(IncreaseObservationCardinalityEvent {
+        pool_id: ctx.accounts.pool_state.key(),
+        observation_cardinality_next_old,
+        observation_cardinality_next_new,
+    });
+
+    Ok(())
+}
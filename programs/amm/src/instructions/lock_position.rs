@@ -0,0 +1,36 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct LockPosition<'info> {
+    /// The position NFT holder
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint,
+        constraint = nft_account.amount == 1,
+        token::authority = nft_owner,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Lock the liquidity of this position
+    #[account(mut)]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+}
+
+pub fn lock_position(ctx: Context<LockPosition>, locked_until: u64) -> Result<()> {
+    let personal_position = &mut ctx.accounts.personal_position;
+    // a lock can only be extended, never shortened
+    personal_position.lock(locked_until, Clock::get()?.unix_timestamp as u64)?;
+
+    emit!(LockPositionEvent {
+        pool_state: personal_position.pool_id,
+        position_nft_mint: personal_position.nft_mint,
+        liquidity: personal_position.liquidity,
+        locked_until,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,229 @@
+use super::open_position::mint_nft_and_remove_mint_authority;
+use crate::states::*;
+use crate::util::{create_position_nft_mint_with_extensions, transfer_from_user_to_pool_vault};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{create, AssociatedToken, Create};
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct LockPositionForever<'info> {
+    /// The position NFT holder, receives the receipt NFT
+    #[account(mut)]
+    pub nft_owner: Signer<'info>,
+
+    /// Mint address bound to the personal position
+    #[account(address = personal_position.nft_mint)]
+    pub position_nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The token account for the tokenized position
+    #[account(
+        mut,
+        token::mint = position_nft_mint,
+        token::authority = nft_owner,
+        constraint = nft_account.amount == 1,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Lock the liquidity of this position forever
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// The pool of the position, mint authority of the receipt NFT
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Holds the position NFT in custody
+    #[account(
+        init,
+        seeds = [LOCKED_POSITION_SEED.as_bytes(), receipt_nft_mint.key().as_ref()],
+        bump,
+        payer = nft_owner,
+        space = LockedPositionState::LEN
+    )]
+    pub locked_position: Box<Account<'info, LockedPositionState>>,
+
+    /// CHECK: ATA of `locked_position` where the position NFT is held, initialize in contract
+    #[account(mut)]
+    pub locked_nft_account: UncheckedAccount<'info>,
+
+    /// Unique token mint address of the receipt NFT, initialize in contract
+    #[account(mut)]
+    pub receipt_nft_mint: Signer<'info>,
+
+    /// CHECK: ATA address where the receipt NFT will be minted, initialize in contract
+    #[account(mut)]
+    pub receipt_nft_account: UncheckedAccount<'info>,
+
+    /// Sysvar for token mint and ATA creation
+    pub rent: Sysvar<'info, Rent>,
+
+    /// Program to create the locked position state account
+    pub system_program: Program<'info, System>,
+
+    /// Program to transfer the position NFT of the Token program
+    pub token_program: Program<'info, Token>,
+
+    /// Program to create the receipt NFT mint/token account and transfer the position NFT of the Token-2022 program
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// Program to create the ATAs of the position NFT and the receipt NFT
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+pub fn lock_position_forever<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, LockPositionForever<'info>>,
+    with_metadata: bool,
+) -> Result<()> {
+    let personal_position = &mut ctx.accounts.personal_position;
+    // the liquidity can never be removed, and the delegate of the previous owner is dropped
+    personal_position.lock_forever()?;
+
+    let locked_position = &mut ctx.accounts.locked_position;
+    locked_position.bump = [ctx.bumps.locked_position];
+    locked_position.receipt_nft_mint = ctx.accounts.receipt_nft_mint.key();
+    locked_position.position_nft_mint = personal_position.nft_mint;
+    locked_position.personal_position = personal_position.key();
+    locked_position.pool_id = personal_position.pool_id;
+
+    // move the position NFT into the custody of the locked position
+    let position_nft_mint_info = ctx.accounts.position_nft_mint.to_account_info();
+    let position_nft_token_program =
+        if position_nft_mint_info.owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        };
+    create(CpiContext::new(
+        ctx.accounts.associated_token_program.to_account_info(),
+        Create {
+            payer: ctx.accounts.nft_owner.to_account_info(),
+            associated_token: ctx.accounts.locked_nft_account.to_account_info(),
+            authority: locked_position.to_account_info(),
+            mint: position_nft_mint_info,
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: position_nft_token_program,
+        },
+    ))?;
+    transfer_from_user_to_pool_vault(
+        &ctx.accounts.nft_owner,
+        &ctx.accounts.nft_account.to_account_info(),
+        &ctx.accounts.locked_nft_account.to_account_info(),
+        Some(ctx.accounts.position_nft_mint.clone()),
+        &ctx.accounts.token_program.to_account_info(),
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        1,
+    )?;
+
+    // mint the fee-claim receipt NFT to the owner
+    create_position_nft_mint_with_extensions(
+        &ctx.accounts.nft_owner,
+        &ctx.accounts.receipt_nft_mint,
+        &ctx.accounts.pool_state.to_account_info(),
+        &locked_position.to_account_info(),
+        &ctx.accounts.system_program,
+        &ctx.accounts.token_program_2022,
+        with_metadata,
+    )?;
+    create(CpiContext::new(
+        ctx.accounts.associated_token_program.to_account_info(),
+        Create {
+            payer: ctx.accounts.nft_owner.to_account_info(),
+            associated_token: ctx.accounts.receipt_nft_account.to_account_info(),
+            authority: ctx.accounts.nft_owner.to_account_info(),
+            mint: ctx.accounts.receipt_nft_mint.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.token_program_2022.to_account_info(),
+        },
+    ))?;
+    mint_nft_and_remove_mint_authority(
+        &ctx.accounts.nft_owner,
+        &ctx.accounts.pool_state,
+        &locked_position.to_account_info(),
+        &ctx.accounts.receipt_nft_mint,
+        &ctx.accounts.receipt_nft_account,
+        None,
+        None,
+        &ctx.accounts.token_program,
+        Some(&ctx.accounts.token_program_2022),
+        &ctx.accounts.system_program,
+        &ctx.accounts.rent,
+        with_metadata.then(|| get_receipt_metadata_data(locked_position.key())),
+        true,
+    )?;
+
+    emit!(LockPositionEvent {
+        pool_state: personal_position.pool_id,
+        position_nft_mint: personal_position.nft_mint,
+        liquidity: personal_position.liquidity,
+        locked_until: personal_position.locked_until,
+    });
+    emit!(LockPositionForeverEvent {
+        pool_state: personal_position.pool_id,
+        position_nft_mint: personal_position.nft_mint,
+        receipt_nft_mint: ctx.accounts.receipt_nft_mint.key(),
+        receipt_nft_owner: ctx.accounts.nft_owner.key(),
+        liquidity: personal_position.liquidity,
+    });
+
+    Ok(())
+}
+
+/// The metadata of the fee-claim receipt NFT, which points to the locked position rather than
+/// the position NFT it holds
+pub fn get_receipt_metadata_data(locked_position_id: Pubkey) -> (String, String, String) {
+    (
+        String::from("Swap.io Locked Liquidity Receipt"),
+        String::from("SWPCLR"),
+        format!(
+            "https://metanova.swap.io/solana/clmm/locked-position?id={}",
+            locked_position_id
+        ),
+    )
+}
+
+#[cfg(test)]
+mod lock_position_forever_test {
+    use super::super::open_position::get_metadata_data;
+    use super::*;
+
+    #[test]
+    fn lock_forever_test() {
+        let holder = Pubkey::new_unique();
+        let delegate = Pubkey::new_unique();
+        let mut personal_position = PersonalPositionState {
+            locked_until: 1_000,
+            ..Default::default()
+        };
+        personal_position.set_delegate(delegate, holder, POSITION_PERMISSION_MASK);
+        personal_position.lock_forever().unwrap();
+        assert_eq!(personal_position.locked_until, u64::MAX);
+        assert!(personal_position.is_locked(u64::MAX - 1));
+        assert!(personal_position.unlock(u64::MAX - 1).is_err());
+        // the delegate approved before the lock can no longer act on the position
+        assert!(personal_position
+            .check_authority(delegate, holder, PositionPermissionBitIndex::Collect)
+            .is_err());
+
+        let mut limit_order = PersonalPositionState {
+            limit_order_fill_count: 1,
+            ..Default::default()
+        };
+        assert!(limit_order.lock_forever().is_err());
+        assert_eq!(limit_order.locked_until, 0);
+    }
+
+    #[test]
+    fn receipt_metadata_test() {
+        let personal_position_id = Pubkey::new_unique();
+        let locked_position_id = Pubkey::new_unique();
+        let (name, symbol, uri) = get_receipt_metadata_data(locked_position_id);
+        let (position_name, position_symbol, position_uri) =
+            get_metadata_data(personal_position_id);
+        // the receipt can not be mistaken for the position NFT it holds
+        assert_ne!(name, position_name);
+        assert_ne!(symbol, position_symbol);
+        assert!(uri.ends_with(&locked_position_id.to_string()));
+        assert!(!uri.contains(&personal_position_id.to_string()));
+        assert_ne!(uri, position_uri);
+    }
+}
@@ -0,0 +1,279 @@
+use super::calculate_latest_token_fees;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::{burn, close_spl_account};
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct MergePositions<'info> {
+    /// The owner of both positions
+    #[account(mut)]
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the tokenized position which receives the liquidity
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint,
+        constraint = nft_account.amount == 1,
+        token::authority = nft_owner,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The position which receives the liquidity
+    #[account(mut)]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// The protocol position of the shared range
+    #[account(
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            personal_position.pool_id.as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Mint address bound to the merged position, burned by the instruction
+    #[account(
+      mut,
+      address = source_personal_position.nft_mint,
+      mint::token_program = token_program,
+    )]
+    pub source_position_nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// User token account where the merged position NFT be minted to
+    #[account(
+        mut,
+        token::mint = source_position_nft_mint,
+        token::authority = nft_owner,
+        constraint = source_position_nft_account.amount == 1,
+        token::token_program = token_program,
+    )]
+    pub source_position_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The position merged into `personal_position` and closed
+    #[account(
+        mut,
+        seeds = [POSITION_SEED.as_bytes(), source_position_nft_mint.key().as_ref()],
+        bump,
+        close = nft_owner
+    )]
+    pub source_personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// System program to close the position state account
+    pub system_program: Program<'info, System>,
+
+    /// Token/Token2022 program to close token/mint account of the merged position
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn merge_positions<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, MergePositions<'info>>,
+) -> Result<()> {
+    let personal_position = &mut ctx.accounts.personal_position;
+    let source_position = &mut ctx.accounts.source_personal_position;
+    require_keys_neq!(
+        personal_position.key(),
+        source_position.key(),
+        ErrorCode::InvalidMergePositions
+    );
+    merge_personal_positions(
+        &ctx.accounts.protocol_position,
+        personal_position,
+        source_position,
+    )?;
+
+    emit!(MergePositionsEvent {
+        position_nft_mint: personal_position.nft_mint,
+        source_position_nft_mint: source_position.nft_mint,
+        liquidity: source_position.liquidity,
+    });
+
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let source_position_nft_mint = ctx.accounts.source_position_nft_mint.to_account_info();
+    let source_nft_account = ctx.accounts.source_position_nft_account.to_account_info();
+    burn(
+        &ctx.accounts.nft_owner,
+        &source_position_nft_mint,
+        &source_nft_account,
+        &token_program,
+        &[],
+        1,
+    )?;
+
+    close_spl_account(
+        &ctx.accounts.nft_owner,
+        &ctx.accounts.nft_owner,
+        &source_nft_account,
+        &token_program,
+        &[],
+    )?;
+
+    if *source_position_nft_mint.owner == spl_token_2022::id() {
+        close_spl_account(
+            &source_position.to_account_info(),
+            &ctx.accounts.nft_owner,
+            &source_position_nft_mint,
+            &token_program,
+            &[&source_position.seeds()],
+        )?;
+    }
+    Ok(())
+}
+
+/// Settles both positions of the same range and adds the liquidity and amounts owed of
+/// `source_position` to `personal_position`
+pub fn merge_personal_positions(
+    protocol_position: &ProtocolPositionState,
+    personal_position: &mut PersonalPositionState,
+    source_position: &mut PersonalPositionState,
+) -> Result<()> {
+    require!(
+        personal_position.pool_id == source_position.pool_id
+            && personal_position.tick_lower_index == source_position.tick_lower_index
+            && personal_position.tick_upper_index == source_position.tick_upper_index
+            && !personal_position.is_limit_order()
+            && !source_position.is_limit_order(),
+        ErrorCode::InvalidMergePositions
+    );
+
+    // settle both positions to the growth of the protocol position, which no snapshot is newer than
+    for position in [&mut *personal_position, &mut *source_position] {
+        position.token_fees_owed_0 = calculate_latest_token_fees(
+            position.token_fees_owed_0,
+            position.fee_growth_inside_0_last_x64,
+            protocol_position.fee_growth_inside_0_last_x64,
+            position.liquidity,
+        );
+        position.token_fees_owed_1 = calculate_latest_token_fees(
+            position.token_fees_owed_1,
+            position.fee_growth_inside_1_last_x64,
+            protocol_position.fee_growth_inside_1_last_x64,
+            position.liquidity,
+        );
+        position.fee_growth_inside_0_last_x64 = protocol_position.fee_growth_inside_0_last_x64;
+        position.fee_growth_inside_1_last_x64 = protocol_position.fee_growth_inside_1_last_x64;
+        position.update_rewards(protocol_position.reward_growth_inside, true)?;
+    }
+
+    personal_position.liquidity = personal_position
+        .liquidity
+        .checked_add(source_position.liquidity)
+        .unwrap();
+    personal_position.token_fees_owed_0 = personal_position
+        .token_fees_owed_0
+        .checked_add(source_position.token_fees_owed_0)
+        .unwrap();
+    personal_position.token_fees_owed_1 = personal_position
+        .token_fees_owed_1
+        .checked_add(source_position.token_fees_owed_1)
+        .unwrap();
+    // the merged liquidity stays locked as long as the longer lock
+    personal_position.locked_until = personal_position
+        .locked_until
+        .max(source_position.locked_until);
+    for i in 0..REWARD_NUM {
+        personal_position.reward_infos[i].reward_amount_owed = personal_position.reward_infos[i]
+            .reward_amount_owed
+            .checked_add(source_position.reward_infos[i].reward_amount_owed)
+            .unwrap();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod merge_positions_test {
+    use super::*;
+    use crate::libraries::fixed_point_64;
+
+    fn build_position(
+        pool_id: Pubkey,
+        liquidity: u128,
+        growth_inside_last_x64: u128,
+        locked_until: u64,
+    ) -> PersonalPositionState {
+        let mut personal_position = PersonalPositionState {
+            pool_id,
+            tick_lower_index: -100,
+            tick_upper_index: 100,
+            liquidity,
+            fee_growth_inside_0_last_x64: growth_inside_last_x64,
+            fee_growth_inside_1_last_x64: growth_inside_last_x64,
+            token_fees_owed_0: 10,
+            token_fees_owed_1: 20,
+            locked_until,
+            ..Default::default()
+        };
+        for reward_info in personal_position.reward_infos.iter_mut() {
+            reward_info.growth_inside_last_x64 = growth_inside_last_x64;
+            reward_info.reward_amount_owed = 5;
+        }
+        personal_position
+    }
+
+    fn build_protocol_position(growth_inside_x64: u128) -> ProtocolPositionState {
+        ProtocolPositionState {
+            tick_lower_index: -100,
+            tick_upper_index: 100,
+            fee_growth_inside_0_last_x64: growth_inside_x64,
+            fee_growth_inside_1_last_x64: growth_inside_x64,
+            reward_growth_inside: [growth_inside_x64; REWARD_NUM],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn merge_positions_test() {
+        let pool_id = Pubkey::new_unique();
+        // the protocol growth is one token per liquidity above the target snapshot,
+        // and half a token per liquidity above the source snapshot
+        let protocol_position = build_protocol_position(fixed_point_64::Q64);
+        let mut personal_position = build_position(pool_id, 1_000, 0, 100);
+        let mut source_position = build_position(pool_id, 4_000, fixed_point_64::Q64 / 2, 200);
+        merge_personal_positions(
+            &protocol_position,
+            &mut personal_position,
+            &mut source_position,
+        )
+        .unwrap();
+
+        assert_eq!(personal_position.liquidity, 5_000);
+        assert_eq!(personal_position.token_fees_owed_0, 10 + 1_000 + 10 + 2_000);
+        assert_eq!(personal_position.token_fees_owed_1, 20 + 1_000 + 20 + 2_000);
+        assert_eq!(
+            personal_position.fee_growth_inside_0_last_x64,
+            fixed_point_64::Q64
+        );
+        assert_eq!(personal_position.locked_until, 200);
+        for reward_info in personal_position.reward_infos {
+            assert_eq!(reward_info.growth_inside_last_x64, fixed_point_64::Q64);
+            assert_eq!(reward_info.reward_amount_owed, 5 + 1_000 + 5 + 2_000);
+        }
+    }
+
+    #[test]
+    fn merge_invalid_positions_test() {
+        let pool_id = Pubkey::new_unique();
+        let protocol_position = build_protocol_position(0);
+        let personal_position = build_position(pool_id, 1_000, 0, 0);
+
+        let mut other_pool = build_position(Pubkey::new_unique(), 1_000, 0, 0);
+        let mut other_range = build_position(pool_id, 1_000, 0, 0);
+        other_range.tick_upper_index = 200;
+        let mut limit_order = build_position(pool_id, 1_000, 0, 0);
+        limit_order.limit_order_fill_count = 1;
+        for source_position in [&mut other_pool, &mut other_range, &mut limit_order] {
+            let mut target_position = personal_position.clone();
+            assert!(merge_personal_positions(
+                &protocol_position,
+                &mut target_position,
+                source_position
+            )
+            .is_err());
+            assert_eq!(target_position.liquidity, 1_000);
+        }
+    }
+}
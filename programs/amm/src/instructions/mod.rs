@@ -10,6 +10,9 @@ pub use open_position_v2::*;
 pub mod open_position_with_token22_nft;
 pub use open_position_with_token22_nft::*;
 
+pub mod open_position_zap;
+pub use open_position_zap::*;
+
 pub mod close_position;
 pub use close_position::*;
 
@@ -25,6 +28,54 @@ pub use decrease_liquidity::*;
 pub mod decrease_liquidity_v2;
 pub use decrease_liquidity_v2::*;
 
+pub mod decrease_liquidity_to_single;
+pub use decrease_liquidity_to_single::*;
+
+pub mod emergency_withdraw;
+pub use emergency_withdraw::*;
+
+pub mod collect_fees;
+pub use collect_fees::*;
+
+pub mod collect_position_rewards;
+pub use collect_position_rewards::*;
+
+pub mod compound_position;
+pub use compound_position::*;
+
+pub mod rebalance_position;
+pub use rebalance_position::*;
+
+pub mod split_position;
+pub use split_position::*;
+
+pub mod merge_positions;
+pub use merge_positions::*;
+
+pub mod set_position_delegate;
+pub use set_position_delegate::*;
+
+pub mod lock_position;
+pub use lock_position::*;
+
+pub mod unlock_position;
+pub use unlock_position::*;
+
+pub mod lock_position_forever;
+pub use lock_position_forever::*;
+
+pub mod collect_locked_position;
+pub use collect_locked_position::*;
+
+pub mod create_share_vault;
+pub use create_share_vault::*;
+
+pub mod deposit_share_vault;
+pub use deposit_share_vault::*;
+
+pub mod withdraw_share_vault;
+pub use withdraw_share_vault::*;
+
 pub mod swap;
 pub use swap::*;
 
@@ -34,6 +85,24 @@ pub use swap_v2::*;
 pub mod swap_router_base_in;
 pub use swap_router_base_in::*;
 
+pub mod swap_router_base_out;
+pub use swap_router_base_out::*;
+
+pub mod quote_swap;
+pub use quote_swap::*;
+
+pub mod flash;
+pub use flash::*;
+
+pub mod observe;
+pub use observe::*;
+
+pub mod increase_observation_cardinality;
+pub use increase_observation_cardinality::*;
+
+pub mod snapshot_cumulatives_inside;
+pub use snapshot_cumulatives_inside::*;
+
 pub mod update_reward_info;
 pub use update_reward_info::*;
 
@@ -0,0 +1,65 @@
+use crate::error::ErrorCode;
+use crate::libraries::tick_math;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct Observe<'info> {
+    /// The program account of the pool to observe
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The program account for the oracle observation
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+}
+
+/// The result of an observe, returned through return data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ObserveResult {
+    /// The tick cumulative as of each seconds_ago
+    pub tick_cumulatives: Vec<i64>,
+    /// The seconds per in range liquidity cumulative as of each seconds_ago, as a Q64.64
+    pub seconds_per_liquidity_cumulatives_x64: Vec<u128>,
+    /// The time-weighted average tick between the first and the last seconds_ago
+    pub arithmetic_mean_tick: i32,
+    /// The geometric mean price of the same window, as a Q64.64 sqrt price
+    pub arithmetic_mean_sqrt_price_x64: u128,
+}
+
+pub fn observe(ctx: Context<Observe>, seconds_agos: Vec<u32>) -> Result<ObserveResult> {
+    // the window runs from the first seconds_ago to the last one
+    require!(
+        seconds_agos.len() >= 2 && seconds_agos[0] > seconds_agos[seconds_agos.len() - 1],
+        ErrorCode::InvalidSecondsAgos
+    );
+
+    let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
+    let (tick_current, seconds_per_liquidity_global_x64) = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        (
+            pool_state.tick_current,
+            pool_state.seconds_per_liquidity_at(block_timestamp),
+        )
+    };
+    let (observation_state, observation_extension) =
+        load_observations(&ctx.accounts.observation_state)?;
+    let (tick_cumulatives, seconds_per_liquidity_cumulatives_x64) = observation_state.observe(
+        &observation_extension,
+        oracle::block_timestamp(),
+        &seconds_agos,
+        tick_current,
+        seconds_per_liquidity_global_x64,
+    )?;
+
+    let arithmetic_mean_tick = get_arithmetic_mean_tick(
+        tick_cumulatives[0],
+        tick_cumulatives[tick_cumulatives.len() - 1],
+        seconds_agos[0] - seconds_agos[seconds_agos.len() - 1],
+    );
+    Ok(ObserveResult {
+        tick_cumulatives,
+        seconds_per_liquidity_cumulatives_x64,
+        arithmetic_mean_tick,
+        arithmetic_mean_sqrt_price_x64: tick_math::get_sqrt_price_at_tick(arithmetic_mean_tick)?,
+    })
+}
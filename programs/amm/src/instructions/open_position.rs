@@ -213,6 +213,7 @@ pub fn open_position_v1<'a, 'b, 'c: 'info, 'info>(
         with_metadata,
         base_flag,
         false,
+        false,
     )
 }
 
@@ -253,13 +254,24 @@ pub fn open_position<'a, 'b, 'c: 'info, 'info>(
     with_metadata: bool,
     base_flag: Option<bool>,
     use_metadata_extension: bool,
+    limit_order: bool,
 ) -> Result<()> {
     let mut liquidity = liquidity;
     {
         let pool_state = &mut pool_state_loader.load_mut()?;
-        if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity) {
+        if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)? {
             return err!(ErrorCode::NotApproved);
         }
+        // the wallet funding the liquidity and the one owning it must both be allowed
+        for user in [payer.key(), position_nft_owner.key()] {
+            check_pool_access(
+                pool_state,
+                pool_state_loader.key(),
+                remaining_accounts,
+                user,
+                PoolAccessBitIndex::Liquidity,
+            )?;
+        }
         check_ticks_order(tick_lower_index, tick_upper_index)?;
         check_tick_array_start_index(
             tick_array_lower_start_index,
@@ -271,6 +283,16 @@ pub fn open_position<'a, 'b, 'c: 'info, 'info>(
             tick_upper_index,
             pool_state.tick_spacing,
         )?;
+        if limit_order {
+            // a limit order spans one tick spacing inside one tick array, on one side of the price
+            require!(
+                tick_upper_index - tick_lower_index == i32::from(pool_state.tick_spacing)
+                    && tick_array_lower_start_index == tick_array_upper_start_index
+                    && (pool_state.tick_current < tick_lower_index
+                        || pool_state.tick_current >= tick_upper_index),
+                ErrorCode::InvalidLimitOrder
+            );
+        }
 
         // Why not use anchor's `init-if-needed` to create?
         // Beacuse `tick_array_lower` and `tick_array_upper` can be the same account, anchor can initialze tick_array_lower but it causes a crash when anchor to initialze the `tick_array_upper`,
@@ -351,6 +373,43 @@ pub fn open_position<'a, 'b, 'c: 'info, 'info>(
             base_flag,
         )?;
 
+        if limit_order {
+            // an order above the price sells token_0 and is filled when the price crosses its upper tick,
+            // an order below the price sells token_1 and is filled when the price crosses its lower tick
+            let fill_tick = if pool_state.tick_current < tick_lower_index {
+                tick_upper_index
+            } else {
+                tick_lower_index
+            };
+            let mut tick_array = tick_array_lower_loader.load_mut()?;
+            let fill_tick_state =
+                tick_array.get_tick_state_mut(fill_tick, pool_state.tick_spacing)?;
+            fill_tick_state.order_liquidity = fill_tick_state
+                .order_liquidity
+                .checked_add(liquidity)
+                .unwrap();
+            personal_position.limit_order_fill_tick = fill_tick;
+            personal_position.limit_order_fill_count = fill_tick_state.order_fill_count + 1;
+            drop(tick_array);
+            // the fill of the batch records the fees and rewards earned by its orders
+            prepare_limit_order_fill(
+                payer.to_account_info(),
+                system_program.to_account_info(),
+                &tick_array_lower_loader.to_account_info(),
+                remaining_accounts,
+                pool_state_loader.key(),
+                fill_tick,
+            )?;
+            LimitOrderFillState::create_if_needed(
+                payer.to_account_info(),
+                system_program.to_account_info(),
+                remaining_accounts,
+                pool_state_loader.key(),
+                fill_tick,
+                personal_position.limit_order_fill_count,
+            )?;
+        }
+
         // let personal_position = &mut personal_position;
         personal_position.bump = [personal_position_bump];
         personal_position.nft_mint = position_nft_mint.key();
@@ -384,7 +443,7 @@ pub fn open_position<'a, 'b, 'c: 'info, 'info>(
     mint_nft_and_remove_mint_authority(
         payer,
         pool_state_loader,
-        personal_position,
+        &personal_position.to_account_info(),
         position_nft_mint,
         position_nft_account,
         metadata_account,
@@ -393,7 +452,7 @@ pub fn open_position<'a, 'b, 'c: 'info, 'info>(
         token_program_2022,
         system_program,
         rent,
-        with_metadata,
+        with_metadata.then(|| get_metadata_data(personal_position.key())),
         use_metadata_extension,
     )
 }
@@ -466,73 +525,17 @@ pub fn add_liquidity<'b, 'c: 'info, 'info>(
     }
     assert!(*liquidity > 0);
     let liquidity_before = pool_state.liquidity;
-    require_keys_eq!(tick_array_lower_loader.load()?.pool_id, pool_state.key());
-    require_keys_eq!(tick_array_upper_loader.load()?.pool_id, pool_state.key());
-
-    // get tick_state
-    let mut tick_lower_state = *tick_array_lower_loader
-        .load_mut()?
-        .get_tick_state_mut(tick_lower_index, pool_state.tick_spacing)?;
-    let mut tick_upper_state = *tick_array_upper_loader
-        .load_mut()?
-        .get_tick_state_mut(tick_upper_index, pool_state.tick_spacing)?;
-    if tick_lower_state.tick == 0 {
-        tick_lower_state.tick = tick_lower_index;
-    }
-    if tick_upper_state.tick == 0 {
-        tick_upper_state.tick = tick_upper_index;
-    }
-    let clock = Clock::get()?;
-    let (amount_0, amount_1, flip_tick_lower, flip_tick_upper) = modify_position(
-        i128::try_from(*liquidity).unwrap(),
+    let (amount_0, amount_1) = mint_liquidity(
         pool_state,
+        tick_array_lower_loader,
+        tick_array_upper_loader,
         protocol_position,
-        &mut tick_lower_state,
-        &mut tick_upper_state,
-        clock.unix_timestamp as u64,
-    )?;
-
-    // update tick_state
-    tick_array_lower_loader.load_mut()?.update_tick_state(
+        tick_array_bitmap_extension,
+        *liquidity,
         tick_lower_index,
-        pool_state.tick_spacing,
-        tick_lower_state,
-    )?;
-    tick_array_upper_loader.load_mut()?.update_tick_state(
         tick_upper_index,
-        pool_state.tick_spacing,
-        tick_upper_state,
     )?;
 
-    if flip_tick_lower {
-        let mut tick_array_lower = tick_array_lower_loader.load_mut()?;
-        let before_init_tick_count = tick_array_lower.initialized_tick_count;
-        tick_array_lower.update_initialized_tick_count(true)?;
-
-        if before_init_tick_count == 0 {
-            pool_state.flip_tick_array_bit(
-                tick_array_bitmap_extension,
-                tick_array_lower.start_tick_index,
-            )?;
-        }
-    }
-    if flip_tick_upper {
-        let mut tick_array_upper = tick_array_upper_loader.load_mut()?;
-        let before_init_tick_count = tick_array_upper.initialized_tick_count;
-        tick_array_upper.update_initialized_tick_count(true)?;
-
-        if before_init_tick_count == 0 {
-            pool_state.flip_tick_array_bit(
-                tick_array_bitmap_extension,
-                tick_array_upper.start_tick_index,
-            )?;
-        }
-    }
-    require!(
-        amount_0 > 0 || amount_1 > 0,
-        ErrorCode::ForbidBothZeroForSupplyLiquidity
-    );
-
     let mut amount_0_transfer_fee = 0;
     let mut amount_1_transfer_fee = 0;
     if vault_0_mint.is_some() {
@@ -595,6 +598,92 @@ pub fn add_liquidity<'b, 'c: 'info, 'info>(
         token_2022_program_opt.clone(),
         amount_1 + amount_1_transfer_fee,
     )?;
+    Ok((
+        amount_0,
+        amount_1,
+        amount_0_transfer_fee,
+        amount_1_transfer_fee,
+    ))
+}
+
+pub fn mint_liquidity<'c: 'info, 'info>(
+    pool_state: &mut RefMut<PoolState>,
+    tick_array_lower_loader: &AccountLoad<'info, TickArrayState>,
+    tick_array_upper_loader: &AccountLoad<'info, TickArrayState>,
+    protocol_position: &mut ProtocolPositionState,
+    tick_array_bitmap_extension: Option<&'c AccountInfo<'info>>,
+    liquidity: u128,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+) -> Result<(u64, u64)> {
+    let liquidity_before = pool_state.liquidity;
+    require_keys_eq!(tick_array_lower_loader.load()?.pool_id, pool_state.key());
+    require_keys_eq!(tick_array_upper_loader.load()?.pool_id, pool_state.key());
+
+    // get tick_state
+    let mut tick_lower_state = *tick_array_lower_loader
+        .load_mut()?
+        .get_tick_state_mut(tick_lower_index, pool_state.tick_spacing)?;
+    let mut tick_upper_state = *tick_array_upper_loader
+        .load_mut()?
+        .get_tick_state_mut(tick_upper_index, pool_state.tick_spacing)?;
+    if tick_lower_state.tick == 0 {
+        tick_lower_state.tick = tick_lower_index;
+    }
+    if tick_upper_state.tick == 0 {
+        tick_upper_state.tick = tick_upper_index;
+    }
+    let clock = Clock::get()?;
+    let (amount_0, amount_1, flip_tick_lower, flip_tick_upper) = modify_position(
+        i128::try_from(liquidity).unwrap(),
+        pool_state,
+        protocol_position,
+        &mut tick_lower_state,
+        &mut tick_upper_state,
+        clock.unix_timestamp as u64,
+    )?;
+
+    // update tick_state
+    tick_array_lower_loader.load_mut()?.update_tick_state(
+        tick_lower_index,
+        pool_state.tick_spacing,
+        tick_lower_state,
+    )?;
+    tick_array_upper_loader.load_mut()?.update_tick_state(
+        tick_upper_index,
+        pool_state.tick_spacing,
+        tick_upper_state,
+    )?;
+
+    if flip_tick_lower {
+        let mut tick_array_lower = tick_array_lower_loader.load_mut()?;
+        let before_init_tick_count = tick_array_lower.initialized_tick_count;
+        tick_array_lower.update_initialized_tick_count(true)?;
+
+        if before_init_tick_count == 0 {
+            pool_state.flip_tick_array_bit(
+                tick_array_bitmap_extension,
+                tick_array_lower.start_tick_index,
+            )?;
+        }
+    }
+    if flip_tick_upper {
+        let mut tick_array_upper = tick_array_upper_loader.load_mut()?;
+        let before_init_tick_count = tick_array_upper.initialized_tick_count;
+        tick_array_upper.update_initialized_tick_count(true)?;
+
+        if before_init_tick_count == 0 {
+            pool_state.flip_tick_array_bit(
+                tick_array_bitmap_extension,
+                tick_array_upper.start_tick_index,
+            )?;
+        }
+    }
+    require!(
+        amount_0 > 0 || amount_1 > 0,
+        ErrorCode::ForbidBothZeroForSupplyLiquidity
+    );
+
     emit!(LiquidityChangeEvent {
         pool_state: pool_state.key(),
         tick: pool_state.tick_current,
@@ -603,12 +692,8 @@ pub fn add_liquidity<'b, 'c: 'info, 'info>(
         liquidity_before: liquidity_before,
         liquidity_after: pool_state.liquidity,
     });
-    Ok((
-        amount_0,
-        amount_1,
-        amount_0_transfer_fee,
-        amount_1_transfer_fee,
-    ))
+
+    Ok((amount_0, amount_1))
 }
 
 pub fn modify_position(
@@ -649,6 +734,64 @@ pub fn modify_position(
     Ok((amount_0, amount_1, flip_tick_lower, flip_tick_upper))
 }
 
+/// Burns liquidity of a filled limit order. The fill already withdrew the liquidity from the
+/// liquidity net of both ticks and the pool, only the gross liquidity of the ticks is released.
+/// The amount is settled in the filled token at the price range of the order.
+pub fn settle_filled_limit_order(
+    liquidity: u128,
+    fill_tick: i32,
+    pool_state: &mut RefMut<PoolState>,
+    protocol_position_state: &mut ProtocolPositionState,
+    tick_lower_state: &mut TickState,
+    tick_upper_state: &mut TickState,
+    timestamp: u64,
+) -> Result<(u64, u64, bool, bool)> {
+    let liquidity_delta = -i128::try_from(liquidity).unwrap();
+    // offset the liquidity net change of `update_position`
+    tick_lower_state.liquidity_net = tick_lower_state
+        .liquidity_net
+        .checked_sub(liquidity_delta)
+        .unwrap();
+    tick_upper_state.liquidity_net = tick_upper_state
+        .liquidity_net
+        .checked_add(liquidity_delta)
+        .unwrap();
+    let (flip_tick_lower, flip_tick_upper) = update_position(
+        liquidity_delta,
+        pool_state,
+        protocol_position_state,
+        tick_lower_state,
+        tick_upper_state,
+        timestamp,
+    )?;
+
+    let sqrt_price_lower_x64 = tick_math::get_sqrt_price_at_tick(tick_lower_state.tick)?;
+    let sqrt_price_upper_x64 = tick_math::get_sqrt_price_at_tick(tick_upper_state.tick)?;
+    let (amount_0, amount_1) = if fill_tick == tick_upper_state.tick {
+        (
+            0,
+            liquidity_math::get_delta_amount_1_unsigned(
+                sqrt_price_lower_x64,
+                sqrt_price_upper_x64,
+                liquidity,
+                false,
+            )?,
+        )
+    } else {
+        (
+            liquidity_math::get_delta_amount_0_unsigned(
+                sqrt_price_lower_x64,
+                sqrt_price_upper_x64,
+                liquidity,
+                false,
+            )?,
+            0,
+        )
+    };
+
+    Ok((amount_0, amount_1, flip_tick_lower, flip_tick_upper))
+}
+
 /// Updates a position with the given liquidity delta and tick
 pub fn update_position(
     liquidity_delta: i128,
@@ -659,6 +802,7 @@ pub fn update_position(
     timestamp: u64,
 ) -> Result<(bool, bool)> {
     let updated_reward_infos = pool_state.update_reward_infos(timestamp)?;
+    let seconds_per_liquidity_global_x64 = pool_state.update_seconds_per_liquidity(timestamp);
 
     let mut flipped_lower = false;
     let mut flipped_upper = false;
@@ -673,6 +817,8 @@ pub fn update_position(
             pool_state.fee_growth_global_1_x64,
             false,
             &updated_reward_infos,
+            seconds_per_liquidity_global_x64,
+            timestamp as u32,
         )?;
         flipped_upper = tick_upper_state.update(
             pool_state.tick_current,
@@ -681,6 +827,8 @@ pub fn update_position(
             pool_state.fee_growth_global_1_x64,
             true,
             &updated_reward_infos,
+            seconds_per_liquidity_global_x64,
+            timestamp as u32,
         )?;
         #[cfg(feature = "enable-log")]
         msg!(
@@ -726,10 +874,10 @@ pub fn update_position(
     Ok((flipped_lower, flipped_upper))
 }
 
-fn mint_nft_and_remove_mint_authority<'info>(
+pub fn mint_nft_and_remove_mint_authority<'info>(
     payer: &Signer<'info>,
     pool_state_loader: &AccountLoader<'info, PoolState>,
-    personal_position: &Account<'info, PersonalPositionState>,
+    metadata_update_authority: &AccountInfo<'info>,
     position_nft_mint: &AccountInfo<'info>,
     position_nft_account: &AccountInfo<'info>,
     metadata_account: Option<&UncheckedAccount<'info>>,
@@ -738,7 +886,7 @@ fn mint_nft_and_remove_mint_authority<'info>(
     token_program_2022: Option<&Program<'info, Token2022>>,
     system_program: &Program<'info, System>,
     rent: &Sysvar<'info, Rent>,
-    with_metadata: bool,
+    metadata: Option<(String, String, String)>,
     use_metadata_extension: bool,
 ) -> Result<()> {
     let pool_state_info = pool_state_loader.to_account_info();
@@ -752,14 +900,13 @@ fn mint_nft_and_remove_mint_authority<'info>(
         token_program_2022.unwrap().to_account_info()
     };
 
-    if with_metadata {
-        let (name, symbol, uri) = get_metadata_data(personal_position.key());
+    if let Some((name, symbol, uri)) = metadata {
         if use_metadata_extension {
             initialize_token_metadata_extension(
                 payer,
                 &position_nft_mint_info,
                 &pool_state_info,
-                &personal_position.to_account_info(),
+                metadata_update_authority,
                 token_program_2022.unwrap(),
                 name,
                 symbol,
@@ -811,7 +958,7 @@ fn mint_nft_and_remove_mint_authority<'info>(
     )
 }
 
-fn get_metadata_data(personal_position_id: Pubkey) -> (String, String, String) {
+pub fn get_metadata_data(personal_position_id: Pubkey) -> (String, String, String) {
     return (
         String::from("Swap.io Concentrated Liquidity"),
         String::from("SWPCL"),
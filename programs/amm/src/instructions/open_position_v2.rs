@@ -204,5 +204,6 @@ pub fn open_position_v2<'a, 'b, 'c: 'info, 'info>(
         with_metadata,
         base_flag,
         false,
+        false,
     )
 }
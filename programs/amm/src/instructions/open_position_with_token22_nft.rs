@@ -153,6 +153,7 @@ pub fn open_position_with_token22_nft<'a, 'b, 'c: 'info, 'info>(
     tick_array_upper_start_index: i32,
     with_metadata: bool,
     base_flag: Option<bool>,
+    limit_order: bool,
 ) -> Result<()> {
     create_position_nft_mint_with_extensions(
         &ctx.accounts.payer,
@@ -213,5 +214,6 @@ pub fn open_position_with_token22_nft<'a, 'b, 'c: 'info, 'info>(
         with_metadata,
         base_flag,
         true,
+        limit_order,
     )
 }
@@ -0,0 +1,534 @@
+use super::mint_liquidity;
+use super::open_position::open_position;
+use crate::error::ErrorCode;
+use crate::libraries::{big_num::U256, full_math::MulDiv, liquidity_math, tick_math};
+use crate::states::*;
+use crate::swap::swap_internal_with_summary;
+use crate::util::*;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::Metadata;
+use anchor_spl::token::{self, Token};
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+use std::collections::VecDeque;
+use std::ops::Deref;
+
+#[derive(Accounts)]
+#[instruction(tick_lower_index: i32, tick_upper_index: i32, tick_array_lower_start_index: i32, tick_array_upper_start_index: i32)]
+pub struct OpenPositionZap<'info> {
+    /// Pays to mint the position
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Receives the position NFT
+    pub position_nft_owner: UncheckedAccount<'info>,
+
+    /// Unique token mint address
+    #[account(
+        init,
+        mint::decimals = 0,
+        mint::authority = pool_state.key(),
+        payer = payer,
+    )]
+    pub position_nft_mint: Box<Account<'info, token::Mint>>,
+
+    /// Token account where position NFT will be minted
+    #[account(
+        init,
+        associated_token::mint = position_nft_mint,
+        associated_token::authority = position_nft_owner,
+        payer = payer,
+    )]
+    pub position_nft_account: Box<Account<'info, token::TokenAccount>>,
+
+    /// To store metaplex metadata
+    /// CHECK: Safety check performed inside function body
+    #[account(mut)]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// Add liquidity for this pool
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// Store the information of market marking in range
+    #[account(
+        init_if_needed,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_lower_index.to_be_bytes(),
+            &tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        payer = payer,
+        space = ProtocolPositionState::LEN
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// CHECK: Account to store data for the position's lower tick
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_array_lower_start_index.to_be_bytes(),
+        ],
+        bump,
+    )]
+    pub tick_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Account to store data for the position's upper tick
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_array_upper_start_index.to_be_bytes(),
+        ],
+        bump,
+    )]
+    pub tick_array_upper: UncheckedAccount<'info>,
+
+    /// personal position state
+    #[account(
+        init,
+        seeds = [POSITION_SEED.as_bytes(), position_nft_mint.key().as_ref()],
+        bump,
+        payer = payer,
+        space = PersonalPositionState::LEN
+    )]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// The token_0 account deposit token to the pool and receive the token_0 left over
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token_1 account deposit token to the pool and receive the token_1 left over
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Sysvar for token mint and ATA creation
+    pub rent: Sysvar<'info, Rent>,
+
+    /// Program to create the position manager state account
+    pub system_program: Program<'info, System>,
+
+    /// Program to create mint account and mint tokens
+    pub token_program: Program<'info, Token>,
+    /// Program to create an ATA for receiving position NFT
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// Program to create NFT metadata
+    /// CHECK: Metadata program address constraint applied
+    pub metadata_program: Program<'info, Metadata>,
+    /// Program to create mint account and mint tokens
+    pub token_program_2022: Program<'info, Token2022>,
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+    // remaining account
+    // #[account(
+    //     seeds = [
+    //         POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
+    //         pool_state.key().as_ref(),
+    //     ],
+    //     bump
+    // )]
+    // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+    // and the tick arrays to swap through
+}
+
+pub fn open_position_zap<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, OpenPositionZap<'info>>,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    tick_array_lower_start_index: i32,
+    tick_array_upper_start_index: i32,
+    amount_in: u64,
+    zero_for_one: bool,
+    liquidity_min: u128,
+    with_metadata: bool,
+) -> Result<()> {
+    // open an empty position, the liquidity is added below from the single input token
+    open_position(
+        &ctx.accounts.payer,
+        &ctx.accounts.position_nft_owner,
+        &ctx.accounts.position_nft_mint.to_account_info(),
+        &ctx.accounts.position_nft_account.to_account_info(),
+        Some(&ctx.accounts.metadata_account),
+        &ctx.accounts.pool_state,
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+        &mut ctx.accounts.protocol_position,
+        &mut ctx.accounts.personal_position,
+        &ctx.accounts.token_account_0.to_account_info(),
+        &ctx.accounts.token_account_1.to_account_info(),
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.rent,
+        &ctx.accounts.system_program,
+        &ctx.accounts.token_program,
+        &ctx.accounts.associated_token_program,
+        Some(&ctx.accounts.metadata_program),
+        Some(&ctx.accounts.token_program_2022),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        &ctx.remaining_accounts,
+        ctx.bumps.protocol_position,
+        ctx.bumps.personal_position,
+        0,
+        0,
+        0,
+        tick_lower_index,
+        tick_upper_index,
+        tick_array_lower_start_index,
+        tick_array_upper_start_index,
+        with_metadata,
+        None,
+        false,
+        false,
+    )?;
+
+    let (input_token_account, input_vault, input_vault_mint) = if zero_for_one {
+        (
+            &ctx.accounts.token_account_0,
+            &ctx.accounts.token_vault_0,
+            &ctx.accounts.vault_0_mint,
+        )
+    } else {
+        (
+            &ctx.accounts.token_account_1,
+            &ctx.accounts.token_vault_1,
+            &ctx.accounts.vault_1_mint,
+        )
+    };
+    let transfer_fee = get_transfer_fee(input_vault_mint.clone(), amount_in)?;
+    transfer_from_user_to_pool_vault(
+        &ctx.accounts.payer,
+        &input_token_account.to_account_info(),
+        &input_vault.to_account_info(),
+        Some(input_vault_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_in,
+    )?;
+    let amount_received = amount_in.checked_sub(transfer_fee).unwrap();
+
+    let mut tickarray_bitmap_extension = None;
+    let mut swap_tick_array_accounts = Vec::new();
+    let tick_array_bitmap_extension_key =
+        TickArrayBitmapExtension::key(ctx.accounts.pool_state.key());
+    for account_info in ctx.remaining_accounts.iter() {
+        if PoolAllowListState::is_allow_list_account(account_info)
+            || LimitOrderFillState::is_limit_order_fill_account(account_info)
+        {
+            continue;
+        }
+        if account_info.key().eq(&tick_array_bitmap_extension_key) {
+            tickarray_bitmap_extension = Some(account_info);
+            continue;
+        }
+        swap_tick_array_accounts.push(account_info);
+    }
+
+    let sqrt_price_lower_x64 = tick_math::get_sqrt_price_at_tick(tick_lower_index)?;
+    let sqrt_price_upper_x64 = tick_math::get_sqrt_price_at_tick(tick_upper_index)?;
+
+    // swap the part of the input that the range needs in the other token, the tokens stay in the vaults
+    let (mut amount_0, mut amount_1) = if zero_for_one {
+        (amount_received, 0)
+    } else {
+        (0, amount_received)
+    };
+    let swap_amount = get_zap_swap_amount(
+        ctx.accounts.pool_state.load()?.sqrt_price_x64,
+        sqrt_price_lower_x64,
+        sqrt_price_upper_x64,
+        amount_received,
+        zero_for_one,
+    );
+    if swap_amount > 0 {
+        let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+        pool_state.check_open(Clock::get()?.unix_timestamp as u64)?;
+        // the swap hook is only called by the swap instructions
+        require!(!pool_state.has_swap_hook(), ErrorCode::SwapHookNotSupported);
+        check_pool_access(
+            pool_state,
+            ctx.accounts.pool_state.key(),
+            ctx.remaining_accounts,
+            ctx.accounts.payer.key(),
+            PoolAccessBitIndex::Swap,
+        )?;
+        let mut tick_array_states = VecDeque::new();
+        for account_info in swap_tick_array_accounts.into_iter() {
+            tick_array_states.push_back(AccountLoad::load_data_mut(account_info)?);
+        }
+        let swap_bitmap_extension = match tickarray_bitmap_extension {
+            Some(account_info) => Some(
+                *(AccountLoader::<TickArrayBitmapExtension>::try_from(account_info)?
+                    .load()?
+                    .deref()),
+            ),
+            None => None,
+        };
+        let (mut observation_state, mut observation_extension) =
+            load_observations_mut(&ctx.accounts.observation_state)?;
+        let summary = swap_internal_with_summary(
+            &ctx.accounts.amm_config,
+            pool_state,
+            &mut tick_array_states,
+            &mut observation_state,
+            &mut observation_extension,
+            &swap_bitmap_extension,
+            swap_amount,
+            if zero_for_one {
+                tick_math::MIN_SQRT_PRICE_X64 + 1
+            } else {
+                tick_math::MAX_SQRT_PRICE_X64 - 1
+            },
+            None,
+            zero_for_one,
+            true,
+            oracle::block_timestamp(),
+        )?;
+        drop(tick_array_states);
+        record_limit_order_fills(
+            ctx.accounts.pool_state.key(),
+            &summary.limit_order_fills,
+            ctx.remaining_accounts,
+        )?;
+        let (swap_amount_0, swap_amount_1) = (summary.amount_0, summary.amount_1);
+        if zero_for_one {
+            amount_0 = amount_0.checked_sub(swap_amount_0).unwrap();
+            amount_1 = swap_amount_1;
+        } else {
+            amount_0 = swap_amount_0;
+            amount_1 = amount_1.checked_sub(swap_amount_1).unwrap();
+        }
+    }
+
+    let personal_position = &mut ctx.accounts.personal_position;
+    let protocol_position = &mut ctx.accounts.protocol_position;
+    let liquidity;
+    let (deposit_amount_0, deposit_amount_1) = {
+        let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+        liquidity = liquidity_math::get_liquidity_from_amounts(
+            pool_state.sqrt_price_x64,
+            sqrt_price_lower_x64,
+            sqrt_price_upper_x64,
+            amount_0,
+            amount_1,
+        );
+        require_gte!(liquidity, liquidity_min, ErrorCode::PriceSlippageCheck);
+        let (deposit_amount_0, deposit_amount_1) = mint_liquidity(
+            pool_state,
+            &AccountLoad::<TickArrayState>::try_from(
+                &ctx.accounts.tick_array_lower.to_account_info(),
+            )?,
+            &AccountLoad::<TickArrayState>::try_from(
+                &ctx.accounts.tick_array_upper.to_account_info(),
+            )?,
+            protocol_position,
+            tickarray_bitmap_extension,
+            liquidity,
+            tick_lower_index,
+            tick_upper_index,
+        )?;
+        require_gte!(amount_0, deposit_amount_0, ErrorCode::PriceSlippageCheck);
+        require_gte!(amount_1, deposit_amount_1, ErrorCode::PriceSlippageCheck);
+        (deposit_amount_0, deposit_amount_1)
+    };
+
+    // the position was empty, so it only takes the growth snapshots of the range
+    personal_position.fee_growth_inside_0_last_x64 = protocol_position.fee_growth_inside_0_last_x64;
+    personal_position.fee_growth_inside_1_last_x64 = protocol_position.fee_growth_inside_1_last_x64;
+    personal_position.update_rewards(protocol_position.reward_growth_inside, false)?;
+    personal_position.liquidity = liquidity;
+
+    // the tokens not deposited are sent back to the payer
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_account_0.to_account_info(),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_0 - deposit_amount_0,
+    )?;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.token_account_1.to_account_info(),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_1 - deposit_amount_1,
+    )?;
+
+    emit!(IncreaseLiquidityEvent {
+        position_nft_mint: personal_position.nft_mint,
+        liquidity,
+        amount_0: deposit_amount_0,
+        amount_1: deposit_amount_1,
+        amount_0_transfer_fee: if zero_for_one { transfer_fee } else { 0 },
+        amount_1_transfer_fee: if zero_for_one { 0 } else { transfer_fee },
+    });
+
+    Ok(())
+}
+
+/// Calculates the part of a single token input to swap for the other token, so that the rest
+/// and the swap output match the token ratio of the range at the current price.
+/// The price impact and the trade fee of the swap are not accounted, the tokens left over are refunded
+pub fn get_zap_swap_amount(
+    sqrt_price_x64: u128,
+    sqrt_price_lower_x64: u128,
+    sqrt_price_upper_x64: u128,
+    amount_in: u64,
+    zero_for_one: bool,
+) -> u64 {
+    let sqrt_price_x64 = sqrt_price_x64.clamp(sqrt_price_lower_x64, sqrt_price_upper_x64);
+    // the values of the token_0 and token_1 amounts of a unit of liquidity, in token_1
+    let value_0 = U256::from(sqrt_price_upper_x64 - sqrt_price_x64)
+        .mul_div_floor(U256::from(sqrt_price_x64), U256::from(sqrt_price_upper_x64))
+        .unwrap();
+    let value_1 = U256::from(sqrt_price_x64 - sqrt_price_lower_x64);
+    let value_swapped = if zero_for_one { value_1 } else { value_0 };
+    U256::from(amount_in)
+        .mul_div_floor(value_swapped, value_0 + value_1)
+        .unwrap()
+        .as_u64()
+}
+
+#[cfg(test)]
+mod get_zap_swap_amount_test {
+    use super::*;
+
+    #[test]
+    fn price_below_range_test() {
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(-100).unwrap();
+        let sqrt_price_lower_x64 = tick_math::get_sqrt_price_at_tick(0).unwrap();
+        let sqrt_price_upper_x64 = tick_math::get_sqrt_price_at_tick(100).unwrap();
+        // only token_0 is needed
+        assert_eq!(
+            get_zap_swap_amount(
+                sqrt_price_x64,
+                sqrt_price_lower_x64,
+                sqrt_price_upper_x64,
+                1_000_000,
+                true
+            ),
+            0
+        );
+        assert_eq!(
+            get_zap_swap_amount(
+                sqrt_price_x64,
+                sqrt_price_lower_x64,
+                sqrt_price_upper_x64,
+                1_000_000,
+                false
+            ),
+            1_000_000
+        );
+    }
+
+    #[test]
+    fn price_above_range_test() {
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(200).unwrap();
+        let sqrt_price_lower_x64 = tick_math::get_sqrt_price_at_tick(0).unwrap();
+        let sqrt_price_upper_x64 = tick_math::get_sqrt_price_at_tick(100).unwrap();
+        // only token_1 is needed
+        assert_eq!(
+            get_zap_swap_amount(
+                sqrt_price_x64,
+                sqrt_price_lower_x64,
+                sqrt_price_upper_x64,
+                1_000_000,
+                true
+            ),
+            1_000_000
+        );
+        assert_eq!(
+            get_zap_swap_amount(
+                sqrt_price_x64,
+                sqrt_price_lower_x64,
+                sqrt_price_upper_x64,
+                1_000_000,
+                false
+            ),
+            0
+        );
+    }
+
+    #[test]
+    fn price_in_symmetric_range_test() {
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(0).unwrap();
+        let sqrt_price_lower_x64 = tick_math::get_sqrt_price_at_tick(-1000).unwrap();
+        let sqrt_price_upper_x64 = tick_math::get_sqrt_price_at_tick(1000).unwrap();
+        let amount_in = 1_000_000;
+        for zero_for_one in [true, false] {
+            let swap_amount = get_zap_swap_amount(
+                sqrt_price_x64,
+                sqrt_price_lower_x64,
+                sqrt_price_upper_x64,
+                amount_in,
+                zero_for_one,
+            );
+            // at price 1 the range needs about the same amount of both tokens
+            assert!(swap_amount > amount_in * 49 / 100 && swap_amount < amount_in * 51 / 100);
+
+            // without fee and price impact, the rest and the swap output add the same liquidity
+            let (amount_0, amount_1) = if zero_for_one {
+                (amount_in - swap_amount, swap_amount)
+            } else {
+                (swap_amount, amount_in - swap_amount)
+            };
+            let liquidity_0 = liquidity_math::get_liquidity_from_amount_0(
+                sqrt_price_x64,
+                sqrt_price_upper_x64,
+                amount_0,
+            );
+            let liquidity_1 = liquidity_math::get_liquidity_from_amount_1(
+                sqrt_price_lower_x64,
+                sqrt_price_x64,
+                amount_1,
+            );
+            assert!(liquidity_0.abs_diff(liquidity_1) * 10_000 < liquidity_0);
+        }
+    }
+}
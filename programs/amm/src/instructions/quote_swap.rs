@@ -0,0 +1,300 @@
+use crate::libraries::tick_math;
+use crate::states::*;
+use crate::swap::swap_internal_with_summary;
+use crate::util::AccountLoad;
+use anchor_lang::prelude::*;
+use std::collections::VecDeque;
+use std::ops::Deref;
+
+#[derive(Accounts)]
+pub struct QuoteSwap<'info> {
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The program account of the pool to quote
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The program account for the most recent oracle observation
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+    // remaining accounts
+    // tickarray_bitmap_extension: must add account if need regardless the sequence
+    // tick_array_account_1
+    // tick_array_account_2
+    // tick_array_account_...
+}
+
+/// The result of a quote, returned through return data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SwapQuote {
+    /// The amount of input token the pool receives, fee included
+    pub amount_in: u64,
+    /// The amount of output token the pool pays out
+    pub amount_out: u64,
+    /// The fee paid in input token, including protocol and fund fee
+    pub fee_amount: u64,
+    /// The trade fee rate of the last swap step
+    pub trade_fee_rate: u32,
+    /// The pool price after the swap
+    pub sqrt_price_x64: u128,
+    /// The pool tick after the swap
+    pub tick: i32,
+    /// The number of initialized ticks crossed
+    pub ticks_crossed: u32,
+    /// The number of tick array accounts consumed, including skipped ones
+    pub tick_arrays_consumed: u32,
+}
+
+/// Runs a swap against heap copies of the pool and observation, the tick arrays are borrowed
+/// read-only and the ticks the swap writes are kept aside. Nothing is written back.
+/// Amounts are the pool side amounts, Token-2022 transfer fees are not included.
+pub fn quote_swap<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, QuoteSwap<'info>>,
+    amount_specified: u64,
+    sqrt_price_limit_x64: u128,
+    zero_for_one: bool,
+    is_base_input: bool,
+) -> Result<SwapQuote> {
+    quote_swap_internal(
+        ctx.accounts,
+        ctx.remaining_accounts,
+        amount_specified,
+        sqrt_price_limit_x64,
+        zero_for_one,
+        is_base_input,
+    )
+}
+
+fn quote_swap_internal<'c: 'info, 'info>(
+    accounts: &QuoteSwap<'info>,
+    remaining_accounts: &'c [AccountInfo<'info>],
+    amount_specified: u64,
+    sqrt_price_limit_x64: u128,
+    zero_for_one: bool,
+    is_base_input: bool,
+) -> Result<SwapQuote> {
+    let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
+
+    let pool_state_copy =
+        AccountLoad::<PoolState>::load_data_copy(&accounts.pool_state.to_account_info())?;
+    let observation_state_copy = AccountLoad::<ObservationState>::load_data_copy(
+        &accounts.observation_state.to_account_info(),
+    )?;
+    let pool_state = &mut AccountLoad::<PoolState>::load_copy_mut(&pool_state_copy);
+    pool_state.check_open(block_timestamp)?;
+    // The oracle is not part of a quote. Restart the copied ring so that its update
+    // stays in the fixed part of the account even if the account has been grown.
+    let observation_state =
+        &mut AccountLoad::<ObservationState>::load_copy_mut(&observation_state_copy);
+    observation_state.initialized = false;
+    observation_state.observation_index = 0;
+
+    let mut tickarray_bitmap_extension = None;
+    let tick_array_states = &mut VecDeque::new();
+    let tick_array_bitmap_extension_key = TickArrayBitmapExtension::key(pool_state.key());
+    for account_info in remaining_accounts.iter() {
+        if account_info.key().eq(&tick_array_bitmap_extension_key) {
+            tickarray_bitmap_extension = Some(
+                *(AccountLoader::<TickArrayBitmapExtension>::try_from(account_info)?
+                    .load()?
+                    .deref()),
+            );
+            continue;
+        }
+        tick_array_states.push_back(TickArrayOverlay::new(
+            AccountLoad::<TickArrayState>::load_data(account_info)?,
+        ));
+    }
+    let tick_array_count = tick_array_states.len();
+
+    let summary = swap_internal_with_summary(
+        &accounts.amm_config,
+        pool_state,
+        tick_array_states,
+        observation_state,
+        &mut [],
+        &tickarray_bitmap_extension,
+        amount_specified,
+        if sqrt_price_limit_x64 == 0 {
+            if zero_for_one {
+                tick_math::MIN_SQRT_PRICE_X64 + 1
+            } else {
+                tick_math::MAX_SQRT_PRICE_X64 - 1
+            }
+        } else {
+            sqrt_price_limit_x64
+        },
+        None,
+        zero_for_one,
+        is_base_input,
+        oracle::block_timestamp(),
+    )?;
+
+    let (amount_in, amount_out) = if zero_for_one {
+        (summary.amount_0, summary.amount_1)
+    } else {
+        (summary.amount_1, summary.amount_0)
+    };
+    Ok(SwapQuote {
+        amount_in,
+        amount_out,
+        fee_amount: summary.fee_amount,
+        trade_fee_rate: summary.trade_fee_rate,
+        sqrt_price_x64: pool_state.sqrt_price_x64,
+        tick: pool_state.tick_current,
+        ticks_crossed: summary.ticks_crossed,
+        tick_arrays_consumed: (tick_array_count - tick_array_states.len()) as u32,
+    })
+}
+
+#[cfg(test)]
+mod quote_swap_test {
+    use super::*;
+    use crate::states::pool_test::{build_pool, build_pool_account_info};
+    use crate::states::tick_array_test::{
+        build_tick, build_tick_array_account_info, build_tick_array_with_tick_states,
+    };
+    use crate::util::{build_account_info, set_clock_sysvar_stub};
+    use anchor_lang::Discriminator;
+    use std::cell::RefCell;
+
+    const TICK_SPACING: u16 = 10;
+    const LIQUIDITY: u128 = 1_000_000_000;
+
+    #[test]
+    fn quote_across_tick_arrays_test() {
+        set_clock_sysvar_stub();
+        let amm_config = AmmConfig {
+            trade_fee_rate: 1000,
+            tick_spacing: TICK_SPACING,
+            ..Default::default()
+        };
+        let pool_state_ref = build_pool(
+            300,
+            TICK_SPACING,
+            tick_math::get_sqrt_price_at_tick(300).unwrap(),
+            LIQUIDITY,
+        );
+        let pool_id = {
+            let mut pool_state = pool_state_ref.borrow_mut();
+            pool_state.fee_growth_global_0_x64 = 0;
+            pool_state.fee_growth_global_1_x64 = 0;
+            pool_state.key()
+        };
+        // the positions [-1100, 500] and [-500, 100], the ticks of a swap down to -800 are in
+        // three tick arrays
+        let tick_array_refs = [
+            build_tick_array_with_tick_states(
+                pool_id,
+                0,
+                TICK_SPACING,
+                vec![
+                    *build_tick(100, LIQUIDITY, -(LIQUIDITY as i128)).borrow(),
+                    *build_tick(500, LIQUIDITY, -(LIQUIDITY as i128)).borrow(),
+                ],
+            ),
+            build_tick_array_with_tick_states(
+                pool_id,
+                -600,
+                TICK_SPACING,
+                vec![*build_tick(-500, LIQUIDITY, LIQUIDITY as i128).borrow()],
+            ),
+            build_tick_array_with_tick_states(
+                pool_id,
+                -1200,
+                TICK_SPACING,
+                vec![*build_tick(-1100, LIQUIDITY, LIQUIDITY as i128).borrow()],
+            ),
+        ];
+        for start_index in [0, -600, -1200] {
+            pool_state_ref
+                .borrow_mut()
+                .flip_tick_array_bit(None, start_index)
+                .unwrap();
+        }
+        let observation_state_ref = RefCell::new(ObservationState {
+            pool_id,
+            ..Default::default()
+        });
+
+        let mut amm_config_data = Vec::new();
+        amm_config.try_serialize(&mut amm_config_data).unwrap();
+        let mut observation_data = ObservationState::discriminator().to_vec();
+        observation_data.extend_from_slice(bytemuck::bytes_of(&*observation_state_ref.borrow()));
+        let accounts = QuoteSwap {
+            amm_config: Box::new(
+                Account::try_from(build_account_info(Pubkey::new_unique(), amm_config_data))
+                    .unwrap(),
+            ),
+            pool_state: AccountLoader::try_from(build_pool_account_info(&pool_state_ref.borrow()))
+                .unwrap(),
+            observation_state: AccountLoader::try_from(build_account_info(
+                Pubkey::new_unique(),
+                observation_data,
+            ))
+            .unwrap(),
+        };
+        // the tick arrays are not writable, a quote only reads them
+        let tick_array_infos: &'static [AccountInfo<'static>] = tick_array_refs
+            .iter()
+            .map(|tick_array_ref| {
+                let mut account_info =
+                    build_tick_array_account_info(&tick_array_ref.borrow()).clone();
+                account_info.is_writable = false;
+                account_info
+            })
+            .collect::<Vec<_>>()
+            .leak();
+        let tick_array_data = tick_array_infos
+            .iter()
+            .map(|account_info| account_info.data.borrow().to_vec())
+            .collect::<Vec<_>>();
+
+        let amount_specified = 1_000_000_000_000;
+        let sqrt_price_limit_x64 = tick_math::get_sqrt_price_at_tick(-800).unwrap();
+        let quote = quote_swap_internal(
+            &accounts,
+            tick_array_infos,
+            amount_specified,
+            sqrt_price_limit_x64,
+            true,
+            true,
+        )
+        .unwrap();
+        assert_eq!(quote.ticks_crossed, 2);
+        assert_eq!(quote.tick_arrays_consumed, 3);
+        assert_eq!(quote.sqrt_price_x64, sqrt_price_limit_x64);
+        assert_eq!(quote.tick, -800);
+        for (account_info, data) in tick_array_infos.iter().zip(tick_array_data) {
+            assert_eq!(*account_info.data.borrow(), &data[..]);
+        }
+
+        // the quote matches the swap
+        let summary = swap_internal_with_summary(
+            &amm_config,
+            &mut pool_state_ref.borrow_mut(),
+            &mut tick_array_refs
+                .iter()
+                .map(|tick_array_ref| tick_array_ref.borrow_mut())
+                .collect(),
+            &mut observation_state_ref.borrow_mut(),
+            &mut [],
+            &None,
+            amount_specified,
+            sqrt_price_limit_x64,
+            None,
+            true,
+            true,
+            oracle::block_timestamp(),
+        )
+        .unwrap();
+        assert_eq!(quote.amount_in, summary.amount_0);
+        assert_eq!(quote.amount_out, summary.amount_1);
+        assert_eq!(quote.fee_amount, summary.fee_amount);
+        assert_eq!(quote.ticks_crossed, summary.ticks_crossed);
+        let pool_state = pool_state_ref.borrow();
+        assert_eq!(quote.sqrt_price_x64, { pool_state.sqrt_price_x64 });
+        assert_eq!(quote.tick, { pool_state.tick_current });
+    }
+}
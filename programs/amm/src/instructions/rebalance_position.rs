@@ -0,0 +1,777 @@
+use super::check_unclaimed_fees_and_vault;
+use super::decrease_liquidity_and_update_position;
+use super::mint_liquidity;
+use crate::error::ErrorCode;
+use crate::libraries::{liquidity_math, tick_math};
+use crate::states::*;
+use crate::swap::swap_internal_with_summary;
+use crate::util::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+use std::cell::RefMut;
+use std::collections::VecDeque;
+use std::ops::Deref;
+
+#[derive(Accounts)]
+#[instruction(tick_lower_index: i32, tick_upper_index: i32, tick_array_lower_start_index: i32, tick_array_upper_start_index: i32)]
+pub struct RebalancePosition<'info> {
+    /// The position NFT holder or a delegate approved to rebalance, pays for the accounts of the new range
+    #[account(mut)]
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint,
+        constraint = nft_account.amount == 1,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Rebalance this position
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// The protocol position of the current range
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Stores init state for the lower tick of the current range
+    #[account(mut, constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick of the current range
+    #[account(mut, constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+
+    /// The protocol position of the new range
+    #[account(
+        init_if_needed,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_lower_index.to_be_bytes(),
+            &tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        payer = nft_owner,
+        space = ProtocolPositionState::LEN
+    )]
+    pub new_protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// CHECK: Account to store data for the lower tick of the new range
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_array_lower_start_index.to_be_bytes(),
+        ],
+        bump,
+    )]
+    pub new_tick_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Account to store data for the upper tick of the new range
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_array_upper_start_index.to_be_bytes(),
+        ],
+        bump,
+    )]
+    pub new_tick_array_upper: UncheckedAccount<'info>,
+
+    /// Token_0 vault
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_1 vault
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The destination token account for the fees and the token_0 left over
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The destination token account for the fees and the token_1 left over
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Program to create the new protocol position and tick arrays
+    pub system_program: Program<'info, System>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+    // remaining account
+    // #[account(
+    //     seeds = [
+    //         POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
+    //         pool_state.key().as_ref(),
+    //     ],
+    //     bump
+    // )]
+    // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+    // and the tick arrays to swap through when `swap_amount` is not zero
+}
+
+pub fn rebalance_position<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, RebalancePosition<'info>>,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    tick_array_lower_start_index: i32,
+    tick_array_upper_start_index: i32,
+    amount_0_min: u64,
+    amount_1_min: u64,
+    swap_amount: u64,
+    zero_for_one: bool,
+    other_amount_threshold: u64,
+) -> Result<()> {
+    let recipient_owner = ctx.accounts.personal_position.check_authority(
+        ctx.accounts.nft_owner.key(),
+        ctx.accounts.nft_account.owner,
+        PositionPermissionBitIndex::Rebalance,
+    )?;
+    check_recipient_owner(
+        recipient_owner,
+        ctx.accounts.recipient_token_account_0.owner,
+    )?;
+    check_recipient_owner(
+        recipient_owner,
+        ctx.accounts.recipient_token_account_1.owner,
+    )?;
+    let personal_position = &mut ctx.accounts.personal_position;
+    let tick_lower_index_before = personal_position.tick_lower_index;
+    let tick_upper_index_before = personal_position.tick_upper_index;
+    let liquidity_before = personal_position.liquidity;
+    require!(
+        tick_lower_index != tick_lower_index_before || tick_upper_index != tick_upper_index_before,
+        ErrorCode::InvalidRebalanceRange
+    );
+    require!(
+        !personal_position.is_limit_order(),
+        ErrorCode::LimitOrderNotRebalanceable
+    );
+
+    let mut tickarray_bitmap_extension = None;
+    let mut swap_tick_array_accounts = Vec::new();
+    {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        if !pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity)?
+            || !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)?
+        {
+            return err!(ErrorCode::NotApproved);
+        }
+        check_pool_access(
+            &pool_state,
+            ctx.accounts.pool_state.key(),
+            ctx.remaining_accounts,
+            ctx.accounts.nft_owner.key(),
+            PoolAccessBitIndex::Liquidity,
+        )?;
+        check_ticks_order(tick_lower_index, tick_upper_index)?;
+        check_tick_array_start_index(
+            tick_array_lower_start_index,
+            tick_lower_index,
+            pool_state.tick_spacing,
+        )?;
+        check_tick_array_start_index(
+            tick_array_upper_start_index,
+            tick_upper_index,
+            pool_state.tick_spacing,
+        )?;
+        for account_info in ctx.remaining_accounts.iter() {
+            if PoolAllowListState::is_allow_list_account(account_info)
+                || LimitOrderFillState::is_limit_order_fill_account(account_info)
+            {
+                continue;
+            }
+            if account_info
+                .key()
+                .eq(&TickArrayBitmapExtension::key(pool_state.key()))
+            {
+                tickarray_bitmap_extension = Some(account_info);
+                continue;
+            }
+            swap_tick_array_accounts.push(account_info);
+        }
+        if pool_state.is_overflow_default_tickarray_bitmap(vec![
+            ctx.accounts.tick_array_lower.load()?.start_tick_index,
+            ctx.accounts.tick_array_upper.load()?.start_tick_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+        ]) {
+            require!(
+                tickarray_bitmap_extension.is_some(),
+                ErrorCode::MissingTickArrayBitmapExtensionAccount
+            );
+        }
+    }
+
+    // withdraw all liquidity from the current range, the tokens stay in the vaults
+    let (decrease_amount_0, fees_owed_0, decrease_amount_1, fees_owed_1) =
+        decrease_liquidity_and_update_position(
+            &ctx.accounts.pool_state,
+            &mut ctx.accounts.protocol_position,
+            personal_position,
+            &ctx.accounts.tick_array_lower,
+            &ctx.accounts.tick_array_upper,
+            tickarray_bitmap_extension,
+            ctx.remaining_accounts,
+            liquidity_before,
+        )?;
+    require_gte!(
+        decrease_amount_0,
+        amount_0_min,
+        ErrorCode::PriceSlippageCheck
+    );
+    require_gte!(
+        decrease_amount_1,
+        amount_1_min,
+        ErrorCode::PriceSlippageCheck
+    );
+    let mut amount_0 = decrease_amount_0;
+    let mut amount_1 = decrease_amount_1;
+
+    // swap the imbalance through the pool, the tokens stay in the vaults
+    let mut swap_amount_0 = 0;
+    let mut swap_amount_1 = 0;
+    if swap_amount > 0 {
+        let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+        pool_state.check_open(Clock::get()?.unix_timestamp as u64)?;
+        // the swap hook is only called by the swap instructions
+        require!(!pool_state.has_swap_hook(), ErrorCode::SwapHookNotSupported);
+        check_pool_access(
+            pool_state,
+            ctx.accounts.pool_state.key(),
+            ctx.remaining_accounts,
+            ctx.accounts.nft_owner.key(),
+            PoolAccessBitIndex::Swap,
+        )?;
+        let mut tick_array_states = VecDeque::new();
+        for account_info in swap_tick_array_accounts.into_iter() {
+            tick_array_states.push_back(AccountLoad::load_data_mut(account_info)?);
+        }
+        let swap_bitmap_extension = match tickarray_bitmap_extension {
+            Some(account_info) => Some(
+                *(AccountLoader::<TickArrayBitmapExtension>::try_from(account_info)?
+                    .load()?
+                    .deref()),
+            ),
+            None => None,
+        };
+        let (mut observation_state, mut observation_extension) =
+            load_observations_mut(&ctx.accounts.observation_state)?;
+        let summary = swap_internal_with_summary(
+            &ctx.accounts.amm_config,
+            pool_state,
+            &mut tick_array_states,
+            &mut observation_state,
+            &mut observation_extension,
+            &swap_bitmap_extension,
+            swap_amount,
+            if zero_for_one {
+                tick_math::MIN_SQRT_PRICE_X64 + 1
+            } else {
+                tick_math::MAX_SQRT_PRICE_X64 - 1
+            },
+            None,
+            zero_for_one,
+            true,
+            oracle::block_timestamp(),
+        )?;
+        drop(tick_array_states);
+        record_limit_order_fills(
+            ctx.accounts.pool_state.key(),
+            &summary.limit_order_fills,
+            ctx.remaining_accounts,
+        )?;
+        (swap_amount_0, swap_amount_1) = (summary.amount_0, summary.amount_1);
+        emit!(SwapEvent {
+            pool_state: ctx.accounts.pool_state.key(),
+            sender: ctx.accounts.nft_owner.key(),
+            token_account_0: ctx.accounts.recipient_token_account_0.key(),
+            token_account_1: ctx.accounts.recipient_token_account_1.key(),
+            amount_0: swap_amount_0,
+            transfer_fee_0: 0,
+            amount_1: swap_amount_1,
+            transfer_fee_1: 0,
+            zero_for_one,
+            sqrt_price_x64: pool_state.sqrt_price_x64,
+            liquidity: pool_state.liquidity,
+            tick: pool_state.tick_current,
+            trade_fee_rate: summary.trade_fee_rate,
+        });
+        if zero_for_one {
+            require!(
+                swap_amount_1 >= other_amount_threshold,
+                ErrorCode::TooLittleOutputReceived
+            );
+            amount_0 = amount_0.checked_sub(swap_amount_0).unwrap();
+            amount_1 = amount_1.checked_add(swap_amount_1).unwrap();
+        } else {
+            require!(
+                swap_amount_0 >= other_amount_threshold,
+                ErrorCode::TooLittleOutputReceived
+            );
+            amount_0 = amount_0.checked_add(swap_amount_0).unwrap();
+            amount_1 = amount_1.checked_sub(swap_amount_1).unwrap();
+        }
+    }
+
+    // deposit as much as possible into the new range
+    let liquidity;
+    let (deposit_amount_0, deposit_amount_1) = {
+        let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+        let tick_array_lower_loader = TickArrayState::get_or_create_tick_array(
+            ctx.accounts.nft_owner.to_account_info(),
+            ctx.accounts.new_tick_array_lower.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.pool_state,
+            tick_array_lower_start_index,
+            pool_state.tick_spacing,
+        )?;
+        let tick_array_upper_loader =
+            if tick_array_lower_start_index == tick_array_upper_start_index {
+                AccountLoad::<TickArrayState>::try_from(
+                    &ctx.accounts.new_tick_array_upper.to_account_info(),
+                )?
+            } else {
+                TickArrayState::get_or_create_tick_array(
+                    ctx.accounts.nft_owner.to_account_info(),
+                    ctx.accounts.new_tick_array_upper.to_account_info(),
+                    ctx.accounts.system_program.to_account_info(),
+                    &ctx.accounts.pool_state,
+                    tick_array_upper_start_index,
+                    pool_state.tick_spacing,
+                )?
+            };
+
+        let new_protocol_position = &mut ctx.accounts.new_protocol_position;
+        if new_protocol_position.pool_id == Pubkey::default() {
+            new_protocol_position.bump = ctx.bumps.new_protocol_position;
+            new_protocol_position.pool_id = ctx.accounts.pool_state.key();
+            new_protocol_position.tick_lower_index = tick_lower_index;
+            new_protocol_position.tick_upper_index = tick_upper_index;
+            tick_array_lower_loader
+                .load_mut()?
+                .get_tick_state_mut(tick_lower_index, pool_state.tick_spacing)?
+                .tick = tick_lower_index;
+            tick_array_upper_loader
+                .load_mut()?
+                .get_tick_state_mut(tick_upper_index, pool_state.tick_spacing)?
+                .tick = tick_upper_index;
+        }
+
+        let (new_liquidity, deposit_amount_0, deposit_amount_1) = move_position_to_range(
+            pool_state,
+            &tick_array_lower_loader,
+            &tick_array_upper_loader,
+            new_protocol_position,
+            personal_position,
+            tickarray_bitmap_extension,
+            tick_lower_index,
+            tick_upper_index,
+            amount_0,
+            amount_1,
+        )?;
+        liquidity = new_liquidity;
+        (deposit_amount_0, deposit_amount_1)
+    };
+
+    // the fees and the tokens not deposited are sent to the recipient
+    let refund_amount_0 = amount_0 - deposit_amount_0 + fees_owed_0;
+    let refund_amount_1 = amount_1 - deposit_amount_1 + fees_owed_1;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.recipient_token_account_0.to_account_info(),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        refund_amount_0,
+    )?;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.recipient_token_account_1.to_account_info(),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        refund_amount_1,
+    )?;
+    check_unclaimed_fees_and_vault(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+    )?;
+
+    emit!(RebalancePositionEvent {
+        position_nft_mint: personal_position.nft_mint,
+        tick_lower_index_before,
+        tick_upper_index_before,
+        liquidity_before,
+        tick_lower_index,
+        tick_upper_index,
+        liquidity,
+        swap_amount_0,
+        swap_amount_1,
+        refund_amount_0,
+        refund_amount_1,
+    });
+
+    Ok(())
+}
+
+/// Mints as much liquidity as the amounts allow into the new range and moves the position to it,
+/// the fees and rewards owed are kept. Returns the liquidity and the amounts deposited
+pub fn move_position_to_range<'c: 'info, 'info>(
+    pool_state: &mut RefMut<PoolState>,
+    tick_array_lower_loader: &AccountLoad<'info, TickArrayState>,
+    tick_array_upper_loader: &AccountLoad<'info, TickArrayState>,
+    new_protocol_position: &mut ProtocolPositionState,
+    personal_position: &mut PersonalPositionState,
+    tick_array_bitmap_extension: Option<&'c AccountInfo<'info>>,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    amount_0: u64,
+    amount_1: u64,
+) -> Result<(u128, u64, u64)> {
+    let liquidity = liquidity_math::get_liquidity_from_amounts(
+        pool_state.sqrt_price_x64,
+        tick_math::get_sqrt_price_at_tick(tick_lower_index)?,
+        tick_math::get_sqrt_price_at_tick(tick_upper_index)?,
+        amount_0,
+        amount_1,
+    );
+    let (deposit_amount_0, deposit_amount_1) = mint_liquidity(
+        pool_state,
+        tick_array_lower_loader,
+        tick_array_upper_loader,
+        new_protocol_position,
+        tick_array_bitmap_extension,
+        liquidity,
+        tick_lower_index,
+        tick_upper_index,
+    )?;
+    require_gte!(amount_0, deposit_amount_0, ErrorCode::PriceSlippageCheck);
+    require_gte!(amount_1, deposit_amount_1, ErrorCode::PriceSlippageCheck);
+
+    personal_position.tick_lower_index = tick_lower_index;
+    personal_position.tick_upper_index = tick_upper_index;
+    personal_position.fee_growth_inside_0_last_x64 =
+        new_protocol_position.fee_growth_inside_0_last_x64;
+    personal_position.fee_growth_inside_1_last_x64 =
+        new_protocol_position.fee_growth_inside_1_last_x64;
+    // the rewards owed are kept, only the growth is moved to the new range
+    personal_position.update_rewards(new_protocol_position.reward_growth_inside, false)?;
+    personal_position.liquidity = liquidity;
+    Ok((liquidity, deposit_amount_0, deposit_amount_1))
+}
+
+#[cfg(test)]
+mod rebalance_position_test {
+    use super::*;
+    use crate::states::pool_test::{build_pool, build_pool_account_info};
+    use crate::states::tick_array_test::{
+        build_tick_array_account_info, build_tick_array_with_tick_states,
+    };
+    use crate::util::{build_account_info, set_clock_sysvar_stub};
+
+    const TICK_SPACING: u16 = 10;
+    const TICK_CURRENT: i32 = 300;
+    const TICK_LOWER: i32 = 100;
+    const TICK_UPPER: i32 = 500;
+    const LIQUIDITY: u128 = 1_000_000;
+    const FEES_OWED: u64 = 1_000;
+    const REWARD_OWED: u64 = 50;
+
+    fn build_account<T>(account: &T) -> Box<Account<'static, T>>
+    where
+        T: AccountSerialize + AccountDeserialize + Owner + Clone,
+    {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        Box::new(Account::try_from(build_account_info(Pubkey::new_unique(), data)).unwrap())
+    }
+
+    type PositionAccounts = (
+        AccountLoader<'static, PoolState>,
+        Box<Account<'static, ProtocolPositionState>>,
+        Box<Account<'static, PersonalPositionState>>,
+        &'static AccountInfo<'static>,
+    );
+
+    struct Rebalanced {
+        pool_state: AccountLoader<'static, PoolState>,
+        protocol_position: Box<Account<'static, ProtocolPositionState>>,
+        new_protocol_position: ProtocolPositionState,
+        personal_position: Box<Account<'static, PersonalPositionState>>,
+        decrease_amount_0: u64,
+        fees_owed_0: u64,
+        decrease_amount_1: u64,
+        fees_owed_1: u64,
+        liquidity: u128,
+        deposit_amount_0: u64,
+        deposit_amount_1: u64,
+    }
+
+    /// Opens a position of `LIQUIDITY` in `[TICK_LOWER, TICK_UPPER]` of a pool at `TICK_CURRENT`,
+    /// which is owed `FEES_OWED` of both tokens and `REWARD_OWED` of the first reward
+    fn build_position() -> PositionAccounts {
+        set_clock_sysvar_stub();
+        let pool_state_ref = build_pool(
+            TICK_CURRENT,
+            TICK_SPACING,
+            tick_math::get_sqrt_price_at_tick(TICK_CURRENT).unwrap(),
+            0,
+        );
+        let mut pool_state = pool_state_ref.borrow_mut();
+        pool_state.fee_growth_global_0_x64 = 0;
+        pool_state.fee_growth_global_1_x64 = 0;
+        pool_state.total_fees_token_0 = FEES_OWED;
+        pool_state.total_fees_token_1 = FEES_OWED;
+        let tick_array_info = build_tick_array_account_info(
+            &build_tick_array_with_tick_states(
+                pool_state.key(),
+                TickArrayState::get_array_start_index(TICK_LOWER, TICK_SPACING),
+                TICK_SPACING,
+                vec![],
+            )
+            .borrow(),
+        );
+        let tick_array = AccountLoad::<TickArrayState>::try_from(tick_array_info).unwrap();
+        let mut protocol_position = ProtocolPositionState::default();
+        mint_liquidity(
+            &mut pool_state,
+            &tick_array,
+            &tick_array,
+            &mut protocol_position,
+            None,
+            LIQUIDITY,
+            TICK_LOWER,
+            TICK_UPPER,
+        )
+        .unwrap();
+        let mut personal_position = PersonalPositionState {
+            tick_lower_index: TICK_LOWER,
+            tick_upper_index: TICK_UPPER,
+            liquidity: LIQUIDITY,
+            fee_growth_inside_0_last_x64: protocol_position.fee_growth_inside_0_last_x64,
+            fee_growth_inside_1_last_x64: protocol_position.fee_growth_inside_1_last_x64,
+            token_fees_owed_0: FEES_OWED,
+            token_fees_owed_1: FEES_OWED,
+            ..Default::default()
+        };
+        personal_position.reward_infos[0].reward_amount_owed = REWARD_OWED;
+        (
+            AccountLoader::try_from(build_pool_account_info(&pool_state)).unwrap(),
+            build_account(&protocol_position),
+            build_account(&personal_position),
+            tick_array_info,
+        )
+    }
+
+    /// Withdraws all the liquidity of the position and deposits it into `[tick_lower, tick_upper]`
+    fn rebalance(tick_lower: i32, tick_upper: i32) -> Rebalanced {
+        let (pool_state_loader, mut protocol_position, mut personal_position, tick_array_info) =
+            build_position();
+        let tick_array = AccountLoader::<TickArrayState>::try_from(tick_array_info).unwrap();
+        let (decrease_amount_0, fees_owed_0, decrease_amount_1, fees_owed_1) =
+            decrease_liquidity_and_update_position(
+                &pool_state_loader,
+                &mut protocol_position,
+                &mut personal_position,
+                &tick_array,
+                &tick_array,
+                None,
+                &[],
+                LIQUIDITY,
+            )
+            .unwrap();
+
+        let tick_array = AccountLoad::<TickArrayState>::try_from(tick_array_info).unwrap();
+        let mut new_protocol_position = ProtocolPositionState::default();
+        let (liquidity, deposit_amount_0, deposit_amount_1) = move_position_to_range(
+            &mut pool_state_loader.load_mut().unwrap(),
+            &tick_array,
+            &tick_array,
+            &mut new_protocol_position,
+            &mut personal_position,
+            None,
+            tick_lower,
+            tick_upper,
+            decrease_amount_0,
+            decrease_amount_1,
+        )
+        .unwrap();
+        Rebalanced {
+            pool_state: pool_state_loader,
+            protocol_position,
+            new_protocol_position,
+            personal_position,
+            decrease_amount_0,
+            fees_owed_0,
+            decrease_amount_1,
+            fees_owed_1,
+            liquidity,
+            deposit_amount_0,
+            deposit_amount_1,
+        }
+    }
+
+    #[test]
+    fn rebalance_position_in_range_test() {
+        let Rebalanced {
+            pool_state,
+            protocol_position,
+            new_protocol_position,
+            personal_position,
+            decrease_amount_0,
+            fees_owed_0,
+            decrease_amount_1,
+            fees_owed_1,
+            liquidity,
+            deposit_amount_0,
+            deposit_amount_1,
+        } = rebalance(200, 400);
+        assert!(decrease_amount_0 > 0 && decrease_amount_1 > 0);
+        assert_eq!((fees_owed_0, fees_owed_1), (FEES_OWED, FEES_OWED));
+
+        // the narrower range concentrates the withdrawn amounts
+        assert!(liquidity > LIQUIDITY);
+        assert!(deposit_amount_0 <= decrease_amount_0 && deposit_amount_1 <= decrease_amount_1);
+        assert!(
+            decrease_amount_0 - deposit_amount_0 <= 1 || decrease_amount_1 - deposit_amount_1 <= 1
+        );
+        assert_eq!(protocol_position.liquidity, 0);
+        assert_eq!(new_protocol_position.liquidity, liquidity);
+        assert_eq!({ pool_state.load().unwrap().liquidity }, liquidity);
+
+        assert_eq!(personal_position.tick_lower_index, 200);
+        assert_eq!(personal_position.tick_upper_index, 400);
+        assert_eq!(personal_position.liquidity, liquidity);
+        assert_eq!(personal_position.token_fees_owed_0, 0);
+        assert_eq!(personal_position.token_fees_owed_1, 0);
+        assert_eq!(
+            personal_position.reward_infos[0].reward_amount_owed,
+            REWARD_OWED
+        );
+        assert_eq!(
+            personal_position.fee_growth_inside_0_last_x64,
+            new_protocol_position.fee_growth_inside_0_last_x64
+        );
+    }
+
+    #[test]
+    fn rebalance_position_above_price_test() {
+        let Rebalanced {
+            pool_state,
+            new_protocol_position,
+            personal_position,
+            decrease_amount_0,
+            decrease_amount_1,
+            liquidity,
+            deposit_amount_0,
+            deposit_amount_1,
+            ..
+        } = rebalance(400, 500);
+        // only token_0 is deposited above the price, all of token_1 is refunded
+        assert!(liquidity > 0);
+        assert!(deposit_amount_0 > 0 && deposit_amount_0 <= decrease_amount_0);
+        assert_eq!(deposit_amount_1, 0);
+        assert!(decrease_amount_1 > 0);
+        assert_eq!(new_protocol_position.liquidity, liquidity);
+        // the new range is out of range, the pool has no active liquidity left
+        assert_eq!({ pool_state.load().unwrap().liquidity }, 0);
+        assert_eq!(personal_position.tick_lower_index, 400);
+        assert_eq!(personal_position.liquidity, liquidity);
+    }
+
+    #[test]
+    fn rebalance_locked_position_test() {
+        let (pool_state_loader, mut protocol_position, mut personal_position, tick_array_info) =
+            build_position();
+        let block_timestamp = Clock::get().unwrap().unix_timestamp as u64;
+        personal_position
+            .lock(block_timestamp + 3_600, block_timestamp)
+            .unwrap();
+        let tick_array = AccountLoader::<TickArrayState>::try_from(tick_array_info).unwrap();
+        // the liquidity of a locked position can not be withdrawn to the new range
+        assert!(decrease_liquidity_and_update_position(
+            &pool_state_loader,
+            &mut protocol_position,
+            &mut personal_position,
+            &tick_array,
+            &tick_array,
+            None,
+            &[],
+            LIQUIDITY,
+        )
+        .is_err());
+        assert_eq!(personal_position.liquidity, LIQUIDITY);
+        assert_eq!(protocol_position.liquidity, LIQUIDITY);
+
+        // once expired the lock is released and the liquidity can be moved
+        personal_position.unlock(block_timestamp + 3_600).unwrap();
+        decrease_liquidity_and_update_position(
+            &pool_state_loader,
+            &mut protocol_position,
+            &mut personal_position,
+            &tick_array,
+            &tick_array,
+            None,
+            &[],
+            LIQUIDITY,
+        )
+        .unwrap();
+        assert_eq!(personal_position.liquidity, 0);
+    }
+}
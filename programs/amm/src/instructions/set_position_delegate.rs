@@ -0,0 +1,49 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct SetPositionDelegate<'info> {
+    /// The position NFT holder
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint,
+        constraint = nft_account.amount == 1,
+        token::authority = nft_owner,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Set the delegate of this position
+    #[account(mut)]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+}
+
+pub fn set_position_delegate(
+    ctx: Context<SetPositionDelegate>,
+    delegate: Pubkey,
+    permissions: u8,
+) -> Result<()> {
+    require!(
+        permissions & !POSITION_PERMISSION_MASK == 0,
+        ErrorCode::InvalidDelegatePermissions
+    );
+    let personal_position = &mut ctx.accounts.personal_position;
+    // the delegation is bound to the current holder and lapses when the NFT is transferred
+    personal_position.set_delegate(delegate, ctx.accounts.nft_owner.key(), permissions);
+
+    emit!(SetPositionDelegateEvent {
+        position_nft_mint: personal_position.nft_mint,
+        delegate: if personal_position.delegate_permissions == 0 {
+            Pubkey::default()
+        } else {
+            delegate
+        },
+        nft_owner: ctx.accounts.nft_owner.key(),
+        permissions: personal_position.delegate_permissions,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SnapshotCumulativesInside<'info> {
+    /// The program account of the pool
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The tick array holding the lower tick
+    #[account(constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// The tick array holding the upper tick
+    #[account(constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+}
+
+/// The cumulatives of a tick range, returned through return data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CumulativesInside {
+    /// The seconds per in range liquidity inside the range, as a Q64.64
+    pub seconds_per_liquidity_inside_x64: u128,
+    /// The seconds the price spent inside the range
+    pub seconds_inside: u32,
+}
+
+/// Returns a snapshot of the seconds per liquidity and the seconds spent inside a tick range.
+/// Snapshots are only comparable with other snapshots of the same range taken while a position
+/// exists on it, i.e. both ticks stay initialized.
+pub fn snapshot_cumulatives_inside(
+    ctx: Context<SnapshotCumulativesInside>,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+) -> Result<CumulativesInside> {
+    check_ticks_order(tick_lower_index, tick_upper_index)?;
+    let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
+
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let tick_array_lower = ctx.accounts.tick_array_lower.load()?;
+    let tick_lower_state =
+        tick_array_lower.get_tick_state(tick_lower_index, pool_state.tick_spacing)?;
+    let tick_array_upper = ctx.accounts.tick_array_upper.load()?;
+    let tick_upper_state =
+        tick_array_upper.get_tick_state(tick_upper_index, pool_state.tick_spacing)?;
+    require!(
+        tick_lower_state.is_initialized() && tick_upper_state.is_initialized(),
+        ErrorCode::TickNotInitialized
+    );
+
+    let (seconds_per_liquidity_inside_x64, seconds_inside) = get_seconds_inside(
+        tick_lower_state,
+        tick_upper_state,
+        pool_state.tick_current,
+        pool_state.seconds_per_liquidity_at(block_timestamp),
+        block_timestamp as u32,
+    );
+    Ok(CumulativesInside {
+        seconds_per_liquidity_inside_x64,
+        seconds_inside,
+    })
+}
@@ -0,0 +1,259 @@
+use super::open_position::{get_metadata_data, mint_nft_and_remove_mint_authority};
+use crate::error::ErrorCode;
+use crate::libraries::{big_num::U256, full_math::MulDiv};
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::Metadata;
+use anchor_spl::token::{self, Token};
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct SplitPosition<'info> {
+    /// The position owner, pays to mint the new position
+    #[account(mut)]
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint,
+        constraint = nft_account.amount == 1,
+        token::authority = nft_owner,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Split liquidity from this position
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// The pool of the position, mint authority of the new position NFT
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// CHECK: Receives the new position NFT
+    pub new_position_nft_owner: UncheckedAccount<'info>,
+
+    /// Unique token mint address of the new position
+    #[account(
+        init,
+        mint::decimals = 0,
+        mint::authority = pool_state.key(),
+        payer = nft_owner,
+    )]
+    pub new_position_nft_mint: Box<Account<'info, token::Mint>>,
+
+    /// Token account where the new position NFT will be minted
+    #[account(
+        init,
+        associated_token::mint = new_position_nft_mint,
+        associated_token::authority = new_position_nft_owner,
+        payer = nft_owner,
+    )]
+    pub new_position_nft_account: Box<Account<'info, token::TokenAccount>>,
+
+    /// To store metaplex metadata
+    /// CHECK: Safety check performed inside function body
+    #[account(mut)]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    /// The new personal position state
+    #[account(
+        init,
+        seeds = [POSITION_SEED.as_bytes(), new_position_nft_mint.key().as_ref()],
+        bump,
+        payer = nft_owner,
+        space = PersonalPositionState::LEN
+    )]
+    pub new_personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// Sysvar for token mint and ATA creation
+    pub rent: Sysvar<'info, Rent>,
+
+    /// Program to create the position manager state account
+    pub system_program: Program<'info, System>,
+
+    /// Program to create mint account and mint tokens
+    pub token_program: Program<'info, Token>,
+
+    /// Program to create an ATA for receiving position NFT
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// Program to create NFT metadata
+    /// CHECK: Metadata program address constraint applied
+    pub metadata_program: Program<'info, Metadata>,
+}
+
+pub fn split_position<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SplitPosition<'info>>,
+    liquidity: u128,
+    with_metadata: bool,
+) -> Result<()> {
+    let personal_position = &mut ctx.accounts.personal_position;
+    let new_personal_position = &mut ctx.accounts.new_personal_position;
+    new_personal_position.bump = [ctx.bumps.new_personal_position];
+    new_personal_position.nft_mint = ctx.accounts.new_position_nft_mint.key();
+    split_personal_position(personal_position, new_personal_position, liquidity)?;
+
+    emit!(SplitPositionEvent {
+        position_nft_mint: personal_position.nft_mint,
+        new_position_nft_mint: new_personal_position.nft_mint,
+        new_position_nft_owner: ctx.accounts.new_position_nft_owner.key(),
+        liquidity,
+    });
+
+    mint_nft_and_remove_mint_authority(
+        &ctx.accounts.nft_owner,
+        &ctx.accounts.pool_state,
+        &new_personal_position.to_account_info(),
+        &ctx.accounts.new_position_nft_mint.to_account_info(),
+        &ctx.accounts.new_position_nft_account.to_account_info(),
+        Some(&ctx.accounts.metadata_account),
+        Some(&ctx.accounts.metadata_program),
+        &ctx.accounts.token_program,
+        None,
+        &ctx.accounts.system_program,
+        &ctx.accounts.rent,
+        with_metadata.then(|| get_metadata_data(new_personal_position.key())),
+        false,
+    )
+}
+
+/// Moves `liquidity` and its part of the amounts owed from `personal_position` to
+/// `new_personal_position`, which takes the same range, snapshots and lock
+pub fn split_personal_position(
+    personal_position: &mut PersonalPositionState,
+    new_personal_position: &mut PersonalPositionState,
+    liquidity: u128,
+) -> Result<()> {
+    let liquidity_before = personal_position.liquidity;
+    require!(
+        liquidity > 0 && liquidity < liquidity_before,
+        ErrorCode::InvalidSplitLiquidity
+    );
+
+    // the fee and reward growth snapshots are shared, so only the owed amounts are divided
+    new_personal_position.pool_id = personal_position.pool_id;
+    new_personal_position.tick_lower_index = personal_position.tick_lower_index;
+    new_personal_position.tick_upper_index = personal_position.tick_upper_index;
+    new_personal_position.liquidity = liquidity;
+    new_personal_position.fee_growth_inside_0_last_x64 =
+        personal_position.fee_growth_inside_0_last_x64;
+    new_personal_position.fee_growth_inside_1_last_x64 =
+        personal_position.fee_growth_inside_1_last_x64;
+    new_personal_position.token_fees_owed_0 = get_split_amount(
+        personal_position.token_fees_owed_0,
+        liquidity,
+        liquidity_before,
+    );
+    new_personal_position.token_fees_owed_1 = get_split_amount(
+        personal_position.token_fees_owed_1,
+        liquidity,
+        liquidity_before,
+    );
+    for i in 0..REWARD_NUM {
+        new_personal_position.reward_infos[i].growth_inside_last_x64 =
+            personal_position.reward_infos[i].growth_inside_last_x64;
+        new_personal_position.reward_infos[i].reward_amount_owed = get_split_amount(
+            personal_position.reward_infos[i].reward_amount_owed,
+            liquidity,
+            liquidity_before,
+        );
+        personal_position.reward_infos[i].reward_amount_owed -=
+            new_personal_position.reward_infos[i].reward_amount_owed;
+    }
+    new_personal_position.recent_epoch = personal_position.recent_epoch;
+    new_personal_position.limit_order_fill_count = personal_position.limit_order_fill_count;
+    new_personal_position.limit_order_fill_tick = personal_position.limit_order_fill_tick;
+    new_personal_position.locked_until = personal_position.locked_until;
+
+    personal_position.liquidity = liquidity_before - liquidity;
+    personal_position.token_fees_owed_0 -= new_personal_position.token_fees_owed_0;
+    personal_position.token_fees_owed_1 -= new_personal_position.token_fees_owed_1;
+    Ok(())
+}
+
+/// The part of `amount` owed to `liquidity` out of `liquidity_total`, rounded down
+pub fn get_split_amount(amount: u64, liquidity: u128, liquidity_total: u128) -> u64 {
+    U256::from(amount)
+        .mul_div_floor(U256::from(liquidity), U256::from(liquidity_total))
+        .unwrap()
+        .as_u64()
+}
+
+#[cfg(test)]
+mod split_position_test {
+    use super::*;
+
+    fn build_position(liquidity: u128) -> PersonalPositionState {
+        let mut personal_position = PersonalPositionState {
+            pool_id: Pubkey::new_unique(),
+            tick_lower_index: -100,
+            tick_upper_index: 100,
+            liquidity,
+            fee_growth_inside_0_last_x64: 10,
+            fee_growth_inside_1_last_x64: 20,
+            token_fees_owed_0: 1_000,
+            token_fees_owed_1: 333,
+            locked_until: 1_000_000,
+            ..Default::default()
+        };
+        for i in 0..REWARD_NUM {
+            personal_position.reward_infos[i].growth_inside_last_x64 = i as u128 + 1;
+            personal_position.reward_infos[i].reward_amount_owed = 100 * (i as u64 + 1) + 1;
+        }
+        personal_position
+    }
+
+    #[test]
+    fn split_invalid_liquidity_test() {
+        for liquidity in [0, 3_000, 3_001] {
+            let mut personal_position = build_position(3_000);
+            let mut new_personal_position = PersonalPositionState::default();
+            assert!(split_personal_position(
+                &mut personal_position,
+                &mut new_personal_position,
+                liquidity
+            )
+            .is_err());
+            assert_eq!(personal_position.liquidity, 3_000);
+        }
+    }
+
+    #[test]
+    fn split_position_test() {
+        let mut personal_position = build_position(3_000);
+        let position_before = personal_position.clone();
+        let mut new_personal_position = PersonalPositionState::default();
+        split_personal_position(&mut personal_position, &mut new_personal_position, 1_000).unwrap();
+
+        assert_eq!(personal_position.liquidity, 2_000);
+        assert_eq!(new_personal_position.liquidity, 1_000);
+        assert_eq!(new_personal_position.pool_id, position_before.pool_id);
+        assert_eq!(new_personal_position.tick_lower_index, -100);
+        assert_eq!(new_personal_position.tick_upper_index, 100);
+        assert_eq!(new_personal_position.fee_growth_inside_0_last_x64, 10);
+        assert_eq!(new_personal_position.fee_growth_inside_1_last_x64, 20);
+        assert_eq!(new_personal_position.locked_until, 1_000_000);
+
+        // the new position takes its part rounded down, nothing owed is lost
+        assert_eq!(new_personal_position.token_fees_owed_0, 333);
+        assert_eq!(new_personal_position.token_fees_owed_1, 111);
+        assert_eq!(personal_position.token_fees_owed_0, 667);
+        assert_eq!(personal_position.token_fees_owed_1, 222);
+        for i in 0..REWARD_NUM {
+            let new_reward_info = new_personal_position.reward_infos[i];
+            let reward_info = personal_position.reward_infos[i];
+            assert_eq!(
+                new_reward_info.growth_inside_last_x64,
+                reward_info.growth_inside_last_x64
+            );
+            assert_eq!(
+                new_reward_info.reward_amount_owed,
+                position_before.reward_infos[i].reward_amount_owed / 3
+            );
+            assert_eq!(
+                new_reward_info.reward_amount_owed + reward_info.reward_amount_owed,
+                position_before.reward_infos[i].reward_amount_owed
+            );
+        }
+    }
+}
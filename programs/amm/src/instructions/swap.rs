@@ -1,6 +1,9 @@
 use crate::error::ErrorCode;
 use crate::libraries::{
-    big_num::U128, fixed_point_64, full_math::MulDiv, liquidity_math, swap_math, tick_math,
+    big_num::{U128, U256},
+    fixed_point_64,
+    full_math::MulDiv,
+    liquidity_math, swap_math, tick_math,
 };
 use crate::states::*;
 use crate::util::*;
@@ -125,11 +128,30 @@ struct StepComputations {
     fee_amount: u64,
 }
 
+/// The denominator of the max price impact, in basis points
+pub const PRICE_IMPACT_DENOMINATOR_VALUE: u32 = 10_000;
+
+/// The outcome of `swap_internal_with_summary`
+#[derive(Default, Debug)]
+pub struct SwapSummary {
+    pub amount_0: u64,
+    pub amount_1: u64,
+    // total fee paid in the input token, including protocol and fund fee
+    pub fee_amount: u64,
+    // number of initialized ticks crossed
+    pub ticks_crossed: u32,
+    // trade fee rate of the last swap step, changes with volatility in dynamic fee mode
+    pub trade_fee_rate: u32,
+    // growth snapshots of the limit orders filled by the swap, to record to their fill accounts
+    pub limit_order_fills: Vec<LimitOrderFill>,
+}
+
 pub fn swap_internal<'b, 'info>(
     amm_config: &AmmConfig,
     pool_state: &mut RefMut<PoolState>,
     tick_array_states: &mut VecDeque<RefMut<TickArrayState>>,
     observation_state: &mut RefMut<ObservationState>,
+    observation_extension: &mut [Observation],
     tickarray_bitmap_extension: &Option<TickArrayBitmapExtension>,
     amount_specified: u64,
     sqrt_price_limit_x64: u128,
@@ -137,8 +159,39 @@ pub fn swap_internal<'b, 'info>(
     is_base_input: bool,
     block_timestamp: u32,
 ) -> Result<(u64, u64)> {
+    let summary = swap_internal_with_summary(
+        amm_config,
+        pool_state,
+        tick_array_states,
+        observation_state,
+        observation_extension,
+        tickarray_bitmap_extension,
+        amount_specified,
+        sqrt_price_limit_x64,
+        None,
+        zero_for_one,
+        is_base_input,
+        block_timestamp,
+    )?;
+    Ok((summary.amount_0, summary.amount_1))
+}
+
+pub fn swap_internal_with_summary<'b, 'info, T: SwapTickArray>(
+    amm_config: &AmmConfig,
+    pool_state: &mut RefMut<PoolState>,
+    tick_array_states: &mut VecDeque<T>,
+    observation_state: &mut RefMut<ObservationState>,
+    observation_extension: &mut [Observation],
+    tickarray_bitmap_extension: &Option<TickArrayBitmapExtension>,
+    amount_specified: u64,
+    sqrt_price_limit_x64: u128,
+    max_price_impact_bps: Option<u32>,
+    zero_for_one: bool,
+    is_base_input: bool,
+    block_timestamp: u32,
+) -> Result<SwapSummary> {
     require!(amount_specified != 0, ErrorCode::ZeroAmountSpecified);
-    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap) {
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap)? {
         return err!(ErrorCode::NotApproved);
     }
     require!(
@@ -155,6 +208,13 @@ pub fn swap_internal<'b, 'info>(
     let liquidity_start = pool_state.liquidity;
 
     let updated_reward_infos = pool_state.update_reward_infos(block_timestamp as u64)?;
+    let seconds_per_liquidity_global_x64 =
+        pool_state.update_seconds_per_liquidity(block_timestamp as u64);
+    let dynamic_fee_enabled = amm_config.is_dynamic_fee_enabled();
+    if dynamic_fee_enabled {
+        pool_state
+            .update_volatility_reference(amm_config.dynamic_fee_decay_period, block_timestamp);
+    }
 
     let mut state = SwapState {
         amount_specified_remaining: amount_specified,
@@ -175,6 +235,8 @@ pub fn swap_internal<'b, 'info>(
     // check observation account is owned by the pool
     require_keys_eq!(observation_state.pool_id, pool_state.key());
 
+    let mut summary = SwapSummary::default();
+
     let (mut is_match_pool_current_tick_array, first_vaild_tick_array_start_index) =
         pool_state.get_first_initialized_tick_array(&tickarray_bitmap_extension, zero_for_one)?;
     let mut current_vaild_tick_array_start_index = first_vaild_tick_array_start_index;
@@ -182,7 +244,8 @@ pub fn swap_internal<'b, 'info>(
     let mut tick_array_current = tick_array_states.pop_front().unwrap();
     // find the first active tick array account
     for _ in 0..tick_array_states.len() {
-        if tick_array_current.start_tick_index == current_vaild_tick_array_start_index {
+        if tick_array_current.tick_array().start_tick_index == current_vaild_tick_array_start_index
+        {
             break;
         }
         tick_array_current = tick_array_states
@@ -190,10 +253,10 @@ pub fn swap_internal<'b, 'info>(
             .ok_or(ErrorCode::NotEnoughTickArrayAccount)?;
     }
     // check the first tick_array account is owned by the pool
-    require_keys_eq!(tick_array_current.pool_id, pool_state.key());
+    require_keys_eq!(tick_array_current.tick_array().pool_id, pool_state.key());
     // check first tick array account is correct
     require_eq!(
-        tick_array_current.start_tick_index,
+        tick_array_current.tick_array().start_tick_index,
         current_vaild_tick_array_start_index,
         ErrorCode::InvalidFirstTickArrayAccount
     );
@@ -223,11 +286,11 @@ pub fn swap_internal<'b, 'info>(
         let mut next_initialized_tick = if let Some(tick_state) = tick_array_current
             .next_initialized_tick(state.tick, pool_state.tick_spacing, zero_for_one)?
         {
-            Box::new(*tick_state)
+            Box::new(tick_state)
         } else {
             if !is_match_pool_current_tick_array {
                 is_match_pool_current_tick_array = true;
-                Box::new(*tick_array_current.first_initialized_tick(zero_for_one)?)
+                Box::new(tick_array_current.first_initialized_tick(zero_for_one)?)
             } else {
                 Box::new(TickState::default())
             }
@@ -237,7 +300,7 @@ pub fn swap_internal<'b, 'info>(
             "next_initialized_tick, status:{}, tick_index:{}, tick_array_current:{}",
             next_initialized_tick.is_initialized(),
             identity(next_initialized_tick.tick),
-            tick_array_current.key().to_string(),
+            tick_array_current.tick_array().key().to_string(),
         );
         if !next_initialized_tick.is_initialized() {
             let next_initialized_tickarray_index = pool_state
@@ -250,17 +313,19 @@ pub fn swap_internal<'b, 'info>(
                 return err!(ErrorCode::LiquidityInsufficient);
             }
 
-            while tick_array_current.start_tick_index != next_initialized_tickarray_index.unwrap() {
+            while tick_array_current.tick_array().start_tick_index
+                != next_initialized_tickarray_index.unwrap()
+            {
                 tick_array_current = tick_array_states
                     .pop_front()
                     .ok_or(ErrorCode::NotEnoughTickArrayAccount)?;
                 // check the tick_array account is owned by the pool
-                require_keys_eq!(tick_array_current.pool_id, pool_state.key());
+                require_keys_eq!(tick_array_current.tick_array().pool_id, pool_state.key());
             }
             current_vaild_tick_array_start_index = next_initialized_tickarray_index.unwrap();
 
             let first_initialized_tick = tick_array_current.first_initialized_tick(zero_for_one)?;
-            next_initialized_tick = Box::new(*first_initialized_tick);
+            next_initialized_tick = Box::new(first_initialized_tick);
         }
         step.tick_next = next_initialized_tick.tick;
         step.initialized = next_initialized_tick.is_initialized();
@@ -297,12 +362,21 @@ pub fn swap_internal<'b, 'info>(
             state.liquidity,
             state.amount_specified_remaining
         );
+        // in dynamic fee mode the fee of a step grows with the distance the price has moved
+        summary.trade_fee_rate = if dynamic_fee_enabled {
+            amm_config.dynamic_trade_fee_rate(
+                pool_state.trade_fee_rate(amm_config),
+                pool_state.volatility_at(state.tick),
+            )
+        } else {
+            pool_state.trade_fee_rate(amm_config)
+        };
         let swap_step = swap_math::compute_swap_step(
             step.sqrt_price_start_x64,
             target_price,
             state.liquidity,
             state.amount_specified_remaining,
-            amm_config.trade_fee_rate,
+            summary.trade_fee_rate,
             is_base_input,
             zero_for_one,
             block_timestamp,
@@ -345,6 +419,7 @@ pub fn swap_internal<'b, 'info>(
         }
 
         let step_fee_amount = step.fee_amount;
+        summary.fee_amount = summary.fee_amount.checked_add(step_fee_amount).unwrap();
         // if the protocol fee is on, calculate how much is owed, decrement fee_amount, and increment protocol_fee
         if amm_config.protocol_fee_rate > 0 {
             let delta = U128::from(step_fee_amount)
@@ -406,6 +481,8 @@ pub fn swap_internal<'b, 'info>(
                         state.fee_growth_global_x64
                     },
                     &updated_reward_infos,
+                    seconds_per_liquidity_global_x64,
+                    block_timestamp,
                 );
                 // update tick_state to tick_array account
                 tick_array_current.update_tick_state(
@@ -413,11 +490,31 @@ pub fn swap_internal<'b, 'info>(
                     pool_state.tick_spacing.into(),
                     *next_initialized_tick,
                 )?;
+                // the limit orders filled by this cross are not converted back when the price returns
+                if let Some(fill) = tick_array_current.fill_limit_orders(
+                    next_initialized_tick.tick,
+                    pool_state.tick_spacing,
+                    zero_for_one,
+                    if zero_for_one {
+                        state.fee_growth_global_x64
+                    } else {
+                        pool_state.fee_growth_global_0_x64
+                    },
+                    if zero_for_one {
+                        pool_state.fee_growth_global_1_x64
+                    } else {
+                        state.fee_growth_global_x64
+                    },
+                    &updated_reward_infos,
+                )? {
+                    summary.limit_order_fills.push(fill);
+                }
 
                 if zero_for_one {
                     liquidity_net = liquidity_net.neg();
                 }
                 state.liquidity = liquidity_math::add_delta(state.liquidity, liquidity_net)?;
+                summary.ticks_crossed += 1;
             }
 
             state.tick = if zero_for_one {
@@ -460,19 +557,35 @@ pub fn swap_internal<'b, 'info>(
         //     zero_for_one,
         // });
     }
+    if let Some(max_price_impact_bps) = max_price_impact_bps {
+        check_price_impact(
+            pool_state.sqrt_price_x64,
+            state.sqrt_price_x64,
+            max_price_impact_bps,
+        )?;
+    }
+
     // update tick
     if state.tick != pool_state.tick_current {
         // update the previous tick to the observation
-        observation_state.update(block_timestamp, pool_state.tick_current);
+        observation_state.update(
+            observation_extension,
+            block_timestamp,
+            pool_state.tick_current,
+            seconds_per_liquidity_global_x64,
+        );
         pool_state.tick_current = state.tick;
     }
     pool_state.sqrt_price_x64 = state.sqrt_price_x64;
+    if dynamic_fee_enabled {
+        pool_state.volatility_accumulator = pool_state.volatility_at(state.tick);
+    }
 
     if liquidity_start != state.liquidity {
         pool_state.liquidity = state.liquidity;
     }
 
-    let (amount_0, amount_1) = if zero_for_one == is_base_input {
+    (summary.amount_0, summary.amount_1) = if zero_for_one == is_base_input {
         (
             amount_specified
                 .checked_sub(state.amount_specified_remaining)
@@ -509,11 +622,11 @@ pub fn swap_internal<'b, 'info>(
         }
         pool_state.swap_in_amount_token_0 = pool_state
             .swap_in_amount_token_0
-            .checked_add(u128::from(amount_0))
+            .checked_add(u128::from(summary.amount_0))
             .unwrap();
         pool_state.swap_out_amount_token_1 = pool_state
             .swap_out_amount_token_1
-            .checked_add(u128::from(amount_1))
+            .checked_add(u128::from(summary.amount_1))
             .unwrap();
     } else {
         pool_state.fee_growth_global_1_x64 = state.fee_growth_global_x64;
@@ -536,15 +649,36 @@ pub fn swap_internal<'b, 'info>(
         }
         pool_state.swap_in_amount_token_1 = pool_state
             .swap_in_amount_token_1
-            .checked_add(u128::from(amount_1))
+            .checked_add(u128::from(summary.amount_1))
             .unwrap();
         pool_state.swap_out_amount_token_0 = pool_state
             .swap_out_amount_token_0
-            .checked_add(u128::from(amount_0))
+            .checked_add(u128::from(summary.amount_0))
             .unwrap();
     }
 
-    Ok((amount_0, amount_1))
+    Ok(summary)
+}
+
+/// Checks the price moved from the start price by at most `max_price_impact_bps` basis points
+pub fn check_price_impact(
+    sqrt_price_start_x64: u128,
+    sqrt_price_end_x64: u128,
+    max_price_impact_bps: u32,
+) -> Result<()> {
+    let price_start = U256::from(sqrt_price_start_x64) * U256::from(sqrt_price_start_x64);
+    let price_end = U256::from(sqrt_price_end_x64) * U256::from(sqrt_price_end_x64);
+    let price_delta = if price_end > price_start {
+        price_end - price_start
+    } else {
+        price_start - price_end
+    };
+    require!(
+        price_delta * U256::from(PRICE_IMPACT_DENOMINATOR_VALUE)
+            <= price_start * U256::from(max_price_impact_bps),
+        ErrorCode::PriceImpactTooHigh
+    );
+    Ok(())
 }
 
 /// Performs a single exact input/output swap
@@ -554,24 +688,55 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
     remaining_accounts: &'c [AccountInfo<'info>],
     amount_specified: u64,
     sqrt_price_limit_x64: u128,
+    max_price_impact_bps: Option<u32>,
     is_base_input: bool,
 ) -> Result<u64> {
     let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
 
     let amount_0;
     let amount_1;
+    let trade_fee_rate;
     let zero_for_one;
     let swap_price_before;
 
     let input_balance_before = ctx.input_vault.amount;
     let output_balance_before = ctx.output_vault.amount;
 
+    let swap_hook = {
+        let pool_state = ctx.pool_state.load()?;
+        zero_for_one = ctx.input_vault.mint == pool_state.token_mint_0;
+        get_swap_hook_accounts(&pool_state, remaining_accounts)?
+    };
+    if let Some((swap_hook_program, swap_hook_state)) = swap_hook {
+        invoke_swap_hook(
+            swap_hook_program,
+            swap_hook_state,
+            &ctx.signer.to_account_info(),
+            &ctx.pool_state.to_account_info(),
+            &ctx.input_token_account.to_account_info(),
+            &ctx.output_token_account.to_account_info(),
+            BEFORE_SWAP_HOOK,
+            &BeforeSwapParams {
+                zero_for_one,
+                is_base_input,
+                amount_specified,
+                sqrt_price_limit_x64,
+            },
+        )?;
+    }
+
     {
         swap_price_before = ctx.pool_state.load()?.sqrt_price_x64;
         let pool_state = &mut ctx.pool_state.load_mut()?;
-        zero_for_one = ctx.input_vault.mint == pool_state.token_mint_0;
 
-        require_gt!(block_timestamp, pool_state.open_time);
+        pool_state.check_open(block_timestamp)?;
+        check_pool_access(
+            pool_state,
+            ctx.pool_state.key(),
+            remaining_accounts,
+            ctx.signer.key(),
+            PoolAccessBitIndex::Swap,
+        )?;
 
         require!(
             if zero_for_one {
@@ -585,11 +750,17 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
         );
 
         let mut tickarray_bitmap_extension = None;
-        let tick_array_states = &mut VecDeque::new();
+        let mut tick_array_states = VecDeque::new();
         tick_array_states.push_back(ctx.tick_array_state.load_mut()?);
 
         let tick_array_bitmap_extension_key = TickArrayBitmapExtension::key(pool_state.key());
         for account_info in remaining_accounts.into_iter() {
+            if pool_state.is_swap_hook_account(account_info.key)
+                || PoolAllowListState::is_allow_list_account(account_info)
+                || LimitOrderFillState::is_limit_order_fill_account(account_info)
+            {
+                continue;
+            }
             if account_info.key().eq(&tick_array_bitmap_extension_key) {
                 tickarray_bitmap_extension = Some(
                     *(AccountLoader::<TickArrayBitmapExtension>::try_from(account_info)?
@@ -601,11 +772,14 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
             tick_array_states.push_back(AccountLoad::load_data_mut(account_info)?);
         }
 
-        (amount_0, amount_1) = swap_internal(
+        let (mut observation_state, mut observation_extension) =
+            load_observations_mut(ctx.observation_state)?;
+        let summary = swap_internal_with_summary(
             &ctx.amm_config,
             pool_state,
-            tick_array_states,
-            &mut ctx.observation_state.load_mut()?,
+            &mut tick_array_states,
+            &mut observation_state,
+            &mut observation_extension,
             &tickarray_bitmap_extension,
             amount_specified,
             if sqrt_price_limit_x64 == 0 {
@@ -617,10 +791,19 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
             } else {
                 sqrt_price_limit_x64
             },
+            max_price_impact_bps,
             zero_for_one,
             is_base_input,
             oracle::block_timestamp(),
         )?;
+        drop(tick_array_states);
+        record_limit_order_fills(
+            ctx.pool_state.key(),
+            &summary.limit_order_fills,
+            std::iter::once(ctx.tick_array_state.as_ref()).chain(remaining_accounts),
+        )?;
+        (amount_0, amount_1, trade_fee_rate) =
+            (summary.amount_0, summary.amount_1, summary.trade_fee_rate);
 
         #[cfg(feature = "enable-log")]
         msg!(
@@ -702,6 +885,30 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
     ctx.output_vault.reload()?;
     ctx.input_vault.reload()?;
 
+    if let Some((swap_hook_program, swap_hook_state)) = swap_hook {
+        let (sqrt_price_x64, tick) = {
+            let pool_state = ctx.pool_state.load()?;
+            (pool_state.sqrt_price_x64, pool_state.tick_current)
+        };
+        invoke_swap_hook(
+            swap_hook_program,
+            swap_hook_state,
+            &ctx.signer.to_account_info(),
+            &ctx.pool_state.to_account_info(),
+            &ctx.input_token_account.to_account_info(),
+            &ctx.output_token_account.to_account_info(),
+            AFTER_SWAP_HOOK,
+            &AfterSwapParams {
+                zero_for_one,
+                amount_0,
+                amount_1,
+                sqrt_price_x64,
+                tick,
+                trade_fee_rate,
+            },
+        )?;
+    }
+
     let pool_state = ctx.pool_state.load()?;
     emit!(SwapEvent {
         pool_state: pool_state.key(),
@@ -715,7 +922,8 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
         zero_for_one,
         sqrt_price_x64: pool_state.sqrt_price_x64,
         liquidity: pool_state.liquidity,
-        tick: pool_state.tick_current
+        tick: pool_state.tick_current,
+        trade_fee_rate,
     });
     if zero_for_one {
         require_gt!(swap_price_before, pool_state.sqrt_price_x64);
@@ -758,6 +966,42 @@ pub fn swap<'a, 'b, 'c: 'info, 'info>(
     other_amount_threshold: u64,
     sqrt_price_limit_x64: u128,
     is_base_input: bool,
+) -> Result<()> {
+    swap_checked(
+        ctx,
+        amount,
+        other_amount_threshold,
+        sqrt_price_limit_x64,
+        None,
+        is_base_input,
+    )
+}
+
+pub fn swap_with_impact_limit<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapSingle<'info>>,
+    amount: u64,
+    other_amount_threshold: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+    max_price_impact_bps: u32,
+) -> Result<()> {
+    swap_checked(
+        ctx,
+        amount,
+        other_amount_threshold,
+        sqrt_price_limit_x64,
+        Some(max_price_impact_bps),
+        is_base_input,
+    )
+}
+
+fn swap_checked<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapSingle<'info>>,
+    amount: u64,
+    other_amount_threshold: u64,
+    sqrt_price_limit_x64: u128,
+    max_price_impact_bps: Option<u32>,
+    is_base_input: bool,
 ) -> Result<()> {
     let amount = exact_internal(
         &mut SwapAccounts {
@@ -775,6 +1019,7 @@ pub fn swap<'a, 'b, 'c: 'info, 'info>(
         ctx.remaining_accounts,
         amount,
         sqrt_price_limit_x64,
+        max_price_impact_bps,
         is_base_input,
     )?;
     if is_base_input {
@@ -957,6 +1202,8 @@ mod swap_test {
                             0,
                             false,
                             &[RewardInfo::default(); 3],
+                            0,
+                            0,
                         )
                         .unwrap();
 
@@ -978,6 +1225,8 @@ mod swap_test {
                             0,
                             false,
                             &[RewardInfo::default(); 3],
+                            0,
+                            0,
                         )
                         .unwrap();
                 }
@@ -1005,6 +1254,8 @@ mod swap_test {
                             0,
                             true,
                             &[RewardInfo::default(); 3],
+                            0,
+                            0,
                         )
                         .unwrap();
 
@@ -1027,6 +1278,8 @@ mod swap_test {
                             0,
                             true,
                             &[RewardInfo::default(); 3],
+                            0,
+                            0,
                         )
                         .unwrap();
                 }
@@ -1120,6 +1373,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &None,
                 12188240002,
                 3049500711113990606,
@@ -1151,6 +1405,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &None,
                 121882400020,
                 3049500711113990606,
@@ -1179,6 +1434,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &None,
                 60941200010,
                 3049500711113990606,
@@ -1236,6 +1492,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &None,
                 477470480,
                 3049500711113990606,
@@ -1267,6 +1524,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &None,
                 4751002622,
                 3049500711113990606,
@@ -1295,6 +1553,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &None,
                 2358130642,
                 3049500711113990606,
@@ -1352,6 +1611,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &None,
                 887470480,
                 5882283448660210779,
@@ -1382,6 +1642,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &None,
                 3087470480,
                 5882283448660210779,
@@ -1411,6 +1672,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &None,
                 200941200010,
                 5882283448660210779,
@@ -1468,6 +1730,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &None,
                 22796232052,
                 5882283448660210779,
@@ -1498,6 +1761,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &None,
                 79023558189,
                 5882283448660210779,
@@ -1527,6 +1791,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &None,
                 4315086194758,
                 5882283448660210779,
@@ -1577,6 +1842,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &None,
                 12188240002,
                 tick_math::get_sqrt_price_at_tick(-32400).unwrap(),
@@ -1622,6 +1888,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &None,
                 12188240002,
                 tick_math::get_sqrt_price_at_tick(-28860).unwrap(),
@@ -1667,6 +1934,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &None,
                 12188240002,
                 tick_math::get_sqrt_price_at_tick(-32400).unwrap(),
@@ -1708,6 +1976,7 @@ mod swap_test {
             &mut pool_state.borrow_mut(),
             &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
             &mut observation_state.borrow_mut(),
+            &mut [],
             &None,
             25,
             tick_math::get_sqrt_price_at_tick(-32400).unwrap(),
@@ -1731,6 +2000,7 @@ mod swap_test {
             &mut pool_state.borrow_mut(),
             &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
             &mut observation_state.borrow_mut(),
+            &mut [],
             &None,
             3,
             tick_math::get_sqrt_price_at_tick(-32400).unwrap(),
@@ -1757,6 +2027,7 @@ mod swap_test {
             &mut pool_state.borrow_mut(),
             &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
             &mut observation_state.borrow_mut(),
+            &mut [],
             &None,
             50,
             tick_math::get_sqrt_price_at_tick(-32400).unwrap(),
@@ -1810,6 +2081,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &None,
                 27,
                 tick_math::get_sqrt_price_at_tick(-32400).unwrap(),
@@ -1837,6 +2109,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &None,
                 1,
                 tick_math::get_sqrt_price_at_tick(-32400).unwrap(),
@@ -1862,6 +2135,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &None,
                 10,
                 tick_math::get_sqrt_price_at_tick(-28800).unwrap(),
@@ -1923,6 +2197,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &Some(bitmap_extension_state),
                 amount_specified,
                 tick_math::MIN_SQRT_PRICE_X64 + 1,
@@ -1977,6 +2252,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &Some(bitmap_extension_state),
                 amount_specified,
                 tick_math::MIN_SQRT_PRICE_X64 + 1,
@@ -2031,6 +2307,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &Some(bitmap_extension_state),
                 amount_specified,
                 tick_math::MAX_SQRT_PRICE_X64 - 1,
@@ -2084,6 +2361,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &Some(bitmap_extension_state),
                 amount_specified,
                 tick_math::MAX_SQRT_PRICE_X64 - 1,
@@ -2141,6 +2419,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &Some(bitmap_extension_state),
                 amount_specified,
                 tick_math::MIN_SQRT_PRICE_X64 + 1,
@@ -2195,6 +2474,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &Some(bitmap_extension_state),
                 amount_specified,
                 tick_math::MIN_SQRT_PRICE_X64 + 1,
@@ -2249,6 +2529,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &Some(bitmap_extension_state),
                 amount_specified,
                 tick_math::MAX_SQRT_PRICE_X64 - 1,
@@ -2302,6 +2583,7 @@ mod swap_test {
                 &mut pool_state.borrow_mut(),
                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                 &mut observation_state.borrow_mut(),
+                &mut [],
                 &Some(bitmap_extension_state),
                 amount_specified,
                 tick_math::MAX_SQRT_PRICE_X64 - 1,
@@ -2355,6 +2637,7 @@ mod swap_test {
                         &mut pool_state.borrow_mut(),
                         &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                         &mut observation_state.borrow_mut(),
+                        &mut [],
                         &Some(bitmap_extension_state),
                         amount_specified,
                         tick_math::MIN_SQRT_PRICE_X64 + 1,
@@ -2377,6 +2660,7 @@ mod swap_test {
                             &mut pool_state.borrow_mut(),
                             &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                             &mut observation_state.borrow_mut(),
+                            &mut [],
                             &Some(bitmap_extension_state),
                             amount_specified,
                             tick_math::MIN_SQRT_PRICE_X64 + 1,
@@ -2401,6 +2685,7 @@ mod swap_test {
                                 &mut pool_state.borrow_mut(),
                                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                                 &mut observation_state.borrow_mut(),
+                                &mut [],
                                 &Some(bitmap_extension_state),
                                 amount_specified,
                                 tick_math::MIN_SQRT_PRICE_X64 + 1,
@@ -2446,6 +2731,7 @@ mod swap_test {
                         &mut pool_state.borrow_mut(),
                         &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                         &mut observation_state.borrow_mut(),
+                        &mut [],
                         &Some(bitmap_extension_state),
                         amount_specified,
                         tick_math::MIN_SQRT_PRICE_X64 + 1,
@@ -2468,6 +2754,7 @@ mod swap_test {
                             &mut pool_state.borrow_mut(),
                             &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                             &mut observation_state.borrow_mut(),
+                            &mut [],
                             &Some(bitmap_extension_state),
                             amount_specified,
                             tick_math::MIN_SQRT_PRICE_X64 + 1,
@@ -2492,6 +2779,7 @@ mod swap_test {
                                 &mut pool_state.borrow_mut(),
                                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                                 &mut observation_state.borrow_mut(),
+                                &mut [],
                                 &Some(bitmap_extension_state),
                                 amount_specified,
                                 tick_math::MIN_SQRT_PRICE_X64 + 1,
@@ -2538,6 +2826,7 @@ mod swap_test {
                         &mut pool_state.borrow_mut(),
                         &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                         &mut observation_state.borrow_mut(),
+                        &mut [],
                         &Some(bitmap_extension_state),
                         amount_specified,
                         tick_math::MAX_SQRT_PRICE_X64 - 1,
@@ -2561,6 +2850,7 @@ mod swap_test {
                             &mut pool_state.borrow_mut(),
                             &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                             &mut observation_state.borrow_mut(),
+                            &mut [],
                             &Some(bitmap_extension_state),
                             amount_specified,
                             tick_math::MAX_SQRT_PRICE_X64 - 1,
@@ -2585,6 +2875,7 @@ mod swap_test {
                                 &mut pool_state.borrow_mut(),
                                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                                 &mut observation_state.borrow_mut(),
+                                &mut [],
                                 &Some(bitmap_extension_state),
                                 amount_specified,
                                 tick_math::MAX_SQRT_PRICE_X64 - 1,
@@ -2629,6 +2920,7 @@ mod swap_test {
                         &mut pool_state.borrow_mut(),
                         &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                         &mut observation_state.borrow_mut(),
+                        &mut [],
                         &Some(bitmap_extension_state),
                         amount_specified,
                         tick_math::MAX_SQRT_PRICE_X64 - 1,
@@ -2651,6 +2943,7 @@ mod swap_test {
                             &mut pool_state.borrow_mut(),
                             &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                             &mut observation_state.borrow_mut(),
+                            &mut [],
                             &Some(bitmap_extension_state),
                             amount_specified,
                             tick_math::MAX_SQRT_PRICE_X64 - 1,
@@ -2675,6 +2968,7 @@ mod swap_test {
                                 &mut pool_state.borrow_mut(),
                                 &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
                                 &mut observation_state.borrow_mut(),
+                                &mut [],
                                 &Some(bitmap_extension_state),
                                 amount_specified,
                                 tick_math::MAX_SQRT_PRICE_X64 - 1,
@@ -2690,4 +2984,355 @@ mod swap_test {
             }
         }
     }
+
+    mod swap_summary_test {
+        use super::*;
+
+        #[test]
+        fn zero_for_one_cross_tick_summary_test() {
+            let (amm_config, pool_state, tick_array_states, observation_state) = build_swap_param(
+                -32395,
+                60,
+                3651942632306380802,
+                5124165121219,
+                vec![
+                    TickArrayInfo {
+                        start_tick_index: -32400,
+                        ticks: vec![build_tick(-32400, 277065331032, -277065331032).take()],
+                    },
+                    TickArrayInfo {
+                        start_tick_index: -36000,
+                        ticks: vec![
+                            build_tick(-32460, 1194569667438, 536061033698).take(),
+                            build_tick(-32520, 790917615645, 790917615645).take(),
+                        ],
+                    },
+                ],
+            );
+
+            let summary = swap_internal_with_summary(
+                &amm_config,
+                &mut pool_state.borrow_mut(),
+                &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                &mut observation_state.borrow_mut(),
+                &mut [],
+                &None,
+                12188240002,
+                3049500711113990606,
+                None,
+                true,
+                true,
+                oracle::block_timestamp_mock() as u32,
+            )
+            .unwrap();
+            // only the tick -32400 is crossed
+            assert_eq!(summary.ticks_crossed, 1);
+            assert_eq!(summary.amount_0, 12188240002);
+            // trade fee rate is 0.1%
+            let fee = summary.amount_0 / 1000;
+            assert!(summary.fee_amount >= fee && summary.fee_amount <= fee + 2);
+            assert_eq!(
+                summary.fee_amount,
+                pool_state.borrow().total_fees_token_0
+                    + pool_state.borrow().protocol_fees_token_0
+                    + pool_state.borrow().fund_fees_token_0
+            );
+        }
+    }
+
+    mod price_impact_test {
+        use super::*;
+
+        #[test]
+        fn check_price_impact_test() {
+            let sqrt_price_start_x64 = 10 << 60;
+            // the price decreases 19%
+            let sqrt_price_end_x64 = 9 << 60;
+            assert!(check_price_impact(sqrt_price_start_x64, sqrt_price_end_x64, 1900).is_ok());
+            assert_eq!(
+                check_price_impact(sqrt_price_start_x64, sqrt_price_end_x64, 1899).unwrap_err(),
+                error!(ErrorCode::PriceImpactTooHigh)
+            );
+            // the price increases 21%
+            let sqrt_price_end_x64 = 11 << 60;
+            assert!(check_price_impact(sqrt_price_start_x64, sqrt_price_end_x64, 2100).is_ok());
+            assert_eq!(
+                check_price_impact(sqrt_price_start_x64, sqrt_price_end_x64, 2099).unwrap_err(),
+                error!(ErrorCode::PriceImpactTooHigh)
+            );
+        }
+
+        #[test]
+        fn swap_exceeds_max_price_impact_test() {
+            let (amm_config, pool_state, tick_array_states, observation_state) = build_swap_param(
+                -32395,
+                60,
+                3651942632306380802,
+                5124165121219,
+                vec![
+                    TickArrayInfo {
+                        start_tick_index: -32400,
+                        ticks: vec![build_tick(-32400, 277065331032, -277065331032).take()],
+                    },
+                    TickArrayInfo {
+                        start_tick_index: -36000,
+                        ticks: vec![
+                            build_tick(-32460, 1194569667438, 536061033698).take(),
+                            build_tick(-32520, 790917615645, 790917615645).take(),
+                        ],
+                    },
+                ],
+            );
+
+            let result = swap_internal_with_summary(
+                &amm_config,
+                &mut pool_state.borrow_mut(),
+                &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                &mut observation_state.borrow_mut(),
+                &mut [],
+                &None,
+                12188240002,
+                3049500711113990606,
+                Some(1),
+                true,
+                true,
+                oracle::block_timestamp_mock() as u32,
+            );
+            assert_eq!(result.unwrap_err(), error!(ErrorCode::PriceImpactTooHigh));
+        }
+    }
+
+    mod dynamic_fee_test {
+        use super::*;
+
+        #[test]
+        fn fee_follows_volatility_test() {
+            let (mut amm_config, pool_state, tick_array_states, observation_state) =
+                build_swap_param(
+                    -32395,
+                    60,
+                    3651942632306380802,
+                    5124165121219,
+                    vec![
+                        TickArrayInfo {
+                            start_tick_index: -32400,
+                            ticks: vec![build_tick(-32400, 277065331032, -277065331032).take()],
+                        },
+                        TickArrayInfo {
+                            start_tick_index: -36000,
+                            ticks: vec![
+                                build_tick(-32460, 1194569667438, 536061033698).take(),
+                                build_tick(-32520, 790917615645, 790917615645).take(),
+                            ],
+                        },
+                    ],
+                );
+            amm_config.dynamic_fee_max_rate = 10000;
+            amm_config.dynamic_fee_control = 100;
+            amm_config.dynamic_fee_decay_period = 300;
+
+            // a previous swap moved the price 20 tick spacings, half a decay period ago
+            let block_timestamp = oracle::block_timestamp_mock() as u32;
+            pool_state.borrow_mut().volatility_accumulator = 20;
+            pool_state.borrow_mut().volatility_update_time = block_timestamp - 150;
+
+            let summary = swap_internal_with_summary(
+                &amm_config,
+                &mut pool_state.borrow_mut(),
+                &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                &mut observation_state.borrow_mut(),
+                &mut [],
+                &None,
+                12188240002,
+                3049500711113990606,
+                None,
+                true,
+                true,
+                block_timestamp,
+            )
+            .unwrap();
+            // the swap stays within a tick spacing, the decayed volatility of 10 sets the fee
+            assert_eq!(summary.trade_fee_rate, 2000);
+            let fee = summary.amount_0 * 2 / 1000;
+            assert!(summary.fee_amount >= fee && summary.fee_amount <= fee + 2);
+
+            let mut pool_state = pool_state.borrow_mut();
+            let volatility_accumulator = pool_state.volatility_accumulator;
+            assert_eq!(volatility_accumulator, 10);
+            let tick_reference = pool_state.tick_reference;
+            assert_eq!(tick_reference, -32395);
+
+            // later steps of the same second pay more as the price moves further away
+            assert_eq!(pool_state.volatility_at(-32395 - 60 * 10), 20);
+            assert_eq!(amm_config.dynamic_trade_fee_rate(1000, 20), 3000);
+            assert_eq!(amm_config.dynamic_trade_fee_rate(1000, 1000), 10000);
+
+            // half of the decay period later, half of the volatility is left
+            pool_state.update_volatility_reference(300, block_timestamp + 150);
+            let volatility_reference = pool_state.volatility_reference;
+            assert_eq!(volatility_reference, 5);
+
+            // and nothing after the whole decay period
+            pool_state.update_volatility_reference(300, block_timestamp + 600);
+            let volatility_reference = pool_state.volatility_reference;
+            assert_eq!(volatility_reference, 0);
+        }
+    }
+
+    mod trade_fee_override_test {
+        use super::*;
+
+        fn swap_with_override(trade_fee_rate_override: Option<u32>) -> SwapSummary {
+            let (amm_config, pool_state, tick_array_states, observation_state) = build_swap_param(
+                -32395,
+                60,
+                3651942632306380802,
+                5124165121219,
+                vec![
+                    TickArrayInfo {
+                        start_tick_index: -32400,
+                        ticks: vec![build_tick(-32400, 277065331032, -277065331032).take()],
+                    },
+                    TickArrayInfo {
+                        start_tick_index: -36000,
+                        ticks: vec![
+                            build_tick(-32460, 1194569667438, 536061033698).take(),
+                            build_tick(-32520, 790917615645, 790917615645).take(),
+                        ],
+                    },
+                ],
+            );
+            pool_state
+                .borrow_mut()
+                .set_trade_fee_rate_override(trade_fee_rate_override);
+            let summary = swap_internal_with_summary(
+                &amm_config,
+                &mut pool_state.borrow_mut(),
+                &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                &mut observation_state.borrow_mut(),
+                &mut [],
+                &None,
+                12188240002,
+                3049500711113990606,
+                None,
+                true,
+                true,
+                oracle::block_timestamp_mock() as u32,
+            )
+            .unwrap();
+            summary
+        }
+
+        #[test]
+        fn swap_uses_override_rate_test() {
+            // the amm config charges 1000
+            let summary = swap_with_override(None);
+            assert_eq!(summary.trade_fee_rate, 1000);
+            assert!(summary.fee_amount > 0);
+
+            let summary = swap_with_override(Some(3000));
+            assert_eq!(summary.trade_fee_rate, 3000);
+            let fee = summary.amount_0 * 3 / 1000;
+            assert!(summary.fee_amount >= fee && summary.fee_amount <= fee + 2);
+
+            // a zero override is a fee free pool
+            let summary = swap_with_override(Some(0));
+            assert_eq!(summary.trade_fee_rate, 0);
+            assert_eq!(summary.fee_amount, 0);
+        }
+    }
+
+    mod limit_order_test {
+        use super::*;
+        use std::convert::identity;
+
+        #[test]
+        fn filled_limit_order_not_converted_back_test() {
+            let liquidity = 1_000_000_000_000;
+            let order_liquidity = 500_000_000_000;
+            // an order selling token_1 in [700, 710], below the price
+            let mut order_lower_tick =
+                build_tick(700, order_liquidity, order_liquidity as i128).take();
+            order_lower_tick.order_liquidity = order_liquidity;
+            let (amm_config, pool_state, tick_array_states, observation_state) = build_swap_param(
+                900,
+                10,
+                tick_math::get_sqrt_price_at_tick(900).unwrap(),
+                liquidity,
+                vec![TickArrayInfo {
+                    start_tick_index: 600,
+                    ticks: vec![
+                        build_tick(600, liquidity, liquidity as i128).take(),
+                        order_lower_tick,
+                        build_tick(710, order_liquidity, -(order_liquidity as i128)).take(),
+                        build_tick(1190, liquidity, -(liquidity as i128)).take(),
+                    ],
+                }],
+            );
+
+            // the price moves left over the order
+            let summary = swap_internal_with_summary(
+                &amm_config,
+                &mut pool_state.borrow_mut(),
+                &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                &mut observation_state.borrow_mut(),
+                &mut [],
+                &None,
+                u64::MAX / 4,
+                tick_math::get_sqrt_price_at_tick(650).unwrap(),
+                None,
+                true,
+                true,
+                oracle::block_timestamp_mock() as u32,
+            )
+            .unwrap();
+            assert_eq!(summary.ticks_crossed, 2);
+            assert_eq!(identity(pool_state.borrow().liquidity), liquidity);
+            {
+                let tick_array = tick_array_states[0].borrow();
+                let order_lower_tick = tick_array.get_tick_state(700, 10).unwrap();
+                assert_eq!(identity(order_lower_tick.liquidity_net), 0);
+                assert_eq!(identity(order_lower_tick.liquidity_gross), order_liquidity);
+                assert_eq!(identity(order_lower_tick.order_liquidity), 0);
+                assert_eq!(identity(order_lower_tick.order_fill_count), 1);
+                let order_upper_tick = tick_array.get_tick_state(710, 10).unwrap();
+                assert_eq!(identity(order_upper_tick.liquidity_net), 0);
+
+                // the order earns the fees of the swap inside its range, up to the fill
+                assert_eq!(summary.limit_order_fills.len(), 1);
+                let fill = summary.limit_order_fills[0];
+                assert_eq!(identity(fill.tick), 700);
+                assert_eq!(identity(fill.fill_count), 1);
+                assert!(fill.fee_growth_inside_0_x64 > 0);
+                assert_eq!(
+                    identity(fill.fee_growth_inside_0_x64),
+                    order_lower_tick.fee_growth_outside_0_x64
+                        - order_upper_tick.fee_growth_outside_0_x64
+                );
+                assert_eq!(identity(fill.fee_growth_inside_1_x64), 0);
+            }
+
+            // and back, the filled order is not part of the active liquidity any more
+            let summary = swap_internal_with_summary(
+                &amm_config,
+                &mut pool_state.borrow_mut(),
+                &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                &mut observation_state.borrow_mut(),
+                &mut [],
+                &None,
+                u64::MAX / 4,
+                tick_math::get_sqrt_price_at_tick(705).unwrap(),
+                None,
+                false,
+                true,
+                oracle::block_timestamp_mock() as u32,
+            )
+            .unwrap();
+            assert_eq!(summary.ticks_crossed, 1);
+            assert!(summary.limit_order_fills.is_empty());
+            let pool_state = pool_state.borrow();
+            assert_eq!(identity(pool_state.tick_current), 705);
+            assert_eq!(identity(pool_state.liquidity), liquidity);
+        }
+    }
 }
@@ -0,0 +1,828 @@
+use crate::error::ErrorCode;
+use crate::libraries::tick_math;
+use crate::states::*;
+use crate::swap::swap_internal_with_summary;
+use crate::util::{self, *};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::Token,
+    token_interface::{Mint, Token2022, TokenAccount},
+};
+use std::collections::VecDeque;
+use std::ops::Deref;
+
+#[derive(Accounts)]
+pub struct SwapRouterBaseOut<'info> {
+    /// The user performing the swap
+    pub payer: Signer<'info>,
+
+    /// The token account that pays input tokens for the swap
+    #[account(mut)]
+    pub input_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The mint of input token
+    #[account(mut)]
+    pub input_token_mint: InterfaceAccount<'info, Mint>,
+
+    /// SPL program for token transfers
+    pub token_program: Program<'info, Token>,
+    /// SPL program 2022 for token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// CHECK:
+    // #[account(
+    //     address = spl_memo::id()
+    // )]
+    pub memo_program: UncheckedAccount<'info>,
+}
+
+/// A single pool of the route, parsed from remaining accounts
+struct RouterHop<'c, 'info> {
+    amm_config: Box<Account<'info, AmmConfig>>,
+    pool_state: AccountLoader<'info, PoolState>,
+    input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    input_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+    output_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+    observation_state: AccountLoader<'info, ObservationState>,
+    /// tickarray_bitmap_extension, pool_allow_list, swap hook accounts and tick arrays of the pool
+    tick_array_accounts: &'c [AccountInfo<'info>],
+    /// swap_hook_program and swap_hook_state, if the pool has a swap hook
+    swap_hook: Option<(&'c AccountInfo<'info>, &'c AccountInfo<'info>)>,
+    zero_for_one: bool,
+    swap_price_before: u128,
+    /// The amount the pool vault receives, without transfer fee
+    amount_in: u64,
+    /// The amount the pool vault pays out, including transfer fee
+    amount_out: u64,
+    trade_fee_rate: u32,
+}
+
+/// Runs the swap of a single hop for an exact output amount, without moving any token.
+/// Return the amount the input token account has to pay, including transfer fee.
+fn swap_hop_base_out<'c: 'info, 'info>(
+    payer: &Signer<'info>,
+    hop: &mut RouterHop<'c, 'info>,
+    amount_out: u64,
+    block_timestamp: u64,
+) -> Result<u64> {
+    // the amount the pool pays includes the transfer fee charged on the output token
+    let amount_calculate_specified = amount_out
+        + util::get_transfer_inverse_fee(hop.output_vault_mint.clone(), amount_out).unwrap();
+
+    let amount_0;
+    let amount_1;
+    {
+        let pool_state = hop.pool_state.load()?;
+        hop.zero_for_one = hop.input_vault.mint == pool_state.token_mint_0;
+        hop.swap_hook = get_swap_hook_accounts(&pool_state, hop.tick_array_accounts)?;
+    }
+    if let Some((swap_hook_program, swap_hook_state)) = hop.swap_hook {
+        invoke_swap_hook(
+            swap_hook_program,
+            swap_hook_state,
+            &payer.to_account_info(),
+            &hop.pool_state.to_account_info(),
+            &hop.input_token_account.to_account_info(),
+            &hop.output_token_account.to_account_info(),
+            BEFORE_SWAP_HOOK,
+            &BeforeSwapParams {
+                zero_for_one: hop.zero_for_one,
+                is_base_input: false,
+                amount_specified: amount_out,
+                sqrt_price_limit_x64: 0,
+            },
+        )?;
+    }
+    {
+        let pool_state = &mut hop.pool_state.load_mut()?;
+        hop.swap_price_before = pool_state.sqrt_price_x64;
+
+        pool_state.check_open(block_timestamp)?;
+        check_pool_access(
+            pool_state,
+            hop.pool_state.key(),
+            hop.tick_array_accounts,
+            payer.key(),
+            PoolAccessBitIndex::Swap,
+        )?;
+
+        require!(
+            if hop.zero_for_one {
+                hop.input_vault.key() == pool_state.token_vault_0
+                    && hop.output_vault.key() == pool_state.token_vault_1
+            } else {
+                hop.input_vault.key() == pool_state.token_vault_1
+                    && hop.output_vault.key() == pool_state.token_vault_0
+            },
+            ErrorCode::InvalidInputPoolVault
+        );
+
+        let mut tickarray_bitmap_extension = None;
+        let mut tick_array_states = VecDeque::new();
+
+        let tick_array_bitmap_extension_key = TickArrayBitmapExtension::key(pool_state.key());
+        for account_info in hop.tick_array_accounts.into_iter() {
+            if pool_state.is_swap_hook_account(account_info.key)
+                || PoolAllowListState::is_allow_list_account(account_info)
+                || LimitOrderFillState::is_limit_order_fill_account(account_info)
+            {
+                continue;
+            }
+            if account_info.key().eq(&tick_array_bitmap_extension_key) {
+                tickarray_bitmap_extension = Some(
+                    *(AccountLoader::<TickArrayBitmapExtension>::try_from(account_info)?
+                        .load()?
+                        .deref()),
+                );
+                continue;
+            }
+            tick_array_states.push_back(AccountLoad::load_data_mut(account_info)?);
+        }
+
+        let (mut observation_state, mut observation_extension) =
+            load_observations_mut(&hop.observation_state)?;
+        let summary = swap_internal_with_summary(
+            &hop.amm_config,
+            pool_state,
+            &mut tick_array_states,
+            &mut observation_state,
+            &mut observation_extension,
+            &tickarray_bitmap_extension,
+            amount_calculate_specified,
+            if hop.zero_for_one {
+                tick_math::MIN_SQRT_PRICE_X64 + 1
+            } else {
+                tick_math::MAX_SQRT_PRICE_X64 - 1
+            },
+            None,
+            hop.zero_for_one,
+            false,
+            oracle::block_timestamp(),
+        )?;
+        drop(tick_array_states);
+        record_limit_order_fills(
+            hop.pool_state.key(),
+            &summary.limit_order_fills,
+            hop.tick_array_accounts,
+        )?;
+        (amount_0, amount_1, hop.trade_fee_rate) =
+            (summary.amount_0, summary.amount_1, summary.trade_fee_rate);
+        #[cfg(feature = "enable-log")]
+        msg!(
+            "swap_hop_base_out, amount_0: {}, amount_1: {}",
+            amount_0,
+            amount_1
+        );
+        require!(
+            amount_0 != 0 && amount_1 != 0,
+            ErrorCode::TooSmallInputOrOutputAmount
+        );
+    }
+    (hop.amount_in, hop.amount_out) = if hop.zero_for_one {
+        (amount_0, amount_1)
+    } else {
+        (amount_1, amount_0)
+    };
+    // Does't allow partial filled in the route.
+    require_eq!(
+        amount_calculate_specified,
+        hop.amount_out,
+        ErrorCode::HopPartiallyFilled
+    );
+
+    Ok(hop.amount_in
+        + util::get_transfer_inverse_fee(hop.input_vault_mint.clone(), hop.amount_in).unwrap())
+}
+
+/// Runs the swaps of the route backwards, the input required by a hop is the output of the
+/// previous one. Return the amount the payer has to pay, including transfer fee.
+fn swap_route_base_out<'c: 'info, 'info>(
+    payer: &Signer<'info>,
+    hops: &mut [RouterHop<'c, 'info>],
+    amount_out: u64,
+    amount_in_maximum: u64,
+    block_timestamp: u64,
+) -> Result<u64> {
+    let mut amount_out_internal = amount_out;
+    for hop in hops.iter_mut().rev() {
+        amount_out_internal = swap_hop_base_out(payer, hop, amount_out_internal, block_timestamp)?;
+    }
+    require_gte!(
+        amount_in_maximum,
+        amount_out_internal,
+        ErrorCode::TooMuchInputPaid
+    );
+    Ok(amount_out_internal)
+}
+
+/// Moves the tokens of a hop computed by `swap_hop_base_out`
+fn settle_hop<'c: 'info, 'info>(
+    payer: &Signer<'info>,
+    token_program: &Program<'info, Token>,
+    token_program_2022: &Program<'info, Token2022>,
+    hop: &RouterHop<'c, 'info>,
+) -> Result<()> {
+    let input_transfer_fee =
+        util::get_transfer_inverse_fee(hop.input_vault_mint.clone(), hop.amount_in).unwrap();
+    let output_transfer_fee =
+        util::get_transfer_fee(hop.output_vault_mint.clone(), hop.amount_out).unwrap();
+
+    transfer_from_user_to_pool_vault(
+        payer,
+        &hop.input_token_account.to_account_info(),
+        &hop.input_vault.to_account_info(),
+        Some(hop.input_vault_mint.clone()),
+        token_program,
+        Some(token_program_2022.to_account_info()),
+        hop.amount_in + input_transfer_fee,
+    )?;
+    if hop.output_vault.amount <= hop.amount_out {
+        // freeze pool, disable all instructions
+        hop.pool_state.load_mut()?.set_status(255);
+    }
+    transfer_from_pool_vault_to_user(
+        &hop.pool_state,
+        &hop.output_vault.to_account_info(),
+        &hop.output_token_account.to_account_info(),
+        Some(hop.output_vault_mint.clone()),
+        token_program,
+        Some(token_program_2022.to_account_info()),
+        hop.amount_out,
+    )?;
+
+    if let Some((swap_hook_program, swap_hook_state)) = hop.swap_hook {
+        let (sqrt_price_x64, tick) = {
+            let pool_state = hop.pool_state.load()?;
+            (pool_state.sqrt_price_x64, pool_state.tick_current)
+        };
+        let (amount_0, amount_1) = if hop.zero_for_one {
+            (hop.amount_in, hop.amount_out)
+        } else {
+            (hop.amount_out, hop.amount_in)
+        };
+        invoke_swap_hook(
+            swap_hook_program,
+            swap_hook_state,
+            &payer.to_account_info(),
+            &hop.pool_state.to_account_info(),
+            &hop.input_token_account.to_account_info(),
+            &hop.output_token_account.to_account_info(),
+            AFTER_SWAP_HOOK,
+            &AfterSwapParams {
+                zero_for_one: hop.zero_for_one,
+                amount_0,
+                amount_1,
+                sqrt_price_x64,
+                tick,
+                trade_fee_rate: hop.trade_fee_rate,
+            },
+        )?;
+    }
+
+    let pool_state = hop.pool_state.load()?;
+    let (token_account_0, token_account_1, amount_0, transfer_fee_0, amount_1, transfer_fee_1) =
+        if hop.zero_for_one {
+            (
+                hop.input_token_account.key(),
+                hop.output_token_account.key(),
+                hop.amount_in,
+                input_transfer_fee,
+                hop.amount_out - output_transfer_fee,
+                output_transfer_fee,
+            )
+        } else {
+            (
+                hop.output_token_account.key(),
+                hop.input_token_account.key(),
+                hop.amount_out - output_transfer_fee,
+                output_transfer_fee,
+                hop.amount_in,
+                input_transfer_fee,
+            )
+        };
+    emit!(SwapEvent {
+        pool_state: pool_state.key(),
+        sender: payer.key(),
+        token_account_0,
+        token_account_1,
+        amount_0,
+        transfer_fee_0,
+        amount_1,
+        transfer_fee_1,
+        zero_for_one: hop.zero_for_one,
+        sqrt_price_x64: pool_state.sqrt_price_x64,
+        liquidity: pool_state.liquidity,
+        tick: pool_state.tick_current,
+        trade_fee_rate: hop.trade_fee_rate,
+    });
+    if hop.zero_for_one {
+        require_gt!(hop.swap_price_before, pool_state.sqrt_price_x64);
+    } else {
+        require_gt!(pool_state.sqrt_price_x64, hop.swap_price_before);
+    }
+    Ok(())
+}
+
+pub fn swap_router_base_out<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseOut<'info>>,
+    amount_out: u64,
+    amount_in_maximum: u64,
+) -> Result<()> {
+    let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
+
+    let mut input_token_account = Box::new(ctx.accounts.input_token_account.clone());
+    let mut input_token_mint = Box::new(ctx.accounts.input_token_mint.clone());
+    let mut hops: Vec<RouterHop> = Vec::new();
+    let mut accounts: &'c [AccountInfo<'info>] = ctx.remaining_accounts;
+    while !accounts.is_empty() {
+        let mut remaining_accounts = accounts.iter();
+        let amm_config = Box::new(Account::<AmmConfig>::try_from(
+            remaining_accounts.next().unwrap(),
+        )?);
+        let pool_state_loader =
+            AccountLoader::<PoolState>::try_from(remaining_accounts.next().unwrap())?;
+        let output_token_account = Box::new(InterfaceAccount::<TokenAccount>::try_from(
+            &remaining_accounts.next().unwrap(),
+        )?);
+        let input_vault = Box::new(InterfaceAccount::<TokenAccount>::try_from(
+            remaining_accounts.next().unwrap(),
+        )?);
+        let output_vault = Box::new(InterfaceAccount::<TokenAccount>::try_from(
+            remaining_accounts.next().unwrap(),
+        )?);
+        let output_token_mint = Box::new(InterfaceAccount::<Mint>::try_from(
+            remaining_accounts.next().unwrap(),
+        )?);
+        let observation_state =
+            AccountLoader::<ObservationState>::try_from(remaining_accounts.next().unwrap())?;
+
+        {
+            let pool_state = pool_state_loader.load()?;
+            // check observation account is owned by the pool
+            require_keys_eq!(pool_state.observation_key, observation_state.key());
+            // check ammConfig account is associate with the pool
+            require_keys_eq!(pool_state.amm_config, amm_config.key());
+        }
+        require_keys_eq!(input_vault.mint, input_token_mint.key());
+        require_keys_eq!(output_vault.mint, output_token_mint.key());
+
+        // tick arrays of this pool end at the amm_config of the next pool
+        accounts = remaining_accounts.as_slice();
+        let tick_array_accounts_len = accounts
+            .iter()
+            .position(|account_info| account_info.data_len() == AmmConfig::LEN)
+            .unwrap_or(accounts.len());
+        let (tick_array_accounts, next_accounts) = accounts.split_at(tick_array_accounts_len);
+        accounts = next_accounts;
+
+        hops.push(RouterHop {
+            amm_config,
+            pool_state: pool_state_loader,
+            input_token_account: input_token_account.clone(),
+            output_token_account: output_token_account.clone(),
+            input_vault,
+            output_vault,
+            input_vault_mint: input_token_mint.clone(),
+            output_vault_mint: output_token_mint.clone(),
+            observation_state,
+            tick_array_accounts,
+            swap_hook: None,
+            zero_for_one: false,
+            swap_price_before: 0,
+            amount_in: 0,
+            amount_out: 0,
+            trade_fee_rate: 0,
+        });
+        // output token is the new swap input token
+        input_token_account = output_token_account;
+        input_token_mint = output_token_mint;
+    }
+
+    swap_route_base_out(
+        &ctx.accounts.payer,
+        &mut hops,
+        amount_out,
+        amount_in_maximum,
+        block_timestamp,
+    )?;
+
+    // Tokens flow forwards through the route
+    for hop in hops.iter() {
+        settle_hop(
+            &ctx.accounts.payer,
+            &ctx.accounts.token_program,
+            &ctx.accounts.token_program_2022,
+            hop,
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod swap_router_base_out_test {
+    use super::*;
+    use crate::states::pool_test::{build_pool, build_pool_account_info};
+    use crate::states::tick_array_bitmap_extension_test::{
+        build_tick_array_bitmap_extension_info, BuildExtensionAccountInfo,
+    };
+    use crate::states::tick_array_test::{
+        build_tick, build_tick_array_account_info, build_tick_array_with_tick_states,
+    };
+    use crate::util::{build_account_info, set_clock_sysvar_stub};
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_lang::Discriminator;
+    use anchor_spl::token_2022::spl_token_2022::{
+        extension::{
+            transfer_fee::{TransferFee, TransferFeeConfig},
+            ExtensionType, StateWithExtensionsMut,
+        },
+        state::{Account as SplAccount, AccountState, Mint as SplMint},
+    };
+
+    const TICK_CURRENT: i32 = 300;
+    const TICK_UPPER: i32 = 480;
+    const LIQUIDITY: u128 = 1_000_000_000;
+    const VAULT_AMOUNT: u64 = 1_000_000_000_000;
+    /// The transfer fee of the Token-2022 mint in the middle of the route, 1%
+    const TRANSFER_FEE_BPS: u16 = 100;
+
+    fn build_info(
+        key: Pubkey,
+        owner: Pubkey,
+        data: Vec<u8>,
+        is_signer: bool,
+        executable: bool,
+    ) -> &'static AccountInfo<'static> {
+        Box::leak(Box::new(AccountInfo::new(
+            Box::leak(Box::new(key)),
+            is_signer,
+            true,
+            Box::leak(Box::new(0)),
+            Box::leak(data.into_boxed_slice()),
+            Box::leak(Box::new(owner)),
+            executable,
+            0,
+        )))
+    }
+
+    /// A SPL Token mint, or a Token-2022 mint charging `transfer_fee_bps` on transfers
+    fn build_mint(transfer_fee_bps: Option<u16>) -> Box<InterfaceAccount<'static, Mint>> {
+        let base = SplMint {
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        };
+        let (owner, data) = match transfer_fee_bps {
+            None => {
+                let mut data = vec![0u8; SplMint::LEN];
+                base.pack_into_slice(&mut data);
+                (Token::id(), data)
+            }
+            Some(transfer_fee_bps) => {
+                let len = ExtensionType::try_calculate_account_len::<SplMint>(&[
+                    ExtensionType::TransferFeeConfig,
+                ])
+                .unwrap();
+                let mut data = vec![0u8; len];
+                let mut state =
+                    StateWithExtensionsMut::<SplMint>::unpack_uninitialized(&mut data).unwrap();
+                let transfer_fee = TransferFee {
+                    epoch: 0.into(),
+                    maximum_fee: u64::MAX.into(),
+                    transfer_fee_basis_points: transfer_fee_bps.into(),
+                };
+                let config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+                config.older_transfer_fee = transfer_fee;
+                config.newer_transfer_fee = transfer_fee;
+                state.base = base;
+                state.pack_base();
+                state.init_account_type().unwrap();
+                (Token2022::id(), data)
+            }
+        };
+        Box::new(
+            InterfaceAccount::try_from(build_info(Pubkey::new_unique(), owner, data, false, false))
+                .unwrap(),
+        )
+    }
+
+    fn build_token_account(
+        mint: &InterfaceAccount<'static, Mint>,
+        amount: u64,
+    ) -> Box<InterfaceAccount<'static, TokenAccount>> {
+        let mut data = vec![0u8; SplAccount::LEN];
+        SplAccount {
+            mint: mint.key(),
+            owner: Pubkey::new_unique(),
+            amount,
+            state: AccountState::Initialized,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+        let owner = *mint.to_account_info().owner;
+        Box::new(
+            InterfaceAccount::try_from(build_info(Pubkey::new_unique(), owner, data, false, false))
+                .unwrap(),
+        )
+    }
+
+    /// A hop through a pool of `LIQUIDITY` in `[tick_lower, TICK_UPPER]` at `TICK_CURRENT`,
+    /// swapping the input mint as token_0 for the output mint as token_1
+    fn build_hop(
+        input_token_account: &InterfaceAccount<'static, TokenAccount>,
+        output_token_account: &InterfaceAccount<'static, TokenAccount>,
+        input_vault_mint: &InterfaceAccount<'static, Mint>,
+        output_vault_mint: &InterfaceAccount<'static, Mint>,
+        tick_spacing: u16,
+        tick_lower: i32,
+    ) -> RouterHop<'static, 'static> {
+        let input_vault = build_token_account(input_vault_mint, VAULT_AMOUNT);
+        let output_vault = build_token_account(output_vault_mint, VAULT_AMOUNT);
+
+        let pool_state_ref = build_pool(
+            TICK_CURRENT,
+            tick_spacing,
+            tick_math::get_sqrt_price_at_tick(TICK_CURRENT).unwrap(),
+            LIQUIDITY,
+        );
+        let mut pool_state = pool_state_ref.borrow_mut();
+        pool_state.fee_growth_global_0_x64 = 0;
+        pool_state.fee_growth_global_1_x64 = 0;
+        pool_state.token_mint_0 = input_vault_mint.key();
+        pool_state.token_mint_1 = output_vault_mint.key();
+        pool_state.token_vault_0 = input_vault.key();
+        pool_state.token_vault_1 = output_vault.key();
+        pool_state.bump = [Pubkey::find_program_address(
+            &[
+                POOL_SEED.as_bytes(),
+                pool_state.amm_config.as_ref(),
+                pool_state.token_mint_0.as_ref(),
+                pool_state.token_mint_1.as_ref(),
+            ],
+            &crate::id(),
+        )
+        .1];
+        let bitmap_extension: &'static AccountInfo<'static> =
+            Box::leak(Box::new(build_tick_array_bitmap_extension_info(Box::leak(
+                Box::new(BuildExtensionAccountInfo {
+                    key: TickArrayBitmapExtension::key(pool_state.key()),
+                    ..Default::default()
+                }),
+            ))));
+        let tick_upper = *build_tick(TICK_UPPER, LIQUIDITY, -(LIQUIDITY as i128)).borrow();
+        let tick_lower = *build_tick(tick_lower, LIQUIDITY, LIQUIDITY as i128).borrow();
+        let upper_start_index = TickArrayState::get_array_start_index(TICK_UPPER, tick_spacing);
+        let lower_start_index =
+            TickArrayState::get_array_start_index(tick_lower.tick, tick_spacing);
+        // the tick arrays are passed in the swap direction, from the upper tick down
+        let tick_arrays = if upper_start_index == lower_start_index {
+            vec![(upper_start_index, vec![tick_upper, tick_lower])]
+        } else {
+            vec![
+                (upper_start_index, vec![tick_upper]),
+                (lower_start_index, vec![tick_lower]),
+            ]
+        };
+        let mut tick_array_accounts = vec![bitmap_extension.clone()];
+        for (start_index, tick_states) in tick_arrays {
+            let tick_array = build_tick_array_with_tick_states(
+                pool_state.key(),
+                start_index,
+                tick_spacing,
+                tick_states,
+            );
+            pool_state
+                .flip_tick_array_bit(Some(bitmap_extension), start_index)
+                .unwrap();
+            tick_array_accounts.push(build_tick_array_account_info(&tick_array.borrow()).clone());
+        }
+
+        let mut amm_config_data = Vec::new();
+        AmmConfig {
+            trade_fee_rate: 2500,
+            tick_spacing,
+            ..Default::default()
+        }
+        .try_serialize(&mut amm_config_data)
+        .unwrap();
+        let observation_state = ObservationState {
+            pool_id: pool_state.key(),
+            ..Default::default()
+        };
+        let mut observation_data = ObservationState::discriminator().to_vec();
+        observation_data.extend_from_slice(bytemuck::bytes_of(&observation_state));
+
+        RouterHop {
+            amm_config: Box::new(
+                Account::try_from(build_account_info(pool_state.amm_config, amm_config_data))
+                    .unwrap(),
+            ),
+            pool_state: AccountLoader::try_from(build_pool_account_info(&pool_state)).unwrap(),
+            input_token_account: Box::new(input_token_account.clone()),
+            output_token_account: Box::new(output_token_account.clone()),
+            input_vault,
+            output_vault,
+            input_vault_mint: Box::new(input_vault_mint.clone()),
+            output_vault_mint: Box::new(output_vault_mint.clone()),
+            observation_state: AccountLoader::try_from(build_account_info(
+                Pubkey::new_unique(),
+                observation_data,
+            ))
+            .unwrap(),
+            tick_array_accounts: Box::leak(tick_array_accounts.into_boxed_slice()),
+            swap_hook: None,
+            zero_for_one: false,
+            swap_price_before: 0,
+            amount_in: 0,
+            amount_out: 0,
+            trade_fee_rate: 0,
+        }
+    }
+
+    fn build_payer() -> Signer<'static> {
+        Signer::try_from(build_info(
+            Pubkey::new_unique(),
+            anchor_lang::system_program::ID,
+            Vec::new(),
+            true,
+            false,
+        ))
+        .unwrap()
+    }
+
+    fn build_program<T: Id + Clone>() -> Program<'static, T> {
+        Program::try_from(build_info(
+            T::id(),
+            Pubkey::default(),
+            Vec::new(),
+            false,
+            true,
+        ))
+        .unwrap()
+    }
+
+    /// A route from a SPL Token through a Token-2022 mint with transfer fee to another SPL Token
+    fn build_route(
+        tick_spacing: u16,
+        tick_lower: i32,
+    ) -> (
+        Vec<RouterHop<'static, 'static>>,
+        Box<InterfaceAccount<'static, Mint>>,
+    ) {
+        set_clock_sysvar_stub();
+        let mint_a = build_mint(None);
+        let mint_b = build_mint(Some(TRANSFER_FEE_BPS));
+        let mint_c = build_mint(None);
+        let token_account_a = build_token_account(&mint_a, VAULT_AMOUNT);
+        let token_account_b = build_token_account(&mint_b, 0);
+        let token_account_c = build_token_account(&mint_c, 0);
+        let hops = vec![
+            build_hop(
+                &token_account_a,
+                &token_account_b,
+                &mint_a,
+                &mint_b,
+                tick_spacing,
+                tick_lower,
+            ),
+            build_hop(
+                &token_account_b,
+                &token_account_c,
+                &mint_b,
+                &mint_c,
+                tick_spacing,
+                tick_lower,
+            ),
+        ];
+        (hops, mint_b)
+    }
+
+    fn block_timestamp() -> u64 {
+        Clock::get().unwrap().unix_timestamp as u64
+    }
+
+    #[test]
+    fn two_hops_with_transfer_fee_test() {
+        let (mut hops, mint_b) = build_route(60, 120);
+        let payer = build_payer();
+        let amount_in =
+            swap_route_base_out(&payer, &mut hops, 10_000, u64::MAX, block_timestamp()).unwrap();
+
+        // the last hop pays the exact output, its mint has no transfer fee
+        assert_eq!(hops[1].amount_out, 10_000);
+        // the token in the middle is charged twice, out of the first pool and into the second
+        let fee_into_second_pool =
+            util::get_transfer_inverse_fee(mint_b.clone(), hops[1].amount_in).unwrap();
+        assert!(fee_into_second_pool > 0);
+        let received_from_first_pool = hops[1].amount_in + fee_into_second_pool;
+        let fee_out_of_first_pool =
+            util::get_transfer_inverse_fee(mint_b.clone(), received_from_first_pool).unwrap();
+        assert!(fee_out_of_first_pool > 0);
+        assert_eq!(
+            hops[0].amount_out,
+            received_from_first_pool + fee_out_of_first_pool
+        );
+        // the first input has no transfer fee either
+        assert_eq!(amount_in, hops[0].amount_in);
+
+        // the receiving side gets what the next hop spends once the fee is taken
+        assert_eq!(
+            hops[0].amount_out
+                - util::get_transfer_fee(mint_b.clone(), hops[0].amount_out).unwrap(),
+            received_from_first_pool
+        );
+
+        let token_program = build_program::<Token>();
+        let token_program_2022 = build_program::<Token2022>();
+        for hop in hops.iter() {
+            assert!(hop.zero_for_one);
+            settle_hop(&payer, &token_program, &token_program_2022, hop).unwrap();
+            assert!(hop.pool_state.load().unwrap().sqrt_price_x64 < hop.swap_price_before);
+        }
+    }
+
+    #[test]
+    fn amount_in_maximum_exceeded_test() {
+        let (mut hops, _) = build_route(60, 120);
+        let payer = build_payer();
+        let amount_in =
+            swap_route_base_out(&payer, &mut hops, 10_000, u64::MAX, block_timestamp()).unwrap();
+
+        let (mut hops, _) = build_route(60, 120);
+        assert_eq!(
+            swap_route_base_out(&payer, &mut hops, 10_000, amount_in - 1, block_timestamp())
+                .unwrap_err(),
+            error!(ErrorCode::TooMuchInputPaid)
+        );
+        let (mut hops, _) = build_route(60, 120);
+        swap_route_base_out(&payer, &mut hops, 10_000, amount_in, block_timestamp()).unwrap();
+    }
+
+    #[test]
+    fn hop_partially_filled_test() {
+        // the liquidity of the last pool reaches the price limit, but holds less than asked for
+        let (mut hops, _) = build_route(4, tick_math::MIN_TICK);
+        let payer = build_payer();
+        assert_eq!(
+            swap_hop_base_out(&payer, &mut hops[1], 2 * VAULT_AMOUNT, block_timestamp())
+                .unwrap_err(),
+            error!(ErrorCode::HopPartiallyFilled)
+        );
+    }
+
+    #[test]
+    fn fill_limit_order_without_fill_account_test() {
+        let (mut hops, _) = build_route(60, 120);
+        let hop = &mut hops[0];
+        let pool_id = hop.pool_state.key();
+        // an order below the price in [180, 240], filled when the price moves left across 180
+        let order_liquidity = 1_000_000;
+        let mut order_lower_tick =
+            *build_tick(180, order_liquidity, order_liquidity as i128).borrow();
+        order_lower_tick.order_liquidity = order_liquidity;
+        let order_upper_tick =
+            *build_tick(240, order_liquidity, -(order_liquidity as i128)).borrow();
+        let tick_array = build_tick_array_with_tick_states(
+            pool_id,
+            0,
+            60,
+            vec![
+                *build_tick(TICK_UPPER, LIQUIDITY, -(LIQUIDITY as i128)).borrow(),
+                order_upper_tick,
+                order_lower_tick,
+                *build_tick(120, LIQUIDITY, LIQUIDITY as i128).borrow(),
+            ],
+        );
+        // the tick array has grown by the fill of the order tick when the order was opened
+        let mut tick_array_data = TickArrayState::discriminator().to_vec();
+        tick_array_data.extend_from_slice(bytemuck::bytes_of(&*tick_array.borrow()));
+        tick_array_data.extend_from_slice(bytemuck::bytes_of(&LimitOrderFill {
+            tick: 180,
+            ..Default::default()
+        }));
+        let tick_array_info = build_account_info(Pubkey::new_unique(), tick_array_data);
+        // no fill account is passed to the swap
+        hop.tick_array_accounts = Box::leak(Box::new([
+            hop.tick_array_accounts[0].clone(),
+            tick_array_info.clone(),
+        ]));
+
+        let payer = build_payer();
+        swap_hop_base_out(&payer, hop, 7_000_000, block_timestamp()).unwrap();
+        assert!(hop.pool_state.load().unwrap().tick_current < 180);
+
+        let (tick_array, fills) = load_limit_order_fills(tick_array_info).unwrap();
+        let order_lower_tick = tick_array.get_tick_state(180, 60).unwrap();
+        assert_eq!({ order_lower_tick.order_liquidity }, 0);
+        assert_eq!({ order_lower_tick.order_fill_count }, 1);
+        // the fill is kept in the tick array until it is written to its fill account
+        assert_eq!({ fills[0].tick }, 180);
+        assert_eq!({ fills[0].fill_count }, 1);
+        assert!({ fills[0].fee_growth_inside_0_x64 } > 0);
+        assert_eq!({ fills[0].fee_growth_inside_1_x64 }, 0);
+    }
+}
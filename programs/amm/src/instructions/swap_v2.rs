@@ -3,7 +3,7 @@ use std::ops::Deref;
 
 use crate::error::ErrorCode;
 use crate::libraries::tick_math;
-use crate::swap::swap_internal;
+use crate::swap::swap_internal_with_summary;
 use crate::util::*;
 use crate::{states::*, util};
 use anchor_lang::prelude::*;
@@ -70,6 +70,8 @@ pub struct SwapSingleV2<'info> {
     pub output_vault_mint: Box<InterfaceAccount<'info, Mint>>,
     // remaining accounts
     // tickarray_bitmap_extension: must add account if need regardless the sequence
+    // pool_allow_list: must add account if swaps of the pool are restricted, regardless the sequence
+    // swap_hook_program and swap_hook_state: must add accounts if the pool has a swap hook
     // tick_array_account_1
     // tick_array_account_2
     // tick_array_account_...
@@ -82,6 +84,7 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
     remaining_accounts: &'c [AccountInfo<'info>],
     amount_specified: u64,
     sqrt_price_limit_x64: u128,
+    max_price_impact_bps: Option<u32>,
     is_base_input: bool,
 ) -> Result<u64> {
     // invoke_memo_instruction(SWAP_MEMO_MSG, ctx.memo_program.to_account_info())?;
@@ -90,6 +93,7 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
 
     let amount_0;
     let amount_1;
+    let trade_fee_rate;
     let zero_for_one;
     let swap_price_before;
 
@@ -108,12 +112,41 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
         amount_specified + transfer_fee
     };
 
+    let swap_hook = {
+        let pool_state = ctx.pool_state.load()?;
+        zero_for_one = ctx.input_vault.mint == pool_state.token_mint_0;
+        get_swap_hook_accounts(&pool_state, remaining_accounts)?
+    };
+    if let Some((swap_hook_program, swap_hook_state)) = swap_hook {
+        invoke_swap_hook(
+            swap_hook_program,
+            swap_hook_state,
+            &ctx.payer.to_account_info(),
+            &ctx.pool_state.to_account_info(),
+            &ctx.input_token_account.to_account_info(),
+            &ctx.output_token_account.to_account_info(),
+            BEFORE_SWAP_HOOK,
+            &BeforeSwapParams {
+                zero_for_one,
+                is_base_input,
+                amount_specified,
+                sqrt_price_limit_x64,
+            },
+        )?;
+    }
+
     {
         swap_price_before = ctx.pool_state.load()?.sqrt_price_x64;
         let pool_state = &mut ctx.pool_state.load_mut()?;
-        zero_for_one = ctx.input_vault.mint == pool_state.token_mint_0;
 
-        require_gt!(block_timestamp, pool_state.open_time);
+        pool_state.check_open(block_timestamp)?;
+        check_pool_access(
+            pool_state,
+            ctx.pool_state.key(),
+            remaining_accounts,
+            ctx.payer.key(),
+            PoolAccessBitIndex::Swap,
+        )?;
 
         require!(
             if zero_for_one {
@@ -127,10 +160,16 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
         );
 
         let mut tickarray_bitmap_extension = None;
-        let tick_array_states = &mut VecDeque::new();
+        let mut tick_array_states = VecDeque::new();
 
         let tick_array_bitmap_extension_key = TickArrayBitmapExtension::key(pool_state.key());
         for account_info in remaining_accounts.into_iter() {
+            if pool_state.is_swap_hook_account(account_info.key)
+                || PoolAllowListState::is_allow_list_account(account_info)
+                || LimitOrderFillState::is_limit_order_fill_account(account_info)
+            {
+                continue;
+            }
             if account_info.key().eq(&tick_array_bitmap_extension_key) {
                 tickarray_bitmap_extension = Some(
                     *(AccountLoader::<TickArrayBitmapExtension>::try_from(account_info)?
@@ -142,11 +181,14 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
             tick_array_states.push_back(AccountLoad::load_data_mut(account_info)?);
         }
 
-        (amount_0, amount_1) = swap_internal(
+        let (mut observation_state, mut observation_extension) =
+            load_observations_mut(&ctx.observation_state)?;
+        let summary = swap_internal_with_summary(
             &ctx.amm_config,
             pool_state,
-            tick_array_states,
-            &mut ctx.observation_state.load_mut()?,
+            &mut tick_array_states,
+            &mut observation_state,
+            &mut observation_extension,
             &tickarray_bitmap_extension,
             amount_calculate_specified,
             if sqrt_price_limit_x64 == 0 {
@@ -158,10 +200,19 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
             } else {
                 sqrt_price_limit_x64
             },
+            max_price_impact_bps,
             zero_for_one,
             is_base_input,
             oracle::block_timestamp(),
         )?;
+        drop(tick_array_states);
+        record_limit_order_fills(
+            ctx.pool_state.key(),
+            &summary.limit_order_fills,
+            remaining_accounts,
+        )?;
+        (amount_0, amount_1, trade_fee_rate) =
+            (summary.amount_0, summary.amount_1, summary.trade_fee_rate);
 
         #[cfg(feature = "enable-log")]
         msg!(
@@ -285,6 +336,30 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
     ctx.output_token_account.reload()?;
     ctx.input_token_account.reload()?;
 
+    if let Some((swap_hook_program, swap_hook_state)) = swap_hook {
+        let (sqrt_price_x64, tick) = {
+            let pool_state = ctx.pool_state.load()?;
+            (pool_state.sqrt_price_x64, pool_state.tick_current)
+        };
+        invoke_swap_hook(
+            swap_hook_program,
+            swap_hook_state,
+            &ctx.payer.to_account_info(),
+            &ctx.pool_state.to_account_info(),
+            &ctx.input_token_account.to_account_info(),
+            &ctx.output_token_account.to_account_info(),
+            AFTER_SWAP_HOOK,
+            &AfterSwapParams {
+                zero_for_one,
+                amount_0,
+                amount_1,
+                sqrt_price_x64,
+                tick,
+                trade_fee_rate,
+            },
+        )?;
+    }
+
     let pool_state = ctx.pool_state.load()?;
     emit!(SwapEvent {
         pool_state: pool_state.key(),
@@ -298,7 +373,8 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
         zero_for_one,
         sqrt_price_x64: pool_state.sqrt_price_x64,
         liquidity: pool_state.liquidity,
-        tick: pool_state.tick_current
+        tick: pool_state.tick_current,
+        trade_fee_rate,
     });
     if zero_for_one {
         require_gt!(swap_price_before, pool_state.sqrt_price_x64);
@@ -341,12 +417,49 @@ pub fn swap_v2<'a, 'b, 'c: 'info, 'info>(
     other_amount_threshold: u64,
     sqrt_price_limit_x64: u128,
     is_base_input: bool,
+) -> Result<()> {
+    swap_v2_checked(
+        ctx,
+        amount,
+        other_amount_threshold,
+        sqrt_price_limit_x64,
+        None,
+        is_base_input,
+    )
+}
+
+pub fn swap_v2_with_impact_limit<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2<'info>>,
+    amount: u64,
+    other_amount_threshold: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+    max_price_impact_bps: u32,
+) -> Result<()> {
+    swap_v2_checked(
+        ctx,
+        amount,
+        other_amount_threshold,
+        sqrt_price_limit_x64,
+        Some(max_price_impact_bps),
+        is_base_input,
+    )
+}
+
+fn swap_v2_checked<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2<'info>>,
+    amount: u64,
+    other_amount_threshold: u64,
+    sqrt_price_limit_x64: u128,
+    max_price_impact_bps: Option<u32>,
+    is_base_input: bool,
 ) -> Result<()> {
     let amount_result = exact_internal_v2(
         ctx.accounts,
         ctx.remaining_accounts,
         amount,
         sqrt_price_limit_x64,
+        max_price_impact_bps,
         is_base_input,
     )?;
     if is_base_input {
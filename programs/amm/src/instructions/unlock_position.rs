@@ -0,0 +1,34 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct UnlockPosition<'info> {
+    /// The position NFT holder
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint,
+        constraint = nft_account.amount == 1,
+        token::authority = nft_owner,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Release the expired lock of this position
+    #[account(mut, constraint = personal_position.locked_until != 0)]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+}
+
+pub fn unlock_position(ctx: Context<UnlockPosition>) -> Result<()> {
+    let personal_position = &mut ctx.accounts.personal_position;
+    personal_position.unlock(Clock::get()?.unix_timestamp as u64)?;
+
+    emit!(UnlockPositionEvent {
+        pool_state: personal_position.pool_id,
+        position_nft_mint: personal_position.nft_mint,
+        liquidity: personal_position.liquidity,
+    });
+
+    Ok(())
+}
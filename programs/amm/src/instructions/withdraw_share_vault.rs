@@ -0,0 +1,452 @@
+use super::burn_liquidity;
+use super::calculate_latest_token_fees;
+use super::check_unclaimed_fees_and_vault;
+use super::decrease_liquidity::collect_rewards;
+use super::fold_share_vault_fees;
+use super::get_split_amount;
+use crate::error::ErrorCode;
+use crate::libraries::{big_num::U256, full_math::MulDiv};
+use crate::states::*;
+use crate::util::{burn, get_transfer_fee, transfer_from_pool_vault_to_user};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token};
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct WithdrawShareVault<'info> {
+    /// The owner of the shares to burn
+    pub owner: Signer<'info>,
+
+    /// The share vault to withdraw from
+    #[account(constraint = share_vault.pool_id == pool_state.key())]
+    pub share_vault: Box<Account<'info, ShareVaultState>>,
+
+    /// The share token mint of the vault
+    #[account(mut, address = share_vault.share_mint)]
+    pub share_mint: Box<Account<'info, token::Mint>>,
+
+    /// The token account of the shares to burn
+    #[account(
+        mut,
+        token::mint = share_mint,
+        token::authority = owner,
+    )]
+    pub share_token_account: Box<Account<'info, token::TokenAccount>>,
+
+    /// The position owned by the vault
+    #[account(mut, address = share_vault.personal_position)]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &share_vault.tick_lower_index.to_be_bytes(),
+            &share_vault.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Token_0 vault
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_1 vault
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Stores init state for the lower tick
+    #[account(mut, constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick
+    #[account(mut, constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+
+    /// The destination token account for receive amount_0
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The destination token account for receive amount_1
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program to burn shares and transfer out tokens
+    pub token_program: Program<'info, Token>,
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+    // remaining account
+    // #[account(
+    //     seeds = [
+    //         POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
+    //         pool_state.key().as_ref(),
+    //     ],
+    //     bump
+    // )]
+    // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+    // and for each initialized reward the reward vault, the recipient token account and the reward mint
+}
+
+pub fn withdraw_share_vault<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, WithdrawShareVault<'info>>,
+    shares: u64,
+    amount_0_min: u64,
+    amount_1_min: u64,
+) -> Result<()> {
+    require!(
+        shares > 0 && shares <= ctx.accounts.share_mint.supply,
+        ErrorCode::InvalidShareAmount
+    );
+    let share_supply = ctx
+        .accounts
+        .share_vault
+        .share_supply(ctx.accounts.share_mint.supply);
+    let mut tick_array_bitmap_extension = None;
+    let mut reward_accounts = Vec::new();
+    let (liquidity, decrease_amount_0, decrease_amount_1, fees_0, fees_1) = {
+        let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+        if !pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity)? {
+            return err!(ErrorCode::NotApproved);
+        }
+        let personal_position = &mut ctx.accounts.personal_position;
+        let protocol_position = &mut ctx.accounts.protocol_position;
+        for account_info in ctx.remaining_accounts.iter() {
+            if account_info.key().eq(&TickArrayBitmapExtension::key(
+                ctx.accounts.pool_state.key(),
+            )) {
+                tick_array_bitmap_extension = Some(account_info);
+                continue;
+            }
+            reward_accounts.push(account_info);
+        }
+        if pool_state.is_overflow_default_tickarray_bitmap(vec![
+            personal_position.tick_lower_index,
+            personal_position.tick_upper_index,
+        ]) {
+            require!(
+                tick_array_bitmap_extension.is_some(),
+                ErrorCode::MissingTickArrayBitmapExtensionAccount
+            );
+        }
+
+        fold_share_vault_fees(
+            pool_state,
+            protocol_position,
+            personal_position,
+            &ctx.accounts.tick_array_lower,
+            &ctx.accounts.tick_array_upper,
+            tick_array_bitmap_extension,
+        )?;
+
+        let liquidity =
+            get_liquidity_for_shares(shares, personal_position.liquidity, share_supply)?;
+        let (decrease_amount_0, decrease_amount_1) = burn_liquidity(
+            pool_state,
+            &ctx.accounts.tick_array_lower,
+            &ctx.accounts.tick_array_upper,
+            protocol_position,
+            tick_array_bitmap_extension,
+            liquidity,
+            None,
+        )?;
+
+        personal_position.token_fees_owed_0 = calculate_latest_token_fees(
+            personal_position.token_fees_owed_0,
+            personal_position.fee_growth_inside_0_last_x64,
+            protocol_position.fee_growth_inside_0_last_x64,
+            personal_position.liquidity,
+        );
+        personal_position.token_fees_owed_1 = calculate_latest_token_fees(
+            personal_position.token_fees_owed_1,
+            personal_position.fee_growth_inside_1_last_x64,
+            protocol_position.fee_growth_inside_1_last_x64,
+            personal_position.liquidity,
+        );
+        personal_position.fee_growth_inside_0_last_x64 =
+            protocol_position.fee_growth_inside_0_last_x64;
+        personal_position.fee_growth_inside_1_last_x64 =
+            protocol_position.fee_growth_inside_1_last_x64;
+        personal_position.update_rewards(protocol_position.reward_growth_inside, true)?;
+        personal_position.liquidity = personal_position.liquidity.checked_sub(liquidity).unwrap();
+
+        // the shares also carry their part of the fees owed which were too small to reinvest
+        let mut fees_0 = 0;
+        let mut fees_1 = 0;
+        if pool_state.get_status_by_bit(PoolStatusBitIndex::CollectFee)? {
+            fees_0 = get_split_amount(
+                personal_position.token_fees_owed_0,
+                shares.into(),
+                share_supply.into(),
+            );
+            fees_1 = get_split_amount(
+                personal_position.token_fees_owed_1,
+                shares.into(),
+                share_supply.into(),
+            );
+            personal_position.token_fees_owed_0 -= fees_0;
+            personal_position.token_fees_owed_1 -= fees_1;
+            pool_state.total_fees_claimed_token_0 = pool_state
+                .total_fees_claimed_token_0
+                .checked_add(fees_0)
+                .unwrap();
+            pool_state.total_fees_claimed_token_1 = pool_state
+                .total_fees_claimed_token_1
+                .checked_add(fees_1)
+                .unwrap();
+        }
+        (
+            liquidity,
+            decrease_amount_0,
+            decrease_amount_1,
+            fees_0,
+            fees_1,
+        )
+    };
+
+    burn(
+        &ctx.accounts.owner,
+        &ctx.accounts.share_mint.to_account_info(),
+        &ctx.accounts.share_token_account.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        &[],
+        shares,
+    )?;
+
+    let transfer_fee_0 = get_transfer_fee(ctx.accounts.vault_0_mint.clone(), decrease_amount_0)?;
+    let transfer_fee_1 = get_transfer_fee(ctx.accounts.vault_1_mint.clone(), decrease_amount_1)?;
+    require_gte!(
+        decrease_amount_0 - transfer_fee_0,
+        amount_0_min,
+        ErrorCode::PriceSlippageCheck
+    );
+    require_gte!(
+        decrease_amount_1 - transfer_fee_1,
+        amount_1_min,
+        ErrorCode::PriceSlippageCheck
+    );
+    let amount_0 = decrease_amount_0 + fees_0;
+    let amount_1 = decrease_amount_1 + fees_1;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.recipient_token_account_0.to_account_info(),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_0,
+    )?;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.recipient_token_account_1.to_account_info(),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_1,
+    )?;
+    check_unclaimed_fees_and_vault(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+    )?;
+
+    // the shares carry their part of the rewards owed to the vault position, paid to any
+    // recipient accounts of the owner of the shares
+    let mut share_rewards = PersonalPositionState::default();
+    for (i, reward_amount_owed) in
+        get_share_rewards_owed(&ctx.accounts.personal_position, shares, share_supply)
+            .into_iter()
+            .enumerate()
+    {
+        share_rewards.reward_infos[i].reward_amount_owed = reward_amount_owed;
+    }
+    let reward_amounts = collect_rewards(
+        &ctx.accounts.pool_state,
+        reward_accounts.as_slice(),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        &mut share_rewards,
+        true,
+        None,
+    )?;
+    let personal_position = &mut ctx.accounts.personal_position;
+    for (reward_info, reward_amount) in personal_position
+        .reward_infos
+        .iter_mut()
+        .zip(reward_amounts)
+    {
+        reward_info.reward_amount_owed = reward_info
+            .reward_amount_owed
+            .checked_sub(reward_amount)
+            .unwrap();
+    }
+
+    emit!(WithdrawShareVaultEvent {
+        share_vault: ctx.accounts.share_vault.key(),
+        owner: ctx.accounts.owner.key(),
+        liquidity,
+        shares,
+        amount_0,
+        amount_1,
+        transfer_fee_0,
+        transfer_fee_1,
+        reward_amounts,
+    });
+
+    Ok(())
+}
+
+/// The liquidity of the vault position of `liquidity_total` backing `shares` out of `share_supply`
+pub fn get_liquidity_for_shares(
+    shares: u64,
+    liquidity_total: u128,
+    share_supply: u64,
+) -> Result<u128> {
+    let liquidity = U256::from(liquidity_total)
+        .mul_div_floor(U256::from(shares), U256::from(share_supply))
+        .unwrap()
+        .as_u128();
+    require_gt!(liquidity, 0, ErrorCode::InvalidShareAmount);
+    Ok(liquidity)
+}
+
+/// The part of the rewards owed to the vault position carried by `shares` out of `share_supply`
+pub fn get_share_rewards_owed(
+    personal_position: &PersonalPositionState,
+    shares: u64,
+    share_supply: u64,
+) -> [u64; REWARD_NUM] {
+    let mut rewards_owed = [0; REWARD_NUM];
+    for (reward_owed, reward_info) in rewards_owed.iter_mut().zip(personal_position.reward_infos) {
+        *reward_owed = get_split_amount(
+            reward_info.reward_amount_owed,
+            shares.into(),
+            share_supply.into(),
+        );
+    }
+    rewards_owed
+}
+
+#[cfg(test)]
+mod withdraw_share_vault_test {
+    use super::super::get_shares_for_liquidity;
+    use super::*;
+
+    /// The liquidity and the shares of a vault, `share_supply` counts the locked shares
+    struct Vault {
+        liquidity: u128,
+        share_supply: u64,
+    }
+
+    impl Vault {
+        fn deposit(&mut self, liquidity: u128) -> u64 {
+            let (shares, locked_shares) =
+                get_shares_for_liquidity(liquidity, self.liquidity, self.share_supply).unwrap();
+            self.liquidity += liquidity;
+            self.share_supply += shares + locked_shares;
+            shares
+        }
+
+        fn withdraw(&mut self, shares: u64) -> u128 {
+            let liquidity =
+                get_liquidity_for_shares(shares, self.liquidity, self.share_supply).unwrap();
+            self.liquidity -= liquidity;
+            self.share_supply -= shares;
+            liquidity
+        }
+    }
+
+    #[test]
+    fn deposit_withdraw_round_trip_test() {
+        let mut vault = Vault {
+            liquidity: 0,
+            share_supply: 0,
+        };
+        let shares_a = vault.deposit(1_000_000);
+        // the vault earns fees which are reinvested into its liquidity
+        vault.liquidity += 500_000;
+        let shares_b = vault.deposit(300_001);
+
+        // a deposit can never be withdrawn for more than it added
+        let liquidity_b = vault.withdraw(shares_b);
+        assert!(liquidity_b <= 300_001);
+        assert!(300_001 - liquidity_b <= 2);
+
+        // the first depositor takes the fees, less the part of the locked shares
+        let liquidity_a = vault.withdraw(shares_a);
+        assert!(liquidity_a < 1_500_000);
+        assert!(liquidity_a > 1_500_000 - 2 * u128::from(SHARE_VAULT_LOCKED_SHARES));
+
+        // the locked shares keep backing the rest of the liquidity
+        assert_eq!(vault.share_supply, SHARE_VAULT_LOCKED_SHARES);
+        assert!(vault.liquidity > 0);
+        assert!(get_liquidity_for_shares(0, vault.liquidity, vault.share_supply).is_err());
+    }
+
+    #[test]
+    fn inflate_share_value_test() {
+        let mut vault = Vault {
+            liquidity: 0,
+            share_supply: 0,
+        };
+        // the smallest first deposit leaves a single share to its owner
+        let shares_a = vault.deposit(u128::from(SHARE_VAULT_LOCKED_SHARES) + 1);
+        assert_eq!(shares_a, 1);
+        // even after a large donation to the vault liquidity, the next deposit keeps its value
+        vault.liquidity += 1_000_000_000;
+        let shares_b = vault.deposit(10_000_000);
+        let liquidity_b = vault.withdraw(shares_b);
+        assert!(10_000_000 - liquidity_b <= 1_000_000_000 / u128::from(vault.share_supply));
+        assert!(liquidity_b >= 9_900_000);
+    }
+
+    #[test]
+    fn share_rewards_owed_test() {
+        let mut personal_position = PersonalPositionState::default();
+        personal_position.reward_infos[0].reward_amount_owed = 1_000;
+        personal_position.reward_infos[1].reward_amount_owed = 10;
+        assert_eq!(
+            get_share_rewards_owed(&personal_position, 250, 1_000),
+            [250, 2, 0]
+        );
+        assert_eq!(
+            get_share_rewards_owed(&personal_position, 1_000, 1_000),
+            [1_000, 10, 0]
+        );
+        // the rewards of the locked shares are never paid out
+        assert_eq!(
+            get_share_rewards_owed(&personal_position, 999, 1_000),
+            [999, 9, 0]
+        );
+    }
+}
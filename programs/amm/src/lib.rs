@@ -91,7 +91,11 @@ pub mod amm_v3 {
     /// * `fund_fee_rate`- The new fund fee rate of amm config, be set when `param` is 2
     /// * `new_owner`- The config's new owner, be set when `param` is 3
     /// * `new_fund_owner`- The config's new fund owner, be set when `param` is 4
-    /// * `param`- The value can be 0 | 1 | 2 | 3 | 4, otherwise will report a error
+    /// * `max_observation_cardinality`- The max observation cardinality of the pools, be set when `param` is 5
+    /// * `dynamic_fee_max_rate`- The max trade fee rate of dynamic fee mode, zero disables it, be set when `param` is 6
+    /// * `dynamic_fee_control`- The trade fee rate added per tick spacing of volatility, below the fee rate denominator, be set when `param` is 7
+    /// * `dynamic_fee_decay_period`- The seconds for the volatility to decay to zero, from 1 up to one day, be set when `param` is 8
+    /// * `param`- The value can be 0 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8, otherwise will report a error
     ///
     pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u8, value: u32) -> Result<()> {
         instructions::update_amm_config(ctx, param, value)
@@ -103,7 +107,7 @@ pub mod amm_v3 {
     ///
     /// * `ctx`- The context of accounts
     /// * `sqrt_price_x64` - the initial sqrt price (amount_token_1 / amount_token_0) of the pool as a Q64.64
-    /// Note: The open_time must be smaller than the current block_timestamp on chain.
+    /// * `open_time` - the timestamp after which swaps are allowed, liquidity can be added before it
     pub fn create_pool(
         ctx: Context<CreatePool>,
         sqrt_price_x64: u128,
@@ -123,6 +127,126 @@ pub mod amm_v3 {
         instructions::update_pool_status(ctx, status)
     }
 
+    /// Update pool status with a reason code and an optional scheduled resume.
+    /// Must be called by the admin or an operation owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `status` - The value of status
+    /// * `reason` - The reason code of the change, zero means unspecified
+    /// * `resume_time` - The time the status is lifted, zero means never
+    ///
+    pub fn update_pool_status_v2(
+        ctx: Context<UpdatePoolStatusV2>,
+        status: u8,
+        reason: u8,
+        resume_time: u64,
+    ) -> Result<()> {
+        instructions::update_pool_status_v2(ctx, status, reason, resume_time)
+    }
+
+    /// Put a pool in emergency exit. In emergency exit swaps and adding liquidity are disabled,
+    /// and positions can be withdrawn at the current price with `emergency_withdraw`.
+    /// The emergency exit is one-way, a pool in emergency exit can not be taken out of it.
+    /// When the vaults hold less than the positions are owed, every position is paid the same
+    /// proportion, and decreasing liquidity and collecting fees are disabled
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `emergency_exit` - Must be true
+    /// * `owed_0` - The token_0 owed to the positions at the current price, fees owed included, computed off chain
+    /// * `owed_1` - The token_1 owed to the positions at the current price, fees owed included, computed off chain
+    ///
+    pub fn update_pool_emergency_exit(
+        ctx: Context<UpdatePoolEmergencyExit>,
+        emergency_exit: bool,
+        owed_0: u64,
+        owed_1: u64,
+    ) -> Result<()> {
+        instructions::update_pool_emergency_exit(ctx, emergency_exit, owed_0, owed_1)
+    }
+
+    /// Set the trade fee of a pool, overriding the trade fee of its amm config.
+    /// Must be called by the admin or an operation owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `trade_fee_rate` - The trade fee rate of the pool, it can be zero, none clears the override
+    ///
+    pub fn update_pool_trade_fee_rate(
+        ctx: Context<UpdatePoolTradeFeeRate>,
+        trade_fee_rate: Option<u32>,
+    ) -> Result<()> {
+        instructions::update_pool_trade_fee_rate(ctx, trade_fee_rate)
+    }
+
+    /// Set the swap hook of a pool, the hook program is called before and after each swap.
+    /// Must be called by the admin or an operation owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `swap_hook_program` - The hook program, default key clears the hook
+    /// * `swap_hook_state` - The account passed writable to the hook program on each swap
+    ///
+    pub fn update_pool_swap_hook(
+        ctx: Context<UpdatePoolSwapHook>,
+        swap_hook_program: Pubkey,
+        swap_hook_state: Pubkey,
+    ) -> Result<()> {
+        instructions::update_pool_swap_hook(ctx, swap_hook_program, swap_hook_state)
+    }
+
+    /// Creates the allow list of a pool, which holds the wallets allowed in the restricted accesses.
+    /// Must be called by the admin or an operation owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn create_pool_allow_list(ctx: Context<CreatePoolAllowList>) -> Result<()> {
+        instructions::create_pool_allow_list(ctx)
+    }
+
+    /// Update the allow list of a pool.
+    /// Must be called by the admin or an operation owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `param`- The value can be 0 | 1 | 2 | 3, otherwise will report a error
+    /// * `keys`- add traders when the `param` is 0
+    ///           remove traders when the `param` is 1
+    ///           add liquidity providers when the `param` is 2
+    ///           remove liquidity providers when the `param` is 3
+    ///
+    pub fn update_pool_allow_list(
+        ctx: Context<UpdatePoolAllowList>,
+        param: u8,
+        keys: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::update_pool_allow_list(ctx, param, keys)
+    }
+
+    /// Restrict the accesses of a pool to its allow list, the allow list must then be passed
+    /// in the remaining accounts of the restricted instructions.
+    /// Must be called by the admin or an operation owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `access_mode` - bit0 restricts swaps, bit1 restricts opening positions and adding liquidity
+    ///
+    pub fn update_pool_access_mode(
+        ctx: Context<UpdatePoolAccessMode>,
+        access_mode: u8,
+    ) -> Result<()> {
+        instructions::update_pool_access_mode(ctx, access_mode)
+    }
+
     /// Creates an operation account for the program
     ///
     /// # Arguments
@@ -348,6 +472,46 @@ pub mod amm_v3 {
         )
     }
 
+    /// Creates a new position wrapped in a NFT from a single input token.
+    /// The part of the input the range needs in the other token is swapped through the same pool,
+    /// the tokens left over are sent back to the payer
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `tick_lower_index` - The low boundary of market
+    /// * `tick_upper_index` - The upper boundary of market
+    /// * `tick_array_lower_start_index` - The start index of tick array which include tick low
+    /// * `tick_array_upper_start_index` - The start index of tick array which include tick upper
+    /// * `amount_in` - The amount of the input token to spend
+    /// * `zero_for_one` - true: the input token is token_0, otherwise token_1
+    /// * `liquidity_min` - The minimum liquidity to be added, which serves as a slippage check
+    /// * `with_metadata` - The flag indicating whether to create NFT mint metadata
+    ///
+    pub fn open_position_zap<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, OpenPositionZap<'info>>,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        tick_array_lower_start_index: i32,
+        tick_array_upper_start_index: i32,
+        amount_in: u64,
+        zero_for_one: bool,
+        liquidity_min: u128,
+        with_metadata: bool,
+    ) -> Result<()> {
+        instructions::open_position_zap(
+            ctx,
+            tick_lower_index,
+            tick_upper_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+            amount_in,
+            zero_for_one,
+            liquidity_min,
+            with_metadata,
+        )
+    }
+
     /// Creates a new position wrapped in a Token2022 NFT without relying on metadata_program and metadata_account, reduce the cost for user to create a personal position.
     ///
     /// # Arguments
@@ -386,6 +550,57 @@ pub mod amm_v3 {
             tick_array_upper_start_index,
             with_metadata,
             base_flag,
+            false,
+        )
+    }
+
+    /// Creates a limit order, a position of one tick spacing wrapped in a Token2022 NFT.
+    /// The range must be inside one tick array and entirely above or below the current price, an order above the price sells token_0
+    /// and an order below the price sells token_1. Once the price fully crosses the range, its liquidity is withdrawn from the pool
+    /// and the position holds only the filled token, which is not converted back when the price returns.
+    /// The fees and rewards earned up to the fill are recorded in the fill account of the order batch, PDA of
+    /// `[LIMIT_ORDER_FILL_SEED, pool_state, fill_tick, fill_count]`, which must be passed writable in the remaining accounts.
+    /// It is created with the first order of the batch, which also grows the tick array by room for the fill of the tick.
+    /// Swaps keep the fill in the tick array and do not need the fill account, the fill is written to it by the first settle
+    /// of an order of the batch, or by the first order of the next batch, which must pass the fill account of the previous batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `tick_lower_index` - The low boundary of market
+    /// * `tick_upper_index` - The upper boundary of market, must be one tick spacing above `tick_lower_index`
+    /// * `tick_array_lower_start_index` - The start index of tick array which include tick low
+    /// * `tick_array_upper_start_index` - The start index of tick array which include tick upper, must be equal to `tick_array_lower_start_index`
+    /// * `liquidity` - The liquidity to be added, if zero, and the base_flag is specified, calculate liquidity base amount_0_max or amount_1_max according base_flag, otherwise open position with zero liquidity
+    /// * `amount_0_max` - The max amount of token_0 to spend, which serves as a slippage check
+    /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
+    /// * `with_metadata` - The flag indicating whether to create NFT mint metadata
+    /// * `base_flag` - if the liquidity specified as zero, true: calculate liquidity base amount_0_max otherwise base amount_1_max
+    ///
+    pub fn open_limit_order<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, OpenPositionWithToken22Nft<'info>>,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        tick_array_lower_start_index: i32,
+        tick_array_upper_start_index: i32,
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+        with_metadata: bool,
+        base_flag: Option<bool>,
+    ) -> Result<()> {
+        instructions::open_position_with_token22_nft(
+            ctx,
+            liquidity,
+            amount_0_max,
+            amount_1_max,
+            tick_lower_index,
+            tick_upper_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+            with_metadata,
+            base_flag,
+            true,
         )
     }
 
@@ -481,6 +696,295 @@ pub mod amm_v3 {
         instructions::decrease_liquidity_v2(ctx, liquidity, amount_0_min, amount_1_min)
     }
 
+    /// Decreases liquidity for an existing position and pays out a single token, support Token2022.
+    /// The other token, fees included, is swapped through the same pool
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    /// * `liquidity` - The amount by which liquidity will be decreased
+    /// * `zero_for_one` - true: token_0 is swapped and token_1 is paid out, otherwise token_1 is swapped and token_0 is paid out
+    /// * `min_amount_out` - The minimum amount of the paid out token after the transfer fee, fees included
+    ///
+    pub fn decrease_liquidity_to_single<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, DecreaseLiquidityToSingle<'info>>,
+        liquidity: u128,
+        zero_for_one: bool,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        instructions::decrease_liquidity_to_single(ctx, liquidity, zero_for_one, min_amount_out)
+    }
+
+    /// Withdraws all the liquidity of a position from a pool in emergency exit at the current price,
+    /// together with the fees owed to the position, support Token2022.
+    /// Limit orders, locked positions and positions held by a share vault have no emergency path
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    ///
+    pub fn emergency_withdraw<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, EmergencyWithdraw<'info>>,
+    ) -> Result<()> {
+        instructions::emergency_withdraw(ctx)
+    }
+
+    /// Collects the fees owed to a position without changing its liquidity, support Token2022
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    /// * `amount_0_requested` - The maximum amount of token_0 to collect, capped by the fees owed
+    /// * `amount_1_requested` - The maximum amount of token_1 to collect, capped by the fees owed
+    ///
+    pub fn collect_fees<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectFees<'info>>,
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    ) -> Result<()> {
+        instructions::collect_fees(ctx, amount_0_requested, amount_1_requested)
+    }
+
+    /// Collects one reward owed to a position without changing its liquidity, support Token2022
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    /// * `reward_index` - The index of the reward to collect
+    /// * `amount_requested` - The maximum amount of reward to collect, capped by the reward owed
+    ///
+    pub fn collect_rewards<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectPositionRewards<'info>>,
+        reward_index: u8,
+        amount_requested: u64,
+    ) -> Result<()> {
+        instructions::collect_position_rewards(ctx, reward_index, amount_requested)
+    }
+
+    /// Reinvests the fees owed to a position into its own range, the fees that can not be
+    /// added at the current price are left as fees owed
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    ///
+    pub fn compound_position<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CompoundPosition<'info>>,
+    ) -> Result<()> {
+        instructions::compound_position(ctx)
+    }
+
+    /// Moves all the liquidity of a position to a new price range, keeping the position NFT.
+    /// The imbalance can be swapped through the same pool, the fees and the tokens left over are sent to the recipient
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    /// * `tick_lower_index` - The low boundary of the new range
+    /// * `tick_upper_index` - The upper boundary of the new range
+    /// * `tick_array_lower_start_index` - The start index of the tick array which include the new tick low
+    /// * `tick_array_upper_start_index` - The start index of the tick array which include the new tick upper
+    /// * `amount_0_min` - The minimum amount of token_0 that should be withdrawn from the current range
+    /// * `amount_1_min` - The minimum amount of token_1 that should be withdrawn from the current range
+    /// * `swap_amount` - The input amount swapped through the pool before the deposit, zero for no swap
+    /// * `zero_for_one` - The direction of the swap
+    /// * `other_amount_threshold` - The minimum output amount of the swap
+    ///
+    pub fn rebalance_position<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, RebalancePosition<'info>>,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        tick_array_lower_start_index: i32,
+        tick_array_upper_start_index: i32,
+        amount_0_min: u64,
+        amount_1_min: u64,
+        swap_amount: u64,
+        zero_for_one: bool,
+        other_amount_threshold: u64,
+    ) -> Result<()> {
+        instructions::rebalance_position(
+            ctx,
+            tick_lower_index,
+            tick_upper_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+            amount_0_min,
+            amount_1_min,
+            swap_amount,
+            zero_for_one,
+            other_amount_threshold,
+        )
+    }
+
+    /// Moves part of the liquidity of a position, with the proportional fees and rewards owed,
+    /// to a new position of the same range
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    /// * `liquidity` - The liquidity moved to the new position, must be less than the position liquidity
+    /// * `with_metadata` - The flag to create metadata for the new position NFT
+    ///
+    pub fn split_position<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SplitPosition<'info>>,
+        liquidity: u128,
+        with_metadata: bool,
+    ) -> Result<()> {
+        instructions::split_position(ctx, liquidity, with_metadata)
+    }
+
+    /// Merges a position into another one of the same pool and range, and closes it
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    ///
+    pub fn merge_positions<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, MergePositions<'info>>,
+    ) -> Result<()> {
+        instructions::merge_positions(ctx)
+    }
+
+    /// Sets or revokes the delegate allowed to manage a position on behalf of the NFT holder,
+    /// the payouts of the delegate always go to token accounts of the NFT holder.
+    /// The delegation lapses once the NFT is transferred to another holder
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    /// * `delegate` - The delegate of the position, `Pubkey::default()` to revoke
+    /// * `permissions` - Bitmask of `PositionPermissionBitIndex`: collect, compound, rebalance, decrease liquidity
+    ///
+    pub fn set_position_delegate(
+        ctx: Context<SetPositionDelegate>,
+        delegate: Pubkey,
+        permissions: u8,
+    ) -> Result<()> {
+        instructions::set_position_delegate(ctx, delegate, permissions)
+    }
+
+    /// Locks the liquidity of a position until a timestamp, or extends the current lock.
+    /// Until then liquidity can not be decreased and the position can not be closed,
+    /// fees and rewards can still be collected
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    /// * `locked_until` - The unix timestamp until which the liquidity is locked
+    ///
+    pub fn lock_position(ctx: Context<LockPosition>, locked_until: u64) -> Result<()> {
+        instructions::lock_position(ctx, locked_until)
+    }
+
+    /// Releases the expired lock of a position
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    ///
+    pub fn unlock_position(ctx: Context<UnlockPosition>) -> Result<()> {
+        instructions::unlock_position(ctx)
+    }
+
+    /// Locks the liquidity of a position forever, the position NFT is held in the custody of the program
+    /// and a Token-2022 receipt NFT is minted to the owner to keep collecting fees and rewards
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    /// * `with_metadata` - The flag to create metadata for the receipt NFT
+    ///
+    pub fn lock_position_forever<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, LockPositionForever<'info>>,
+        with_metadata: bool,
+    ) -> Result<()> {
+        instructions::lock_position_forever(ctx, with_metadata)
+    }
+
+    /// Collects the fees and all rewards owed to a position locked forever, signed by the receipt NFT holder
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    /// * `amount_0_requested` - The max amount of token_0 fees to collect
+    /// * `amount_1_requested` - The max amount of token_1 fees to collect
+    ///
+    pub fn collect_locked_position<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectLockedPosition<'info>>,
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    ) -> Result<()> {
+        instructions::collect_locked_position(ctx, amount_0_requested, amount_1_requested)
+    }
+
+    /// Creates a share vault owning one position over a fixed range, with an SPL share token of its liquidity.
+    /// Pools with rewards are not supported
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    /// * `tick_lower_index` - The low boundary of the vault range
+    /// * `tick_upper_index` - The upper boundary of the vault range
+    /// * `tick_array_lower_start_index` - The start index of tick array which include tick low
+    /// * `tick_array_upper_start_index` - The start index of tick array which include tick upper
+    ///
+    pub fn create_share_vault<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CreateShareVault<'info>>,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        tick_array_lower_start_index: i32,
+        tick_array_upper_start_index: i32,
+    ) -> Result<()> {
+        instructions::create_share_vault(
+            ctx,
+            tick_lower_index,
+            tick_upper_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+        )
+    }
+
+    /// Deposits liquidity into a share vault and mints shares, support Token2022.
+    /// The fees earned by the vault are reinvested first, and the first deposit locks `SHARE_VAULT_LOCKED_SHARES`
+    /// of its shares. The vault freezes once a reward is initialized on its pool: deposits are
+    /// rejected and the shares can only be withdrawn
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    /// * `liquidity` - The liquidity to add to the vault position
+    /// * `amount_0_max` - The max amount of token_0 to spend, which serves as a slippage check
+    /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
+    ///
+    pub fn deposit_share_vault<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, DepositShareVault<'info>>,
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+    ) -> Result<()> {
+        instructions::deposit_share_vault(ctx, liquidity, amount_0_max, amount_1_max)
+    }
+
+    /// Burns shares of a share vault and withdraws their liquidity, support Token2022.
+    /// The fees earned by the vault are reinvested first, and the shares are paid their part of
+    /// the rewards owed to the vault, the reward accounts are passed in the remaining accounts
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    /// * `shares` - The amount of shares to burn
+    /// * `amount_0_min` - The minimum amount of token_0 that should be accounted for the burned liquidity
+    /// * `amount_1_min` - The minimum amount of token_1 that should be accounted for the burned liquidity
+    ///
+    pub fn withdraw_share_vault<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, WithdrawShareVault<'info>>,
+        shares: u64,
+        amount_0_min: u64,
+        amount_1_min: u64,
+    ) -> Result<()> {
+        instructions::withdraw_share_vault(ctx, shares, amount_0_min, amount_1_min)
+    }
+
     /// #[deprecated(note = "Use `swap_v2` instead.")]
     /// Swaps one token for as much as possible of another token across a single pool
     ///
@@ -508,6 +1012,36 @@ pub mod amm_v3 {
         )
     }
 
+    /// #[deprecated(note = "Use `swap_v2_with_impact_limit` instead.")]
+    /// Swaps like `swap`, and fails if the price moves further than the max price impact
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - Arranged in pairs with other_amount_threshold. (amount_in, amount_out_minimum) or (amount_out, amount_in_maximum)
+    /// * `other_amount_threshold` - For slippage check
+    /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit. If zero for one, the price cannot
+    /// * `is_base_input` - swap base input or swap base output
+    /// * `max_price_impact_bps` - The max move of the price from the pool price before the swap, in basis points
+    ///
+    pub fn swap_with_impact_limit<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapSingle<'info>>,
+        amount: u64,
+        other_amount_threshold: u64,
+        sqrt_price_limit_x64: u128,
+        is_base_input: bool,
+        max_price_impact_bps: u32,
+    ) -> Result<()> {
+        instructions::swap_with_impact_limit(
+            ctx,
+            amount,
+            other_amount_threshold,
+            sqrt_price_limit_x64,
+            is_base_input,
+            max_price_impact_bps,
+        )
+    }
+
     /// Swaps one token for as much as possible of another token across a single pool, support token program 2022
     ///
     /// # Arguments
@@ -534,6 +1068,35 @@ pub mod amm_v3 {
         )
     }
 
+    /// Swaps like `swap_v2`, and fails if the price moves further than the max price impact
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - Arranged in pairs with other_amount_threshold. (amount_in, amount_out_minimum) or (amount_out, amount_in_maximum)
+    /// * `other_amount_threshold` - For slippage check
+    /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit. If zero for one, the price cannot
+    /// * `is_base_input` - swap base input or swap base output
+    /// * `max_price_impact_bps` - The max move of the price from the pool price before the swap, in basis points
+    ///
+    pub fn swap_v2_with_impact_limit<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2<'info>>,
+        amount: u64,
+        other_amount_threshold: u64,
+        sqrt_price_limit_x64: u128,
+        is_base_input: bool,
+        max_price_impact_bps: u32,
+    ) -> Result<()> {
+        instructions::swap_v2_with_impact_limit(
+            ctx,
+            amount,
+            other_amount_threshold,
+            sqrt_price_limit_x64,
+            is_base_input,
+            max_price_impact_bps,
+        )
+    }
+
     /// Swap token for as much as possible of another token across the path provided, base input
     ///
     /// # Arguments
@@ -549,4 +1112,109 @@ pub mod amm_v3 {
     ) -> Result<()> {
         instructions::swap_router_base_in(ctx, amount_in, amount_out_minimum)
     }
+
+    /// Swap as little as possible of a token for an exact amount of another token across the path provided, base output
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_out` - Token amount to be received at the end of the path
+    /// * `amount_in_maximum` - Panic if input amount is above maximum amount. For slippage.
+    ///
+    pub fn swap_router_base_out<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseOut<'info>>,
+        amount_out: u64,
+        amount_in_maximum: u64,
+    ) -> Result<()> {
+        instructions::swap_router_base_out(ctx, amount_out, amount_in_maximum)
+    }
+
+    /// Quote a swap without changing any account, the result is returned through return data
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - amount_in if is_base_input, otherwise amount_out
+    /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit, zero means no limit
+    /// * `zero_for_one` - The direction of the swap, true for token_0 to token_1
+    /// * `is_base_input` - swap base input or swap base output
+    ///
+    pub fn quote_swap<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, QuoteSwap<'info>>,
+        amount: u64,
+        sqrt_price_limit_x64: u128,
+        zero_for_one: bool,
+        is_base_input: bool,
+    ) -> Result<SwapQuote> {
+        instructions::quote_swap(
+            ctx,
+            amount,
+            sqrt_price_limit_x64,
+            zero_for_one,
+            is_base_input,
+        )
+    }
+
+    /// Lends tokens out of the pool vaults to a receiver program, which must pay them back
+    /// with a fee derived from the trade fee rate before it returns
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_0` - The amount of token_0 to borrow
+    /// * `amount_1` - The amount of token_1 to borrow
+    /// * `data` - The instruction data the receiver program is called with
+    ///
+    pub fn flash<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, Flash<'info>>,
+        amount_0: u64,
+        amount_1: u64,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::flash(ctx, amount_0, amount_1, data)
+    }
+
+    /// Read the oracle tick cumulatives of a pool, the result is returned through return data
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `seconds_agos` - From how long ago each tick cumulative should be returned, the
+    /// time-weighted average tick is computed between the first and the last one
+    ///
+    pub fn observe(ctx: Context<Observe>, seconds_agos: Vec<u32>) -> Result<ObserveResult> {
+        instructions::observe(ctx, seconds_agos)
+    }
+
+    /// Grow the observation account of a pool so that the oracle covers a longer history.
+    /// Anyone can pay for it, up to the max observation cardinality of the amm config.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `observation_cardinality_next` - The number of observations the account should hold
+    ///
+    pub fn increase_observation_cardinality(
+        ctx: Context<IncreaseObservationCardinality>,
+        observation_cardinality_next: u16,
+    ) -> Result<()> {
+        instructions::increase_observation_cardinality(ctx, observation_cardinality_next)
+    }
+
+    /// Returns a snapshot of the seconds per liquidity and the seconds spent inside a tick range,
+    /// the difference between two snapshots measures the time a position was in range.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `tick_lower_index` - The lower tick of the range, must be initialized
+    /// * `tick_upper_index` - The upper tick of the range, must be initialized
+    ///
+    pub fn snapshot_cumulatives_inside(
+        ctx: Context<SnapshotCumulativesInside>,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+    ) -> Result<CumulativesInside> {
+        instructions::snapshot_cumulatives_inside(ctx, tick_lower_index, tick_upper_index)
+    }
 }
@@ -1,9 +1,12 @@
 use crate::error::ErrorCode;
+use crate::states::OBSERVATION_CARDINALITY_MAX_DEFAULT;
 use anchor_lang::prelude::*;
 
 pub const AMM_CONFIG_SEED: &str = "amm_config";
 
 pub const FEE_RATE_DENOMINATOR_VALUE: u32 = 1_000_000;
+/// The longest volatility decay period of the dynamic fee mode, one day
+pub const DYNAMIC_FEE_DECAY_PERIOD_MAX: u32 = 24 * 60 * 60;
 
 /// Holds the current owner of the factory
 #[account]
@@ -22,10 +25,20 @@ pub struct AmmConfig {
     pub tick_spacing: u16,
     /// The fund fee, denominated in hundredths of a bip (10^-6)
     pub fund_fee_rate: u32,
+    /// The max observation cardinality of the pools, zero means OBSERVATION_CARDINALITY_MAX_DEFAULT
+    pub max_observation_cardinality: u16,
     // padding space for upgrade
-    pub padding_u32: u32,
+    pub padding_u16: u16,
     pub fund_owner: Pubkey,
-    pub padding: [u64; 3],
+    /// The max trade fee of the dynamic fee mode, denominated in hundredths of a bip (10^-6), zero disables dynamic fee
+    pub dynamic_fee_max_rate: u32,
+    /// The trade fee added per tick spacing of volatility, denominated in hundredths of a bip (10^-6)
+    pub dynamic_fee_control: u32,
+    /// The seconds it takes the volatility of past swaps to decay to zero
+    pub dynamic_fee_decay_period: u32,
+    // padding space for upgrade
+    pub padding_u32: u32,
+    pub padding: [u64; 1],
 }
 
 impl AmmConfig {
@@ -42,6 +55,28 @@ impl AmmConfig {
         );
         Ok(())
     }
+
+    /// The max number of observations a pool of this config can grow to
+    pub fn observation_cardinality_max(&self) -> u16 {
+        if self.max_observation_cardinality == 0 {
+            OBSERVATION_CARDINALITY_MAX_DEFAULT
+        } else {
+            self.max_observation_cardinality
+        }
+    }
+
+    pub fn is_dynamic_fee_enabled(&self) -> bool {
+        self.dynamic_fee_max_rate != 0
+    }
+
+    /// The trade fee rate for the given volatility, from the base trade fee rate up to dynamic_fee_max_rate
+    pub fn dynamic_trade_fee_rate(&self, base_trade_fee_rate: u32, volatility: u32) -> u32 {
+        let fee_rate = u64::from(base_trade_fee_rate)
+            + u64::from(self.dynamic_fee_control) * u64::from(volatility);
+        fee_rate.min(u64::from(
+            self.dynamic_fee_max_rate.max(base_trade_fee_rate),
+        )) as u32
+    }
 }
 
 /// Emitted when create or update a config
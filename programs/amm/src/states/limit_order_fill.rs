@@ -0,0 +1,495 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::create_or_allocate_account;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+use std::cell::{Ref, RefMut};
+
+pub const LIMIT_ORDER_FILL_SEED: &str = "limit_order_fill";
+
+/// Fee and reward growth inside the range of one batch of limit orders when it was filled,
+/// the orders of the batch earn up to this snapshot and are paid on settle
+///
+/// PDA of `[LIMIT_ORDER_FILL_SEED, pool_id, tick, fill_count]`
+///
+#[account]
+#[derive(Default, Debug)]
+pub struct LimitOrderFillState {
+    /// Bump to identify PDA
+    pub bump: [u8; 1],
+    /// The pool of the limit orders
+    pub pool_id: Pubkey,
+    /// The tick which fills the limit orders when crossed
+    pub tick: i32,
+    /// The fill count of the tick at which the batch is filled
+    pub fill_count: u64,
+    /// Whether the batch has been filled
+    pub filled: bool,
+    /// The fee growth of token_0 inside the range of the orders at the fill
+    pub fee_growth_inside_0_x64: u128,
+    /// The fee growth of token_1 inside the range of the orders at the fill
+    pub fee_growth_inside_1_x64: u128,
+    /// The reward growths inside the range of the orders at the fill
+    pub reward_growths_inside_x64: [u128; REWARD_NUM],
+    pub padding: [u64; 4],
+}
+
+impl LimitOrderFillState {
+    pub const LEN: usize = 8 + 1 + 32 + 4 + 8 + 1 + 16 + 16 + 16 * REWARD_NUM + 8 * 4;
+
+    pub fn key(pool_id: &Pubkey, tick: i32, fill_count: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                LIMIT_ORDER_FILL_SEED.as_bytes(),
+                pool_id.as_ref(),
+                &tick.to_be_bytes(),
+                &fill_count.to_be_bytes(),
+            ],
+            &crate::id(),
+        )
+    }
+
+    pub fn is_limit_order_fill_account(account_info: &AccountInfo) -> bool {
+        account_info.owner == &crate::id()
+            && account_info
+                .try_borrow_data()
+                .map(|data| data.len() >= 8 && data[..8] == LimitOrderFillState::discriminator())
+                .unwrap_or(false)
+    }
+
+    /// Creates the fill account of a batch of limit orders if it does not exist yet, the account
+    /// must be in the remaining accounts
+    pub fn create_if_needed<'info>(
+        payer: AccountInfo<'info>,
+        system_program: AccountInfo<'info>,
+        remaining_accounts: &[AccountInfo<'info>],
+        pool_id: Pubkey,
+        tick: i32,
+        fill_count: u64,
+    ) -> Result<()> {
+        let (expect_pda_address, bump) = LimitOrderFillState::key(&pool_id, tick, fill_count);
+        let account_info = remaining_accounts
+            .iter()
+            .find(|account_info| account_info.key() == expect_pda_address)
+            .ok_or(ErrorCode::LimitOrderFillAccountMissing)?;
+        if account_info.owner != &system_program::ID {
+            return Ok(());
+        }
+        create_or_allocate_account(
+            &crate::id(),
+            payer,
+            system_program,
+            account_info.clone(),
+            &[
+                LIMIT_ORDER_FILL_SEED.as_bytes(),
+                pool_id.as_ref(),
+                &tick.to_be_bytes(),
+                &fill_count.to_be_bytes(),
+                &[bump],
+            ],
+            LimitOrderFillState::LEN,
+        )?;
+        let fill_state = LimitOrderFillState {
+            bump: [bump],
+            pool_id,
+            tick,
+            fill_count,
+            ..Default::default()
+        };
+        fill_state.try_serialize(&mut &mut account_info.try_borrow_mut_data()?[..])
+    }
+
+    /// Loads the fill account of a batch of limit orders from the remaining accounts. The fill is
+    /// written to the account from the snapshot kept in the tick array on the first settle.
+    pub fn load(
+        remaining_accounts: &[AccountInfo],
+        tick_array_info: &AccountInfo,
+        pool_id: Pubkey,
+        tick: i32,
+        fill_count: u64,
+    ) -> Result<LimitOrderFillState> {
+        let account_info =
+            LimitOrderFillState::find(remaining_accounts, &pool_id, tick, fill_count)?;
+        let mut fill_state =
+            LimitOrderFillState::try_deserialize(&mut &account_info.try_borrow_data()?[..])?;
+        if !fill_state.filled {
+            let (_, fills) = load_limit_order_fills(tick_array_info)?;
+            let fill = fills
+                .iter()
+                .find(|fill| fill.tick == tick && fill.fill_count == fill_count)
+                .ok_or(ErrorCode::InvalidLimitOrder)?;
+            fill_state.record(fill);
+            fill_state.try_serialize(&mut &mut account_info.try_borrow_mut_data()?[..])?;
+        }
+        Ok(fill_state)
+    }
+
+    fn find<'a, 'info>(
+        remaining_accounts: &'a [AccountInfo<'info>],
+        pool_id: &Pubkey,
+        tick: i32,
+        fill_count: u64,
+    ) -> Result<&'a AccountInfo<'info>> {
+        let (expect_pda_address, _) = LimitOrderFillState::key(pool_id, tick, fill_count);
+        remaining_accounts
+            .iter()
+            .find(|account_info| account_info.key() == expect_pda_address)
+            .ok_or(error!(ErrorCode::LimitOrderFillAccountMissing))
+    }
+
+    fn record(&mut self, fill: &LimitOrderFill) {
+        self.filled = true;
+        self.fee_growth_inside_0_x64 = fill.fee_growth_inside_0_x64;
+        self.fee_growth_inside_1_x64 = fill.fee_growth_inside_1_x64;
+        self.reward_growths_inside_x64 = fill.reward_growths_inside_x64;
+    }
+}
+
+/// The growth inside the range of a batch of limit orders filled by a swap. The last fill of
+/// each order tick is kept after the TickArrayState in the tick array account, until the next
+/// batch of the tick is opened or an order of the batch is settled.
+#[zero_copy(unsafe)]
+#[repr(C, packed)]
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct LimitOrderFill {
+    pub tick: i32,
+    /// Zero when the tick holds no fill waiting to be written to its fill account
+    pub fill_count: u64,
+    pub fee_growth_inside_0_x64: u128,
+    pub fee_growth_inside_1_x64: u128,
+    pub reward_growths_inside_x64: [u128; REWARD_NUM],
+}
+
+impl LimitOrderFill {
+    pub const LEN: usize = 4 + 8 + 16 + 16 + 16 * REWARD_NUM;
+}
+
+// Fills past the TickArrayState are cast straight from the account data like the observations
+// of a grown ObservationState, packed with plain integer fields only.
+unsafe impl bytemuck::Zeroable for LimitOrderFill {}
+unsafe impl bytemuck::Pod for LimitOrderFill {}
+
+/// Loads a tick array account for writing, together with the limit order fills stored after
+/// the TickArrayState
+pub fn load_limit_order_fills_mut<'a>(
+    account_info: &'a AccountInfo,
+) -> Result<(RefMut<'a, TickArrayState>, RefMut<'a, [LimitOrderFill]>)> {
+    if !account_info.is_writable {
+        return err!(anchor_lang::error::ErrorCode::AccountNotMutable);
+    }
+    check_tick_array_account(account_info)?;
+    let data = account_info.try_borrow_mut_data()?;
+    Ok(RefMut::map_split(data, |data| {
+        let (state_data, fills_data) = data.split_at_mut(TickArrayState::LEN);
+        (
+            bytemuck::from_bytes_mut(&mut state_data[8..]),
+            bytemuck::cast_slice_mut(fills_data),
+        )
+    }))
+}
+
+/// Loads a tick array account for reading, together with the limit order fills stored after
+/// the TickArrayState
+pub fn load_limit_order_fills<'a>(
+    account_info: &'a AccountInfo,
+) -> Result<(Ref<'a, TickArrayState>, Ref<'a, [LimitOrderFill]>)> {
+    check_tick_array_account(account_info)?;
+    let data = account_info.try_borrow_data()?;
+    Ok(Ref::map_split(data, |data| {
+        let (state_data, fills_data) = data.split_at(TickArrayState::LEN);
+        (
+            bytemuck::from_bytes(&state_data[8..]),
+            bytemuck::cast_slice(fills_data),
+        )
+    }))
+}
+
+fn check_tick_array_account(account_info: &AccountInfo) -> Result<()> {
+    if account_info.owner != &crate::id() {
+        return Err(
+            Error::from(anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram)
+                .with_pubkeys((*account_info.owner, crate::id())),
+        );
+    }
+    let data = account_info.try_borrow_data()?;
+    require!(
+        data.len() >= TickArrayState::LEN
+            && (data.len() - TickArrayState::LEN).checked_rem(LimitOrderFill::LEN) == Some(0),
+        anchor_lang::error::ErrorCode::AccountDidNotDeserialize
+    );
+    require!(
+        data[..8] == TickArrayState::discriminator(),
+        anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+    );
+    Ok(())
+}
+
+/// Prepares the tick array of an order tick for the fill of the batch being opened. The tick
+/// array account grows by one fill with the first order of the tick, and the previous fill of
+/// the tick is written to its fill account, which must be in the remaining accounts.
+pub fn prepare_limit_order_fill<'info>(
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    tick_array_info: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    pool_id: Pubkey,
+    tick: i32,
+) -> Result<()> {
+    {
+        let (_, mut fills) = load_limit_order_fills_mut(tick_array_info)?;
+        if let Some(fill) = fills.iter_mut().find(|fill| fill.tick == tick) {
+            if fill.fill_count != 0 {
+                let account_info =
+                    LimitOrderFillState::find(remaining_accounts, &pool_id, tick, fill.fill_count)?;
+                let mut fill_state = LimitOrderFillState::try_deserialize(
+                    &mut &account_info.try_borrow_data()?[..],
+                )?;
+                if !fill_state.filled {
+                    fill_state.record(fill);
+                    fill_state.try_serialize(&mut &mut account_info.try_borrow_mut_data()?[..])?;
+                }
+                *fill = LimitOrderFill {
+                    tick,
+                    ..Default::default()
+                };
+            }
+            return Ok(());
+        }
+    }
+
+    let space = tick_array_info.data_len() + LimitOrderFill::LEN;
+    let required_lamports = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(tick_array_info.lamports());
+    if required_lamports > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program,
+                system_program::Transfer {
+                    from: payer,
+                    to: tick_array_info.clone(),
+                },
+            ),
+            required_lamports,
+        )?;
+    }
+    tick_array_info.realloc(space, true)?;
+    let (_, mut fills) = load_limit_order_fills_mut(tick_array_info)?;
+    fills.last_mut().unwrap().tick = tick;
+    Ok(())
+}
+
+/// Keeps the growth snapshots of the limit orders filled by a swap in their tick arrays, which
+/// must be among `tick_array_infos`. The fill accounts are not needed, a fill is written to its
+/// account when the next batch of the tick is opened or an order of the batch is settled.
+pub fn record_limit_order_fills<'a, 'info: 'a>(
+    pool_id: Pubkey,
+    fills: &[LimitOrderFill],
+    tick_array_infos: impl IntoIterator<Item = &'a AccountInfo<'info>>,
+) -> Result<()> {
+    if fills.is_empty() {
+        return Ok(());
+    }
+    let mut recorded = vec![false; fills.len()];
+    for account_info in tick_array_infos {
+        if check_tick_array_account(account_info).is_err() {
+            continue;
+        }
+        let (tick_array, mut tick_array_fills) = load_limit_order_fills_mut(account_info)?;
+        if tick_array.pool_id != pool_id {
+            continue;
+        }
+        // the tick array holds the fills of its order ticks only
+        for tick_array_fill in tick_array_fills.iter_mut() {
+            if let Some(index) = fills
+                .iter()
+                .position(|fill| fill.tick == tick_array_fill.tick)
+            {
+                *tick_array_fill = fills[index];
+                recorded[index] = true;
+            }
+        }
+    }
+    require!(
+        recorded.iter().all(|recorded| *recorded),
+        ErrorCode::InvalidLimitOrder
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod limit_order_fill_test {
+    use super::*;
+
+    #[test]
+    fn limit_order_fill_len_test() {
+        let mut data = Vec::new();
+        LimitOrderFillState::default()
+            .try_serialize(&mut data)
+            .unwrap();
+        assert_eq!(data.len(), LimitOrderFillState::LEN);
+    }
+
+    fn build_fill_account_info(
+        pool_id: Pubkey,
+        tick: i32,
+        fill_count: u64,
+    ) -> &'static AccountInfo<'static> {
+        let (key, bump) = LimitOrderFillState::key(&pool_id, tick, fill_count);
+        let mut data = Vec::new();
+        LimitOrderFillState {
+            bump: [bump],
+            pool_id,
+            tick,
+            fill_count,
+            ..Default::default()
+        }
+        .try_serialize(&mut data)
+        .unwrap();
+        crate::util::build_account_info(key, data)
+    }
+
+    #[test]
+    fn record_limit_order_fills_test() {
+        let pool_id = Pubkey::new_unique();
+        let tick_array = TickArrayState {
+            pool_id,
+            ..Default::default()
+        };
+        let mut data = TickArrayState::discriminator().to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&tick_array));
+        data.extend_from_slice(bytemuck::bytes_of(&LimitOrderFill {
+            tick: 120,
+            ..Default::default()
+        }));
+        let tick_array_info = crate::util::build_account_info(Pubkey::new_unique(), data);
+        let fill_account_info = build_fill_account_info(pool_id, 120, 1);
+        assert!(LimitOrderFillState::is_limit_order_fill_account(
+            fill_account_info
+        ));
+
+        // the tick array has no room for the fill of another tick
+        let fill = LimitOrderFill {
+            tick: 180,
+            fill_count: 1,
+            ..Default::default()
+        };
+        assert!(record_limit_order_fills(pool_id, &[fill], [tick_array_info]).is_err());
+
+        // a swap keeps the fill in the tick array, its fill account is not needed
+        let fill = LimitOrderFill {
+            tick: 120,
+            fill_count: 1,
+            fee_growth_inside_0_x64: 10,
+            fee_growth_inside_1_x64: 20,
+            reward_growths_inside_x64: [1, 2, 3],
+        };
+        record_limit_order_fills(pool_id, &[fill], [fill_account_info, tick_array_info]).unwrap();
+        assert_eq!(load_limit_order_fills(tick_array_info).unwrap().1[0], fill);
+        assert!(
+            !LimitOrderFillState::try_deserialize(&mut &fill_account_info.data.borrow()[..])
+                .unwrap()
+                .filled
+        );
+
+        // the first settle writes the fill to the fill account
+        let accounts = [fill_account_info.clone()];
+        let fill_state =
+            LimitOrderFillState::load(&accounts, tick_array_info, pool_id, 120, 1).unwrap();
+        assert!(fill_state.filled);
+        assert_eq!(fill_state.fee_growth_inside_0_x64, 10);
+        assert_eq!(fill_state.fee_growth_inside_1_x64, 20);
+        assert_eq!(fill_state.reward_growths_inside_x64, [1, 2, 3]);
+        let fill_state =
+            LimitOrderFillState::try_deserialize(&mut &fill_account_info.data.borrow()[..])
+                .unwrap();
+        assert!(fill_state.filled);
+        assert_eq!(fill_state.reward_growths_inside_x64, [1, 2, 3]);
+
+        // the next batch of the tick frees the fill in the tick array
+        let payer = crate::util::build_account_info(Pubkey::new_unique(), Vec::new());
+        let system_program = crate::util::build_account_info(system_program::ID, Vec::new());
+        prepare_limit_order_fill(
+            payer.clone(),
+            system_program.clone(),
+            tick_array_info,
+            &accounts,
+            pool_id,
+            120,
+        )
+        .unwrap();
+        assert_eq!(
+            load_limit_order_fills(tick_array_info).unwrap().1[0],
+            LimitOrderFill {
+                tick: 120,
+                ..Default::default()
+            }
+        );
+        // the batch is settled from its fill account only
+        assert!(LimitOrderFillState::load(&accounts, tick_array_info, pool_id, 120, 1).is_ok());
+        // the fill of the next batch is not known yet
+        let accounts = [build_fill_account_info(pool_id, 120, 2).clone()];
+        assert!(LimitOrderFillState::load(&accounts, tick_array_info, pool_id, 120, 2).is_err());
+    }
+
+    #[test]
+    fn prepare_limit_order_fill_test() {
+        let pool_id = Pubkey::new_unique();
+        let tick_array = TickArrayState {
+            pool_id,
+            ..Default::default()
+        };
+        let mut data = TickArrayState::discriminator().to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&tick_array));
+        data.extend_from_slice(bytemuck::bytes_of(&LimitOrderFill {
+            tick: -60,
+            fill_count: 3,
+            fee_growth_inside_0_x64: 10,
+            ..Default::default()
+        }));
+        let tick_array_info = crate::util::build_account_info(Pubkey::new_unique(), data);
+        let payer = crate::util::build_account_info(Pubkey::new_unique(), Vec::new());
+        let system_program = crate::util::build_account_info(system_program::ID, Vec::new());
+
+        // the unsettled fill of the previous batch needs its fill account
+        assert!(prepare_limit_order_fill(
+            payer.clone(),
+            system_program.clone(),
+            tick_array_info,
+            &[],
+            pool_id,
+            -60,
+        )
+        .is_err());
+        let fill_account_info = build_fill_account_info(pool_id, -60, 3);
+        prepare_limit_order_fill(
+            payer.clone(),
+            system_program.clone(),
+            tick_array_info,
+            std::slice::from_ref(fill_account_info),
+            pool_id,
+            -60,
+        )
+        .unwrap();
+        let fill_state =
+            LimitOrderFillState::try_deserialize(&mut &fill_account_info.data.borrow()[..])
+                .unwrap();
+        assert!(fill_state.filled);
+        assert_eq!(fill_state.fee_growth_inside_0_x64, 10);
+        assert_eq!(
+            { load_limit_order_fills(tick_array_info).unwrap().1[0].fill_count },
+            0
+        );
+        // nothing is left to write for the following orders of the batch
+        prepare_limit_order_fill(
+            payer.clone(),
+            system_program.clone(),
+            tick_array_info,
+            &[],
+            pool_id,
+            -60,
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+pub const LOCKED_POSITION_SEED: &str = "locked_position";
+
+/// Holds a position NFT locked forever, the holder of the receipt NFT can collect its fees and rewards
+///
+/// PDA of `[LOCKED_POSITION_SEED, receipt_nft_mint]`
+///
+#[account]
+#[derive(Default, Debug)]
+pub struct LockedPositionState {
+    /// Bump to identify PDA
+    pub bump: [u8; 1],
+    /// Mint address of the fee-claim receipt NFT
+    pub receipt_nft_mint: Pubkey,
+    /// Mint address of the locked position NFT, held by this account
+    pub position_nft_mint: Pubkey,
+    /// The personal position state of the locked position
+    pub personal_position: Pubkey,
+    /// The pool of the locked position
+    pub pool_id: Pubkey,
+    pub padding: [u64; 8],
+}
+
+impl LockedPositionState {
+    pub const LEN: usize = 8 + 1 + 32 + 32 + 32 + 32 + 64;
+
+    pub fn seeds(&self) -> [&[u8]; 3] {
+        [
+            LOCKED_POSITION_SEED.as_bytes(),
+            self.receipt_nft_mint.as_ref(),
+            self.bump.as_ref(),
+        ]
+    }
+}
+
+/// Emitted when a position is locked forever and its fee-claim receipt NFT is minted
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct LockPositionForeverEvent {
+    /// The pool of the position
+    #[index]
+    pub pool_state: Pubkey,
+    /// The ID of the token for which liquidity was locked forever
+    pub position_nft_mint: Pubkey,
+    /// The fee-claim receipt NFT minted to the owner of the position
+    pub receipt_nft_mint: Pubkey,
+    /// The owner of the receipt NFT
+    pub receipt_nft_owner: Pubkey,
+    /// The liquidity of the position when locked
+    pub liquidity: u128,
+}
+
+#[cfg(test)]
+mod locked_position_test {
+    use super::*;
+
+    #[test]
+    fn locked_position_len_test() {
+        let mut data = Vec::new();
+        LockedPositionState::default()
+            .try_serialize(&mut data)
+            .unwrap();
+        assert_eq!(data.len(), LockedPositionState::LEN);
+    }
+}
@@ -1,19 +1,27 @@
 pub mod config;
+pub mod limit_order_fill;
+pub mod locked_position;
 pub mod operation_account;
 pub mod oracle;
 pub mod personal_position;
 pub mod pool;
+pub mod pool_allow_list;
 pub mod protocol_position;
+pub mod share_vault;
 pub mod support_mint_associated;
 pub mod tick_array;
 pub mod tickarray_bitmap_extension;
 
 pub use config::*;
+pub use limit_order_fill::*;
+pub use locked_position::*;
 pub use operation_account::*;
 pub use oracle::*;
 pub use personal_position::*;
 pub use pool::*;
+pub use pool_allow_list::*;
 pub use protocol_position::*;
+pub use share_vault::*;
 pub use support_mint_associated::*;
 pub use tick_array::*;
 pub use tickarray_bitmap_extension::*;
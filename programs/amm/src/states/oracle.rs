@@ -1,7 +1,9 @@
 /// Oracle provides price data useful for a wide variety of system designs
 ///
 use anchor_lang::prelude::*;
+use std::cell::{Ref, RefMut};
 
+use crate::error::ErrorCode;
 use crate::util::get_recent_epoch;
 
 /// Seed to derive account address and signature
@@ -9,6 +11,8 @@ pub const OBSERVATION_SEED: &str = "observation";
 // Number of ObservationState element
 pub const OBSERVATION_NUM: usize = 100;
 pub const OBSERVATION_UPDATE_DURATION_DEFAULT: u32 = 15;
+// Max observation cardinality if the amm config doesn't set one, 24 hours of observations
+pub const OBSERVATION_CARDINALITY_MAX_DEFAULT: u16 = 5760;
 
 /// The element of observations in ObservationState
 #[zero_copy(unsafe)]
@@ -19,14 +23,21 @@ pub struct Observation {
     pub block_timestamp: u32,
     /// the cumulative of tick during the duration time
     pub tick_cumulative: i64,
+    /// the seconds per in range liquidity as of the block timestamp, as a Q64.64
+    pub seconds_per_liquidity_cumulative_x64: u128,
     /// padding for feature update
-    pub padding: [u64; 4],
+    pub padding: [u64; 2],
 }
 
 impl Observation {
-    pub const LEN: usize = 4 + 8 + 8 * 4;
+    pub const LEN: usize = 4 + 8 + 16 + 8 * 2;
 }
 
+// Observations past the fixed part of a grown ObservationState are cast straight from the account data.
+// Packed with plain integer fields only, so every bit pattern is valid and there is no padding.
+unsafe impl bytemuck::Zeroable for Observation {}
+unsafe impl bytemuck::Pod for Observation {}
+
 #[account(zero_copy(unsafe))]
 #[repr(C, packed)]
 #[cfg_attr(feature = "client", derive(Debug))]
@@ -39,10 +50,15 @@ pub struct ObservationState {
     pub observation_index: u16,
     /// belongs to which pool
     pub pool_id: Pubkey,
-    /// observation array
+    /// observation array, the observations beyond OBSERVATION_NUM are stored after this struct
     pub observations: [Observation; OBSERVATION_NUM],
+    /// the number of observations in the ring, zero means OBSERVATION_NUM
+    pub observation_cardinality: u16,
+    /// the number of observations the account has room for, the ring grows to it when it wraps
+    pub observation_cardinality_next: u16,
     /// padding for feature update
-    pub padding: [u64; 4],
+    pub padding_u32: u32,
+    pub padding: [u64; 3],
 }
 
 impl Default for ObservationState {
@@ -54,7 +70,10 @@ impl Default for ObservationState {
             observation_index: 0,
             pool_id: Pubkey::default(),
             observations: [Observation::default(); OBSERVATION_NUM],
-            padding: [0u64; 4],
+            observation_cardinality: 0,
+            observation_cardinality_next: 0,
+            padding_u32: 0,
+            padding: [0u64; 3],
         }
     }
 }
@@ -62,49 +81,339 @@ impl Default for ObservationState {
 impl ObservationState {
     pub const LEN: usize = 8 + 1 + 8 + 2 + 32 + (Observation::LEN * OBSERVATION_NUM) + 8 * 4;
 
+    /// The size of an ObservationState account with room for `observation_cardinality` observations
+    pub fn account_len(observation_cardinality: u16) -> usize {
+        ObservationState::LEN
+            + Observation::LEN * (observation_cardinality as usize).saturating_sub(OBSERVATION_NUM)
+    }
+
     pub fn initialize(&mut self, pool_id: Pubkey) -> Result<()> {
         self.initialized = false;
         self.recent_epoch = get_recent_epoch()?;
         self.observation_index = 0;
         self.pool_id = pool_id;
         self.observations = [Observation::default(); OBSERVATION_NUM];
-        self.padding = [0u64; 4];
+        self.observation_cardinality = OBSERVATION_NUM as u16;
+        self.observation_cardinality_next = OBSERVATION_NUM as u16;
+        self.padding_u32 = 0;
+        self.padding = [0u64; 3];
         Ok(())
     }
 
+    /// The number of observations in the ring
+    pub fn cardinality(&self) -> u16 {
+        if self.observation_cardinality == 0 {
+            OBSERVATION_NUM as u16
+        } else {
+            self.observation_cardinality
+        }
+    }
+
+    /// The number of observations the account has room for
+    pub fn cardinality_next(&self) -> u16 {
+        self.cardinality().max(self.observation_cardinality_next)
+    }
+
+    fn observation(&self, extension: &[Observation], index: usize) -> Observation {
+        if index < OBSERVATION_NUM {
+            self.observations[index]
+        } else {
+            extension[index - OBSERVATION_NUM]
+        }
+    }
+
+    fn observation_mut<'a>(
+        &'a mut self,
+        extension: &'a mut [Observation],
+        index: usize,
+    ) -> &'a mut Observation {
+        if index < OBSERVATION_NUM {
+            &mut self.observations[index]
+        } else {
+            &mut extension[index - OBSERVATION_NUM]
+        }
+    }
+
     /// Writes an oracle observation to the account
     ///
     /// # Arguments
     ///
     /// * `self` - The ObservationState account to write in
+    /// * `extension` - The observations stored after the ObservationState
     /// * `block_timestamp` - The current timestamp of to update
+    /// * `tick` - The tick of the pool since the last observation
+    /// * `seconds_per_liquidity_cumulative_x64` - The seconds per liquidity global of the pool as of block_timestamp
     ///
-    pub fn update(&mut self, block_timestamp: u32, tick: i32) {
+    pub fn update(
+        &mut self,
+        extension: &mut [Observation],
+        block_timestamp: u32,
+        tick: i32,
+        seconds_per_liquidity_cumulative_x64: u128,
+    ) {
         let observation_index = self.observation_index;
         if !self.initialized {
             self.initialized = true;
-            self.observations[observation_index as usize].block_timestamp = block_timestamp;
-            self.observations[observation_index as usize].tick_cumulative = 0;
+            let observation = self.observation_mut(extension, observation_index as usize);
+            observation.block_timestamp = block_timestamp;
+            observation.tick_cumulative = 0;
+            observation.seconds_per_liquidity_cumulative_x64 = seconds_per_liquidity_cumulative_x64;
         } else {
-            let last_observation = self.observations[observation_index as usize];
+            let last_observation = self.observation(extension, observation_index as usize);
             let delta_time = block_timestamp.saturating_sub(last_observation.block_timestamp);
             if delta_time < OBSERVATION_UPDATE_DURATION_DEFAULT {
                 return;
             }
 
             let delta_tick_cumulative = i64::from(tick).checked_mul(delta_time.into()).unwrap();
-            let next_observation_index = if observation_index as usize == OBSERVATION_NUM - 1 {
-                0
+            let cardinality = self.cardinality();
+            let next_observation_index = if observation_index == cardinality - 1 {
+                // the ring only grows when it wraps, so that the observations stay in order
+                let cardinality_next = self.cardinality_next();
+                if cardinality_next > cardinality {
+                    self.observation_cardinality = cardinality_next;
+                    observation_index + 1
+                } else {
+                    0
+                }
             } else {
                 observation_index + 1
             };
-            self.observations[next_observation_index as usize].block_timestamp = block_timestamp;
-            self.observations[next_observation_index as usize].tick_cumulative = last_observation
+            let next_observation = self.observation_mut(extension, next_observation_index as usize);
+            next_observation.block_timestamp = block_timestamp;
+            next_observation.tick_cumulative = last_observation
                 .tick_cumulative
                 .wrapping_add(delta_tick_cumulative);
+            next_observation.seconds_per_liquidity_cumulative_x64 =
+                seconds_per_liquidity_cumulative_x64;
             self.observation_index = next_observation_index;
         }
     }
+
+    /// Returns the tick cumulatives and the seconds per liquidity cumulatives as of each `seconds_ago` from `block_timestamp`
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The ObservationState account to read from
+    /// * `extension` - The observations stored after the ObservationState
+    /// * `block_timestamp` - The current timestamp
+    /// * `seconds_agos` - How long ago each cumulative should be returned for
+    /// * `tick_current` - The current pool tick, used to extrapolate beyond the newest observation
+    /// * `seconds_per_liquidity_cumulative_x64` - The seconds per liquidity global of the pool as of block_timestamp
+    ///
+    pub fn observe(
+        &self,
+        extension: &[Observation],
+        block_timestamp: u32,
+        seconds_agos: &[u32],
+        tick_current: i32,
+        seconds_per_liquidity_cumulative_x64: u128,
+    ) -> Result<(Vec<i64>, Vec<u128>)> {
+        require!(self.initialized, ErrorCode::OracleNotInitialized);
+        Ok(seconds_agos
+            .iter()
+            .map(|seconds_ago| {
+                self.observe_single(
+                    extension,
+                    block_timestamp,
+                    *seconds_ago,
+                    tick_current,
+                    seconds_per_liquidity_cumulative_x64,
+                )
+            })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .unzip())
+    }
+
+    fn observe_single(
+        &self,
+        extension: &[Observation],
+        block_timestamp: u32,
+        seconds_ago: u32,
+        tick_current: i32,
+        seconds_per_liquidity_cumulative_x64: u128,
+    ) -> Result<(i64, u128)> {
+        let target = block_timestamp
+            .checked_sub(seconds_ago)
+            .ok_or(ErrorCode::OracleObservationTooOld)?;
+
+        let last_observation = self.observation(extension, self.observation_index as usize);
+        if target >= last_observation.block_timestamp {
+            // the tick has stayed at tick_current since the newest observation
+            let delta_time = target - last_observation.block_timestamp;
+            let tick_cumulative = last_observation
+                .tick_cumulative
+                .wrapping_add(i64::from(tick_current) * i64::from(delta_time));
+            // the liquidity may have changed since the newest observation,
+            // interpolate towards the current value of the pool
+            let seconds_per_liquidity_cumulative_x64 = if target == block_timestamp {
+                seconds_per_liquidity_cumulative_x64
+            } else if delta_time == 0 {
+                last_observation.seconds_per_liquidity_cumulative_x64
+            } else {
+                last_observation
+                    .seconds_per_liquidity_cumulative_x64
+                    .wrapping_add(
+                        seconds_per_liquidity_cumulative_x64
+                            .wrapping_sub(last_observation.seconds_per_liquidity_cumulative_x64)
+                            / u128::from(block_timestamp - last_observation.block_timestamp)
+                            * u128::from(delta_time),
+                    )
+            };
+            return Ok((tick_cumulative, seconds_per_liquidity_cumulative_x64));
+        }
+
+        let (before, after) = self.get_surrounding_observations(extension, target)?;
+        if target == before.block_timestamp {
+            Ok((
+                before.tick_cumulative,
+                before.seconds_per_liquidity_cumulative_x64,
+            ))
+        } else if target == after.block_timestamp {
+            Ok((
+                after.tick_cumulative,
+                after.seconds_per_liquidity_cumulative_x64,
+            ))
+        } else {
+            // interpolate between the two surrounding observations
+            let observation_time_delta = after.block_timestamp - before.block_timestamp;
+            let target_delta = target - before.block_timestamp;
+            Ok((
+                before.tick_cumulative.wrapping_add(
+                    after.tick_cumulative.wrapping_sub(before.tick_cumulative)
+                        / i64::from(observation_time_delta)
+                        * i64::from(target_delta),
+                ),
+                before.seconds_per_liquidity_cumulative_x64.wrapping_add(
+                    after
+                        .seconds_per_liquidity_cumulative_x64
+                        .wrapping_sub(before.seconds_per_liquidity_cumulative_x64)
+                        / u128::from(observation_time_delta)
+                        * u128::from(target_delta),
+                ),
+            ))
+        }
+    }
+
+    /// Binary searches the ring for the observations at or right before and right after `target`.
+    /// `target` must be older than the newest observation.
+    fn get_surrounding_observations(
+        &self,
+        extension: &[Observation],
+        target: u32,
+    ) -> Result<(Observation, Observation)> {
+        let observation_index = self.observation_index as usize;
+        let cardinality = self.cardinality() as usize;
+        // before the ring wraps around, the slots after the newest one have never been written
+        let (oldest_index, observation_count) = if self
+            .observation(extension, (observation_index + 1) % cardinality)
+            .block_timestamp
+            == 0
+        {
+            (0, observation_index + 1)
+        } else {
+            ((observation_index + 1) % cardinality, cardinality)
+        };
+        require_gte!(
+            target,
+            self.observation(extension, oldest_index).block_timestamp,
+            ErrorCode::OracleObservationTooOld
+        );
+
+        let mut left = 0;
+        let mut right = observation_count - 1;
+        loop {
+            let middle = (left + right) / 2;
+            let before = self.observation(extension, (oldest_index + middle) % cardinality);
+            let after = self.observation(extension, (oldest_index + middle + 1) % cardinality);
+            if before.block_timestamp > target {
+                right = middle - 1;
+            } else if after.block_timestamp < target {
+                left = middle + 1;
+            } else {
+                return Ok((before, after));
+            }
+        }
+    }
+}
+
+/// Returns the time-weighted arithmetic mean tick of a window, rounded to negative infinity.
+/// The arithmetic mean tick is the geometric mean price of the window.
+///
+/// # Arguments
+///
+/// * `tick_cumulative_start` - The tick cumulative at the start of the window
+/// * `tick_cumulative_end` - The tick cumulative at the end of the window
+/// * `window_seconds` - The length of the window
+///
+pub fn get_arithmetic_mean_tick(
+    tick_cumulative_start: i64,
+    tick_cumulative_end: i64,
+    window_seconds: u32,
+) -> i32 {
+    let tick_cumulative_delta = tick_cumulative_end.wrapping_sub(tick_cumulative_start);
+    let window_seconds = i64::from(window_seconds);
+    let mut arithmetic_mean_tick = tick_cumulative_delta / window_seconds;
+    if tick_cumulative_delta < 0 && tick_cumulative_delta % window_seconds != 0 {
+        arithmetic_mean_tick -= 1;
+    }
+    arithmetic_mean_tick as i32
+}
+
+/// Loads an ObservationState account for writing, together with the observations stored
+/// after the ObservationState once the account has been grown by `increase_observation_cardinality`
+pub fn load_observations_mut<'a, 'info>(
+    observation_loader: &'a AccountLoader<'info, ObservationState>,
+) -> Result<(RefMut<'a, ObservationState>, RefMut<'a, [Observation]>)> {
+    let account_info: &AccountInfo<'info> = observation_loader.as_ref();
+    if !account_info.is_writable {
+        return err!(anchor_lang::error::ErrorCode::AccountNotMutable);
+    }
+    let data = account_info.try_borrow_mut_data()?;
+    require!(
+        data.len() >= ObservationState::LEN
+            && (data.len() - ObservationState::LEN) % Observation::LEN == 0,
+        anchor_lang::error::ErrorCode::AccountDidNotDeserialize
+    );
+    Ok(RefMut::map_split(data, |data| {
+        let (state_data, extension_data) = data.split_at_mut(ObservationState::LEN);
+        (
+            bytemuck::from_bytes_mut(&mut state_data[8..]),
+            bytemuck::cast_slice_mut(extension_data),
+        )
+    }))
+}
+
+/// Loads an ObservationState account for reading, together with the observations stored
+/// after the ObservationState once the account has been grown by `increase_observation_cardinality`
+pub fn load_observations<'a, 'info>(
+    observation_loader: &'a AccountLoader<'info, ObservationState>,
+) -> Result<(Ref<'a, ObservationState>, Ref<'a, [Observation]>)> {
+    let account_info: &AccountInfo<'info> = observation_loader.as_ref();
+    let data = account_info.try_borrow_data()?;
+    require!(
+        data.len() >= ObservationState::LEN
+            && (data.len() - ObservationState::LEN) % Observation::LEN == 0,
+        anchor_lang::error::ErrorCode::AccountDidNotDeserialize
+    );
+    Ok(Ref::map_split(data, |data| {
+        let (state_data, extension_data) = data.split_at(ObservationState::LEN);
+        (
+            bytemuck::from_bytes(&state_data[8..]),
+            bytemuck::cast_slice(extension_data),
+        )
+    }))
+}
+
+/// Emitted when the observation account of a pool is grown
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct IncreaseObservationCardinalityEvent {
+    #[index]
+    pub pool_id: Pubkey,
+    pub observation_cardinality_next_old: u16,
+    pub observation_cardinality_next_new: u16,
 }
 
 /// Returns the block timestamp truncated to 32 bits, i.e. mod 2**32
@@ -122,6 +431,136 @@ pub fn block_timestamp_mock() -> u64 {
         .as_secs()
 }
 
+#[cfg(test)]
+mod observe_test {
+    use super::*;
+
+    fn build_observation_state(updates: &[(u32, i32)]) -> ObservationState {
+        let mut observation_state = ObservationState::default();
+        for (block_timestamp, tick) in updates {
+            observation_state.update(&mut [], *block_timestamp, *tick, 0);
+        }
+        observation_state
+    }
+
+    #[test]
+    fn observe_interpolate_and_extrapolate_test() {
+        let observation_state = build_observation_state(&[(1000, 0), (1100, 10), (1200, 20)]);
+        let tick_cumulatives = observation_state
+            .observe(&[], 1300, &[300, 250, 150, 100, 0], 30, 0)
+            .unwrap()
+            .0;
+        assert_eq!(tick_cumulatives, vec![0, 500, 2000, 3000, 6000]);
+        assert_eq!(
+            get_arithmetic_mean_tick(tick_cumulatives[0], tick_cumulatives[4], 300),
+            20
+        );
+    }
+
+    #[test]
+    fn observe_too_old_test() {
+        let observation_state = build_observation_state(&[(1000, 0), (1100, 10)]);
+        assert!(observation_state.observe(&[], 1300, &[301], 10, 0).is_err());
+        assert!(ObservationState::default()
+            .observe(&[], 1300, &[0], 10, 0)
+            .is_err());
+    }
+
+    #[test]
+    fn observe_after_ring_wrap_test() {
+        let updates = (0..OBSERVATION_NUM as u32 + 10)
+            .map(|i| (1000 + i * OBSERVATION_UPDATE_DURATION_DEFAULT, -1))
+            .collect::<Vec<_>>();
+        let observation_state = build_observation_state(&updates);
+        let newest = updates.last().unwrap().0;
+        // the oldest kept observation is 99 updates before the newest one
+        let oldest_seconds_ago = 99 * OBSERVATION_UPDATE_DURATION_DEFAULT;
+        let tick_cumulatives = observation_state
+            .observe(
+                &[],
+                newest,
+                &[oldest_seconds_ago, oldest_seconds_ago / 3, 0],
+                -1,
+                0,
+            )
+            .unwrap()
+            .0;
+        assert_eq!(
+            get_arithmetic_mean_tick(tick_cumulatives[0], tick_cumulatives[2], oldest_seconds_ago),
+            -1
+        );
+        assert_eq!(
+            tick_cumulatives[2] - tick_cumulatives[1],
+            -i64::from(oldest_seconds_ago / 3)
+        );
+        assert!(observation_state
+            .observe(&[], newest, &[oldest_seconds_ago + 1], -1, 0)
+            .is_err());
+    }
+
+    #[test]
+    fn observe_after_ring_grow_test() {
+        let mut observation_state = ObservationState::default();
+        let mut extension = vec![Observation::default(); 50];
+        let mut block_timestamp = 1000;
+        for _ in 0..OBSERVATION_NUM + 20 {
+            observation_state.update(&mut extension, block_timestamp, 1, 0);
+            block_timestamp += OBSERVATION_UPDATE_DURATION_DEFAULT;
+        }
+        // grow in the middle of the ring, the ring keeps its size until it wraps
+        observation_state.observation_cardinality_next = (OBSERVATION_NUM + 50) as u16;
+        for _ in 0..OBSERVATION_NUM {
+            observation_state.update(&mut extension, block_timestamp, 1, 0);
+            block_timestamp += OBSERVATION_UPDATE_DURATION_DEFAULT;
+        }
+        assert_eq!(
+            observation_state.cardinality(),
+            (OBSERVATION_NUM + 50) as u16
+        );
+        let observation_index = observation_state.observation_index;
+        assert_eq!(observation_index as usize, OBSERVATION_NUM + 19);
+
+        let newest = block_timestamp - OBSERVATION_UPDATE_DURATION_DEFAULT;
+        // 20 observations written in the extension plus the whole fixed part
+        let oldest_seconds_ago =
+            (OBSERVATION_NUM as u32 + 19) * OBSERVATION_UPDATE_DURATION_DEFAULT;
+        let tick_cumulatives = observation_state
+            .observe(&extension, newest, &[oldest_seconds_ago, 0], 1, 0)
+            .unwrap()
+            .0;
+        assert_eq!(
+            tick_cumulatives[1] - tick_cumulatives[0],
+            i64::from(oldest_seconds_ago)
+        );
+        assert!(observation_state
+            .observe(&extension, newest, &[oldest_seconds_ago + 1], 1, 0)
+            .is_err());
+    }
+
+    #[test]
+    fn observe_seconds_per_liquidity_test() {
+        let mut observation_state = ObservationState::default();
+        observation_state.update(&mut [], 1000, 0, 100 << 64);
+        observation_state.update(&mut [], 1100, 0, 300 << 64);
+        // the liquidity changed after the newest observation, the pool is at 700 now
+        let seconds_per_liquidity_cumulatives = observation_state
+            .observe(&[], 1300, &[300, 250, 200, 100, 0], 0, 700 << 64)
+            .unwrap()
+            .1;
+        assert_eq!(
+            seconds_per_liquidity_cumulatives,
+            vec![100 << 64, 200 << 64, 300 << 64, 500 << 64, 700 << 64]
+        );
+    }
+
+    #[test]
+    fn arithmetic_mean_tick_round_to_negative_infinity_test() {
+        assert_eq!(get_arithmetic_mean_tick(0, -10, 3), -4);
+        assert_eq!(get_arithmetic_mean_tick(0, 10, 3), 3);
+        assert_eq!(get_arithmetic_mean_tick(0, -9, 3), -3);
+    }
+}
+
 #[cfg(test)]
 pub mod oracle_layout_test {
     use super::*;
@@ -132,12 +571,10 @@ pub mod oracle_layout_test {
         let recent_epoch: u64 = 0x123456789abcdef0;
         let observation_index: u16 = 0x1122;
         let pool_id: Pubkey = Pubkey::new_unique();
-        let padding: [u64; 4] = [
-            0x123456789abcde0f,
-            0x123456789abcd0ef,
-            0x123456789abc0def,
-            0x123456789ab0cdef,
-        ];
+        let observation_cardinality: u16 = 0x3344;
+        let observation_cardinality_next: u16 = 0x5566;
+        let padding_u32: u32 = 0x778899aa;
+        let padding: [u64; 3] = [0x123456789abcde0f, 0x123456789abcd0ef, 0x123456789abc0def];
 
         let mut observation_datas = [0u8; Observation::LEN * OBSERVATION_NUM];
         let mut observations = [Observation::default(); OBSERVATION_NUM];
@@ -146,27 +583,24 @@ pub mod oracle_layout_test {
             let index = i + 1;
             let block_timestamp: u32 = u32::MAX - 3 * index as u32;
             let tick_cumulative: i64 = i64::MAX - 3 * index as i64;
-            let padding: [u64; 4] = [
-                u64::MAX - index as u64,
-                u64::MAX - 2 * index as u64,
-                u64::MAX - 3 * index as u64,
-                u64::MAX - 4 * index as u64,
-            ];
+            let seconds_per_liquidity_cumulative_x64: u128 = u128::MAX - 5 * index as u128;
+            let padding: [u64; 2] = [u64::MAX - index as u64, u64::MAX - 2 * index as u64];
             observations[i].block_timestamp = block_timestamp;
             observations[i].tick_cumulative = tick_cumulative;
+            observations[i].seconds_per_liquidity_cumulative_x64 =
+                seconds_per_liquidity_cumulative_x64;
             observations[i].padding = padding;
             observation_datas[offset..offset + 4].copy_from_slice(&block_timestamp.to_le_bytes());
             offset += 4;
             observation_datas[offset..offset + 8].copy_from_slice(&tick_cumulative.to_le_bytes());
             offset += 8;
+            observation_datas[offset..offset + 16]
+                .copy_from_slice(&seconds_per_liquidity_cumulative_x64.to_le_bytes());
+            offset += 16;
             observation_datas[offset..offset + 8].copy_from_slice(&padding[0].to_le_bytes());
             offset += 8;
             observation_datas[offset..offset + 8].copy_from_slice(&padding[1].to_le_bytes());
             offset += 8;
-            observation_datas[offset..offset + 8].copy_from_slice(&padding[2].to_le_bytes());
-            offset += 8;
-            observation_datas[offset..offset + 8].copy_from_slice(&padding[3].to_le_bytes());
-            offset += 8;
         }
 
         // serialize original data
@@ -188,14 +622,20 @@ pub mod oracle_layout_test {
         observation_state_data[offset..offset + Observation::LEN * OBSERVATION_NUM]
             .copy_from_slice(&observation_datas);
         offset += Observation::LEN * OBSERVATION_NUM;
+        observation_state_data[offset..offset + 2]
+            .copy_from_slice(&observation_cardinality.to_le_bytes());
+        offset += 2;
+        observation_state_data[offset..offset + 2]
+            .copy_from_slice(&observation_cardinality_next.to_le_bytes());
+        offset += 2;
+        observation_state_data[offset..offset + 4].copy_from_slice(&padding_u32.to_le_bytes());
+        offset += 4;
         observation_state_data[offset..offset + 8].copy_from_slice(&padding[0].to_le_bytes());
         offset += 8;
         observation_state_data[offset..offset + 8].copy_from_slice(&padding[1].to_le_bytes());
         offset += 8;
         observation_state_data[offset..offset + 8].copy_from_slice(&padding[2].to_le_bytes());
         offset += 8;
-        observation_state_data[offset..offset + 8].copy_from_slice(&padding[3].to_le_bytes());
-        offset += 8;
         // len check
         assert_eq!(offset, observation_state_data.len());
         assert_eq!(
@@ -217,6 +657,15 @@ pub mod oracle_layout_test {
         assert_eq!(unpack_observation_index, observation_index);
         let unpack_pool_id = unpack_data.pool_id;
         assert_eq!(unpack_pool_id, pool_id);
+        let unpack_observation_cardinality = unpack_data.observation_cardinality;
+        assert_eq!(unpack_observation_cardinality, observation_cardinality);
+        let unpack_observation_cardinality_next = unpack_data.observation_cardinality_next;
+        assert_eq!(
+            unpack_observation_cardinality_next,
+            observation_cardinality_next
+        );
+        let unpack_padding_u32 = unpack_data.padding_u32;
+        assert_eq!(unpack_padding_u32, padding_u32);
         let unpack_padding = unpack_data.padding;
         assert_eq!(unpack_padding, padding);
         for (observation, unpack_observation) in
@@ -224,13 +673,21 @@ pub mod oracle_layout_test {
         {
             let block_timestamp = observation.block_timestamp;
             let tick_cumulative = observation.tick_cumulative;
+            let seconds_per_liquidity_cumulative_x64 =
+                observation.seconds_per_liquidity_cumulative_x64;
             let padding = observation.padding;
 
             let unpack_block_timestamp = unpack_observation.block_timestamp;
             let unpack_tick_cumulative = unpack_observation.tick_cumulative;
+            let unpack_seconds_per_liquidity_cumulative_x64 =
+                unpack_observation.seconds_per_liquidity_cumulative_x64;
             let unpack_padding = unpack_observation.padding;
             assert_eq!(block_timestamp, unpack_block_timestamp);
             assert_eq!(tick_cumulative, unpack_tick_cumulative);
+            assert_eq!(
+                seconds_per_liquidity_cumulative_x64,
+                unpack_seconds_per_liquidity_cumulative_x64
+            );
             assert_eq!(padding, unpack_padding);
         }
     }
@@ -1,9 +1,11 @@
+use crate::error::ErrorCode;
 use crate::libraries::{big_num::U256, fixed_point_64, full_math::MulDiv};
 use crate::pool::REWARD_NUM;
 use crate::util::get_recent_epoch;
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
 
-use super::POSITION_SEED;
+use super::{TickState, POSITION_SEED};
 
 #[account]
 #[derive(Default, Debug)]
@@ -42,8 +44,62 @@ pub struct PersonalPositionState {
     pub reward_infos: [PositionRewardInfo; REWARD_NUM],
     // account update recent epoch
     pub recent_epoch: u64,
+
+    /// The order fill count of the fill tick at which this limit order is filled, zero if the position is not a limit order
+    pub limit_order_fill_count: u64,
+
+    /// The tick at which this limit order is filled, the upper tick if it sells token_0, otherwise the lower tick
+    pub limit_order_fill_tick: i32,
+
+    /// Hash of the delegate allowed to manage the position and the NFT holder who approved it,
+    /// so the delegation lapses when the NFT changes hands, zero if none
+    pub delegation_hash: [u8; 32],
+
+    /// Bitmask of `PositionPermissionBitIndex` granted to the delegate
+    pub delegate_permissions: u8,
+
+    /// The timestamp until which the liquidity of the position can not be removed, zero if not locked
+    pub locked_until: u64,
+
     // Unused bytes for future upgrades.
-    pub padding: [u64; 7],
+    pub padding: [u8; 3],
+}
+
+/// The actions a position delegate can be approved for
+#[derive(Clone, Copy)]
+pub enum PositionPermissionBitIndex {
+    /// Collect fees and rewards to the NFT holder
+    Collect,
+    /// Reinvest fees owed into the position
+    Compound,
+    /// Move the liquidity of the position to a new range
+    Rebalance,
+    /// Decrease liquidity to the NFT holder
+    DecreaseLiquidity,
+}
+
+/// All bits of `PositionPermissionBitIndex`
+pub const POSITION_PERMISSION_MASK: u8 = 0b1111;
+
+/// The hash binding a position delegate to the NFT holder who approved it
+pub fn get_delegation_hash(delegate: Pubkey, nft_holder: Pubkey) -> [u8; 32] {
+    hashv(&[delegate.as_ref(), nft_holder.as_ref()]).to_bytes()
+}
+
+/// Checks that `recipient_token_account_owner` is `recipient_owner` when a delegate acts on a position,
+/// see `PersonalPositionState::check_authority`
+pub fn check_recipient_owner(
+    recipient_owner: Option<Pubkey>,
+    recipient_token_account_owner: Pubkey,
+) -> Result<()> {
+    if let Some(recipient_owner) = recipient_owner {
+        require_keys_eq!(
+            recipient_token_account_owner,
+            recipient_owner,
+            ErrorCode::RecipientNotPositionOwner
+        );
+    }
+    Ok(())
 }
 
 impl PersonalPositionState {
@@ -58,6 +114,89 @@ impl PersonalPositionState {
         ]
     }
 
+    pub fn is_limit_order(&self) -> bool {
+        self.limit_order_fill_count != 0
+    }
+
+    /// Whether the limit order has been filled, `fill_tick_state` must be the state of `limit_order_fill_tick`
+    pub fn is_limit_order_filled(&self, fill_tick_state: &TickState) -> bool {
+        self.is_limit_order() && fill_tick_state.order_fill_count >= self.limit_order_fill_count
+    }
+
+    /// Whether the liquidity of the position is locked at `block_timestamp`
+    pub fn is_locked(&self, block_timestamp: u64) -> bool {
+        self.locked_until > block_timestamp
+    }
+
+    /// Locks the liquidity of the position until `locked_until`, a lock can only be extended
+    pub fn lock(&mut self, locked_until: u64, block_timestamp: u64) -> Result<()> {
+        require!(
+            locked_until > block_timestamp && locked_until > self.locked_until,
+            ErrorCode::InvalidLockTime
+        );
+        self.locked_until = locked_until;
+        Ok(())
+    }
+
+    /// Locks the liquidity of the position forever and drops the delegate of the current holder,
+    /// limit orders can not be locked as they must be settled
+    pub fn lock_forever(&mut self) -> Result<()> {
+        require!(!self.is_limit_order(), ErrorCode::LimitOrderNotLockable);
+        self.locked_until = u64::MAX;
+        self.delegation_hash = [0; 32];
+        self.delegate_permissions = 0;
+        Ok(())
+    }
+
+    /// Releases the lock of the position once it has expired
+    pub fn unlock(&mut self, block_timestamp: u64) -> Result<()> {
+        require!(!self.is_locked(block_timestamp), ErrorCode::PositionLocked);
+        self.locked_until = 0;
+        Ok(())
+    }
+
+    /// Approves `delegate` for the `permissions` on behalf of `nft_holder`, a default delegate or
+    /// empty permissions revoke the delegation
+    pub fn set_delegate(&mut self, delegate: Pubkey, nft_holder: Pubkey, permissions: u8) {
+        if delegate == Pubkey::default() || permissions == 0 {
+            self.delegation_hash = [0; 32];
+            self.delegate_permissions = 0;
+        } else {
+            self.delegation_hash = get_delegation_hash(delegate, nft_holder);
+            self.delegate_permissions = permissions;
+        }
+    }
+
+    /// Whether `delegate` was approved for `permission` by `nft_holder`
+    pub fn is_delegate_approved(
+        &self,
+        delegate: Pubkey,
+        nft_holder: Pubkey,
+        permission: PositionPermissionBitIndex,
+    ) -> bool {
+        self.delegation_hash != [0; 32]
+            && self.delegation_hash == get_delegation_hash(delegate, nft_holder)
+            && self.delegate_permissions & (1 << permission as u8) != 0
+    }
+
+    /// Checks that `signer` is the NFT holder or a delegate the holder approved for `permission`.
+    /// Returns the NFT holder, who must own every recipient account, if the signer is the delegate
+    pub fn check_authority(
+        &self,
+        signer: Pubkey,
+        nft_holder: Pubkey,
+        permission: PositionPermissionBitIndex,
+    ) -> Result<Option<Pubkey>> {
+        if signer == nft_holder {
+            return Ok(None);
+        }
+        require!(
+            self.is_delegate_approved(signer, nft_holder, permission),
+            ErrorCode::NotApproved
+        );
+        Ok(Some(nft_holder))
+    }
+
     pub fn update_rewards(
         &mut self,
         reward_growths_inside: [u128; REWARD_NUM],
@@ -192,6 +331,91 @@ pub struct DecreaseLiquidityEvent {
     pub transfer_fee_1: u64,
 }
 
+/// Emitted when a position is withdrawn from a pool in emergency exit
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct EmergencyWithdrawEvent {
+    /// The pool the position is withdrawn from
+    #[index]
+    pub pool_state: Pubkey,
+    /// The ID of the token for which liquidity was withdrawn
+    pub position_nft_mint: Pubkey,
+    /// The liquidity of the position withdrawn
+    pub liquidity: u128,
+    /// The amount of token_0 paid for the liquidity and the fees owed
+    pub amount_0: u64,
+    /// The amount of token_1 paid for the liquidity and the fees owed
+    pub amount_1: u64,
+}
+
+/// Emitted when the liquidity of a position is locked or the lock is extended
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct LockPositionEvent {
+    /// The pool of the position
+    #[index]
+    pub pool_state: Pubkey,
+    /// The ID of the token for which liquidity was locked
+    pub position_nft_mint: Pubkey,
+    /// The liquidity of the position when locked
+    pub liquidity: u128,
+    /// The timestamp until which the liquidity is locked
+    pub locked_until: u64,
+}
+
+/// Emitted when the expired lock of a position is released
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct UnlockPositionEvent {
+    /// The pool of the position
+    #[index]
+    pub pool_state: Pubkey,
+    /// The ID of the token for which liquidity was unlocked
+    pub position_nft_mint: Pubkey,
+    /// The liquidity of the position when unlocked
+    pub liquidity: u128,
+}
+
+/// Emitted when the delegate of a position is set or revoked
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct SetPositionDelegateEvent {
+    /// The ID of the token for which the delegate was set
+    pub position_nft_mint: Pubkey,
+    /// The new delegate, default if revoked
+    pub delegate: Pubkey,
+    /// The NFT holder who approved the delegate
+    pub nft_owner: Pubkey,
+    /// The permissions granted to the delegate
+    pub permissions: u8,
+}
+
+/// Emitted when liquidity is decreased for a position and paid out in a single token
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct DecreaseLiquidityToSingleEvent {
+    /// The ID of the token for which liquidity was decreased
+    pub position_nft_mint: Pubkey,
+    /// The amount by which liquidity for the position was decreased
+    pub liquidity: u128,
+    /// The amount of token_0 that was paid for the decrease in liquidity
+    pub decrease_amount_0: u64,
+    /// The amount of token_1 that was paid for the decrease in liquidity
+    pub decrease_amount_1: u64,
+    /// The amount of token_0 fee
+    pub fee_amount_0: u64,
+    /// The amount of token_1 fee
+    pub fee_amount_1: u64,
+    /// true: token_0 is swapped for token_1, otherwise token_1 is swapped for token_0
+    pub zero_for_one: bool,
+    /// The amount of the unwanted token swapped through the pool
+    pub swap_amount_in: u64,
+    /// The amount of the requested token paid out
+    pub amount_out: u64,
+    /// The transfer fee of the requested token
+    pub transfer_fee: u64,
+}
+
 /// Emitted when liquidity decreased or increase.
 #[event]
 #[cfg_attr(feature = "client", derive(Debug))]
@@ -237,6 +461,98 @@ pub struct CollectPersonalFeeEvent {
     pub amount_1: u64,
 }
 
+/// Emitted when liquidity of a position is split into a new position
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct SplitPositionEvent {
+    /// The ID of the token for the split position
+    #[index]
+    pub position_nft_mint: Pubkey,
+
+    /// The ID of the token for the new position
+    #[index]
+    pub new_position_nft_mint: Pubkey,
+
+    /// The owner of the new position NFT
+    pub new_position_nft_owner: Pubkey,
+
+    /// The liquidity moved to the new position
+    pub liquidity: u128,
+}
+
+/// Emitted when a position is merged into another one
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct MergePositionsEvent {
+    /// The ID of the token for the position receiving the liquidity
+    #[index]
+    pub position_nft_mint: Pubkey,
+
+    /// The ID of the token for the merged position, which is closed
+    #[index]
+    pub source_position_nft_mint: Pubkey,
+
+    /// The liquidity moved from the merged position
+    pub liquidity: u128,
+}
+
+/// Emitted when a position is moved to a new price range
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct RebalancePositionEvent {
+    /// The ID of the token for the rebalanced position
+    #[index]
+    pub position_nft_mint: Pubkey,
+
+    /// The lower tick of the position before the rebalance
+    pub tick_lower_index_before: i32,
+
+    /// The upper tick of the position before the rebalance
+    pub tick_upper_index_before: i32,
+
+    /// The liquidity withdrawn from the previous range
+    pub liquidity_before: u128,
+
+    /// The lower tick of the new range
+    pub tick_lower_index: i32,
+
+    /// The upper tick of the new range
+    pub tick_upper_index: i32,
+
+    /// The liquidity deposited into the new range
+    pub liquidity: u128,
+
+    /// The amount of token_0 swapped through the pool
+    pub swap_amount_0: u64,
+
+    /// The amount of token_1 swapped through the pool
+    pub swap_amount_1: u64,
+
+    /// The amount of token_0 sent to the recipient, fees included
+    pub refund_amount_0: u64,
+
+    /// The amount of token_1 sent to the recipient, fees included
+    pub refund_amount_1: u64,
+}
+
+/// Emitted when rewards are collected for a position
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct CollectPersonalRewardEvent {
+    /// The ID of the token for which rewards were collected
+    #[index]
+    pub position_nft_mint: Pubkey,
+
+    /// The index of the collected reward
+    pub reward_index: u8,
+
+    /// The token account that received the collected reward tokens
+    pub recipient_token_account: Pubkey,
+
+    /// The amount of reward owed to the position that was collected
+    pub amount: u64,
+}
+
 /// Emitted when Reward are updated for a pool
 #[event]
 #[cfg_attr(feature = "client", derive(Debug))]
@@ -244,3 +560,162 @@ pub struct UpdateRewardInfosEvent {
     /// Reward info
     pub reward_growth_global_x64: [u128; REWARD_NUM],
 }
+
+#[cfg(test)]
+mod personal_position_test {
+    use super::*;
+
+    #[test]
+    fn check_authority_test() {
+        let holder = Pubkey::new_unique();
+        let delegate = Pubkey::new_unique();
+        let mut personal_position = PersonalPositionState::default();
+
+        // the holder is always allowed and keeps its own recipients
+        assert_eq!(
+            personal_position
+                .check_authority(holder, holder, PositionPermissionBitIndex::Collect)
+                .unwrap(),
+            None
+        );
+        // no delegate yet
+        assert!(personal_position
+            .check_authority(delegate, holder, PositionPermissionBitIndex::Collect)
+            .is_err());
+
+        personal_position.set_delegate(
+            delegate,
+            holder,
+            1 << PositionPermissionBitIndex::Collect as u8
+                | 1 << PositionPermissionBitIndex::Rebalance as u8,
+        );
+        assert_eq!(
+            personal_position
+                .check_authority(delegate, holder, PositionPermissionBitIndex::Collect)
+                .unwrap(),
+            Some(holder)
+        );
+        assert_eq!(
+            personal_position
+                .check_authority(delegate, holder, PositionPermissionBitIndex::Rebalance)
+                .unwrap(),
+            Some(holder)
+        );
+        // only the approved permissions are granted
+        for permission in [
+            PositionPermissionBitIndex::Compound,
+            PositionPermissionBitIndex::DecreaseLiquidity,
+        ] {
+            assert!(personal_position
+                .check_authority(delegate, holder, permission)
+                .is_err());
+        }
+        // another signer is not the delegate
+        assert!(personal_position
+            .check_authority(
+                Pubkey::new_unique(),
+                holder,
+                PositionPermissionBitIndex::Collect
+            )
+            .is_err());
+
+        // the delegation lapses once the NFT changes hands
+        let new_holder = Pubkey::new_unique();
+        assert!(personal_position
+            .check_authority(delegate, new_holder, PositionPermissionBitIndex::Collect)
+            .is_err());
+        assert_eq!(
+            personal_position
+                .check_authority(new_holder, new_holder, PositionPermissionBitIndex::Collect)
+                .unwrap(),
+            None
+        );
+
+        // a default delegate or empty permissions revoke the delegation
+        personal_position.set_delegate(Pubkey::default(), holder, POSITION_PERMISSION_MASK);
+        assert_eq!(personal_position.delegation_hash, [0; 32]);
+        assert!(personal_position
+            .check_authority(delegate, holder, PositionPermissionBitIndex::Collect)
+            .is_err());
+        personal_position.set_delegate(delegate, holder, POSITION_PERMISSION_MASK);
+        personal_position.set_delegate(delegate, holder, 0);
+        assert_eq!(personal_position.delegate_permissions, 0);
+        assert!(personal_position
+            .check_authority(delegate, holder, PositionPermissionBitIndex::Collect)
+            .is_err());
+    }
+
+    #[test]
+    fn check_authority_all_permissions_test() {
+        let holder = Pubkey::new_unique();
+        let delegate = Pubkey::new_unique();
+        let permissions = [
+            PositionPermissionBitIndex::Collect,
+            PositionPermissionBitIndex::Compound,
+            PositionPermissionBitIndex::Rebalance,
+            PositionPermissionBitIndex::DecreaseLiquidity,
+        ];
+        let mut personal_position = PersonalPositionState::default();
+        for (i, granted) in permissions.iter().enumerate() {
+            personal_position.set_delegate(delegate, holder, 1 << *granted as u8);
+            for (j, permission) in permissions.iter().enumerate() {
+                assert_eq!(
+                    personal_position
+                        .check_authority(delegate, holder, *permission)
+                        .is_ok(),
+                    i == j
+                );
+            }
+        }
+        personal_position.set_delegate(delegate, holder, POSITION_PERMISSION_MASK);
+        for permission in permissions {
+            assert!(personal_position
+                .check_authority(delegate, holder, permission)
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn lock_position_test() {
+        let mut personal_position = PersonalPositionState::default();
+        assert!(!personal_position.is_locked(1_000));
+        // the lock must end in the future
+        assert!(personal_position.lock(1_000, 1_000).is_err());
+        personal_position.lock(2_000, 1_000).unwrap();
+        assert!(personal_position.is_locked(1_999));
+        assert!(!personal_position.is_locked(2_000));
+        // a lock can be extended but never shortened
+        assert!(personal_position.lock(1_500, 1_000).is_err());
+        assert!(personal_position.lock(2_000, 1_000).is_err());
+        personal_position.lock(3_000, 1_000).unwrap();
+        assert_eq!(personal_position.locked_until, 3_000);
+    }
+
+    #[test]
+    fn unlock_position_test() {
+        let mut personal_position = PersonalPositionState::default();
+        personal_position.lock(2_000, 1_000).unwrap();
+        assert!(personal_position.unlock(1_999).is_err());
+        assert_eq!(personal_position.locked_until, 2_000);
+        personal_position.unlock(2_000).unwrap();
+        assert_eq!(personal_position.locked_until, 0);
+        // an expired lock can be replaced by a new one
+        personal_position.lock(5_000, 3_000).unwrap();
+        assert!(personal_position.is_locked(4_000));
+
+        // a position locked forever is never unlocked
+        personal_position.locked_until = u64::MAX;
+        assert!(personal_position.unlock(u64::MAX - 1).is_err());
+        assert!(personal_position.lock(u64::MAX, 0).is_err());
+    }
+
+    #[test]
+    fn check_recipient_owner_test() {
+        let holder = Pubkey::new_unique();
+        // the holder itself may pay out to any account
+        check_recipient_owner(None, Pubkey::new_unique()).unwrap();
+        // a delegate only pays out to accounts of the holder
+        check_recipient_owner(Some(holder), holder).unwrap();
+        assert!(check_recipient_owner(Some(holder), Pubkey::new_unique()).is_err());
+    }
+}
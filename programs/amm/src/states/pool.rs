@@ -41,6 +41,7 @@ pub enum PoolStatusBitIndex {
     CollectFee,
     CollectReward,
     Swap,
+    CollectProtocolFee,
 }
 
 #[derive(PartialEq, Eq)]
@@ -112,9 +113,22 @@ pub struct PoolState {
     /// bit2, 1: disable collect fee, 0: normal
     /// bit3, 1: disable collect reward, 0: normal
     /// bit4, 1: disable swap, 0: normal
+    /// bit5, 1: disable collect protocol and fund fee, 0: normal
     pub status: u8,
+    /// Bitwise representation of the accesses restricted to the pool allow list
+    /// bit0, 1: only allow-listed traders can swap, 0: open
+    /// bit1, 1: only allow-listed liquidity providers can add liquidity, 0: open
+    pub access_mode: u8,
+    /// The reason code recorded with the last status change, zero means unspecified
+    pub status_reason: u8,
+    /// 1: the pool is in emergency exit, swaps and adding liquidity are disabled and
+    /// positions can only be withdrawn at the current price, 0: normal
+    pub emergency_exit: u8,
+    /// 1: tokens are lent out by a flash, swaps and liquidity changes are disabled until
+    /// the repayment is checked, 0: normal
+    pub flash_in_progress: u8,
     /// Leave blank for future use
-    pub padding: [u8; 7],
+    pub padding: [u8; 3],
 
     pub reward_infos: [RewardInfo; REWARD_NUM],
 
@@ -131,14 +145,54 @@ pub struct PoolState {
     pub fund_fees_token_0: u64,
     pub fund_fees_token_1: u64,
 
-    // The timestamp allowed for swap in the pool.
-    // Note: The open_time is disabled for now.
+    // The timestamp allowed for swap in the pool, liquidity can be added before it.
     pub open_time: u64,
     // account recent update epoch
     pub recent_epoch: u64,
 
+    /// The seconds per unit of in range liquidity as a Q64.64 number, i.e. seconds the pool
+    /// had liquidity in range, divided by that liquidity, for the entire life of the pool.
+    pub seconds_per_liquidity_global_x64: u128,
+    /// The timestamp seconds_per_liquidity_global_x64 was last updated at
+    pub seconds_per_liquidity_update_time: u64,
+
+    /// The volatility of recent swaps in tick spacings, drives the dynamic fee
+    pub volatility_accumulator: u32,
+    /// The decayed volatility of the swaps before the current second
+    pub volatility_reference: u32,
+    /// The tick the volatility of the current second is measured from
+    pub tick_reference: i32,
+    /// The timestamp of the last swap in dynamic fee mode
+    pub volatility_update_time: u32,
+
+    /// The trade fee of the pool overriding the one of the amm config, used when
+    /// trade_fee_rate_override_enabled is set
+    pub trade_fee_rate_override: u32,
+    /// 1: trade_fee_rate_override is the trade fee of the pool, it can be zero, 0: not set
+    pub trade_fee_rate_override_enabled: u8,
+    pub padding5: [u8; 3],
+
+    /// The program called before and after each swap of the pool, default means no hook
+    pub swap_hook_program: Pubkey,
+    /// The account passed writable to the hook program on each swap
+    pub swap_hook_state: Pubkey,
+
+    /// The time the status stops applying and the pool resumes, zero means no scheduled resume
+    pub status_resume_time: u64,
+
+    /// The token_0 in the vault for the positions when emergency exit was turned on,
+    /// protocol and fund fees excluded
+    pub emergency_available_0: u64,
+    /// The token_1 in the vault for the positions when emergency exit was turned on,
+    /// protocol and fund fees excluded
+    pub emergency_available_1: u64,
+    /// The token_0 owed to the positions at the price of the emergency exit, fees owed included
+    pub emergency_owed_0: u64,
+    /// The token_1 owed to the positions at the price of the emergency exit, fees owed included
+    pub emergency_owed_1: u64,
+
     // Unused bytes for future upgrades.
-    pub padding1: [u64; 24],
+    pub padding1: [u64; 5],
     pub padding2: [u64; 32],
 }
 
@@ -220,7 +274,11 @@ impl PoolState {
         self.swap_in_amount_token_1 = 0;
         self.swap_out_amount_token_0 = 0;
         self.status = 0;
-        self.padding = [0; 7];
+        self.access_mode = 0;
+        self.status_reason = 0;
+        self.emergency_exit = 0;
+        self.flash_in_progress = 0;
+        self.padding = [0; 3];
         self.tick_array_bitmap = [0; 16];
         self.total_fees_token_0 = 0;
         self.total_fees_claimed_token_0 = 0;
@@ -230,7 +288,23 @@ impl PoolState {
         self.fund_fees_token_1 = 0;
         self.open_time = open_time;
         self.recent_epoch = get_recent_epoch()?;
-        self.padding1 = [0; 24];
+        self.seconds_per_liquidity_global_x64 = 0;
+        self.seconds_per_liquidity_update_time = 0;
+        self.volatility_accumulator = 0;
+        self.volatility_reference = 0;
+        self.tick_reference = 0;
+        self.volatility_update_time = 0;
+        self.trade_fee_rate_override = 0;
+        self.trade_fee_rate_override_enabled = 0;
+        self.padding5 = [0; 3];
+        self.swap_hook_program = Pubkey::default();
+        self.swap_hook_state = Pubkey::default();
+        self.status_resume_time = 0;
+        self.emergency_available_0 = 0;
+        self.emergency_available_1 = 0;
+        self.emergency_owed_0 = 0;
+        self.emergency_owed_1 = 0;
+        self.padding1 = [0; 5];
         self.padding2 = [0; 32];
         self.observation_key = observation_state_key;
 
@@ -332,6 +406,94 @@ impl PoolState {
         Ok(())
     }
 
+    /// Returns the seconds per liquidity global as of the given timestamp, without writing it.
+    /// The in range liquidity is assumed unchanged since the last update.
+    pub fn seconds_per_liquidity_at(&self, curr_timestamp: u64) -> u128 {
+        // nothing accrues before the first update or while no liquidity is in range
+        if self.seconds_per_liquidity_update_time == 0 || self.liquidity == 0 {
+            return self.seconds_per_liquidity_global_x64;
+        }
+        let time_delta = curr_timestamp.saturating_sub(self.seconds_per_liquidity_update_time);
+        self.seconds_per_liquidity_global_x64
+            .wrapping_add((u128::from(time_delta) << fixed_point_64::RESOLUTION) / self.liquidity)
+    }
+
+    // Accrues the seconds per liquidity global up to the given timestamp.
+    // Must be called before the in range liquidity changes.
+    pub fn update_seconds_per_liquidity(&mut self, curr_timestamp: u64) -> u128 {
+        self.seconds_per_liquidity_global_x64 = self.seconds_per_liquidity_at(curr_timestamp);
+        self.seconds_per_liquidity_update_time =
+            curr_timestamp.max(self.seconds_per_liquidity_update_time);
+        self.seconds_per_liquidity_global_x64
+    }
+
+    /// Whether swaps of the pool call a hook program before and after the swap
+    pub fn has_swap_hook(&self) -> bool {
+        self.swap_hook_program != Pubkey::default()
+    }
+
+    /// The hook accounts are passed along with the tick arrays, and skipped when loading them
+    pub fn is_swap_hook_account(&self, key: &Pubkey) -> bool {
+        self.has_swap_hook() && (*key == self.swap_hook_program || *key == self.swap_hook_state)
+    }
+
+    /// Whether any reward of the pool has been initialized
+    pub fn has_rewards(&self) -> bool {
+        self.reward_infos
+            .iter()
+            .any(|reward_info| reward_info.initialized())
+    }
+
+    /// The trade fee rate of the pool, the override if set, otherwise the one of the amm config
+    pub fn trade_fee_rate(&self, amm_config: &AmmConfig) -> u32 {
+        self.get_trade_fee_rate_override()
+            .unwrap_or(amm_config.trade_fee_rate)
+    }
+
+    /// Swaps are only allowed after the open time, liquidity can be added before it
+    pub fn check_open(&self, block_timestamp: u64) -> Result<()> {
+        require_gt!(block_timestamp, self.open_time, ErrorCode::PoolNotOpen);
+        Ok(())
+    }
+
+    /// The trade fee rate overriding the one of the amm config, if set
+    pub fn get_trade_fee_rate_override(&self) -> Option<u32> {
+        if self.trade_fee_rate_override_enabled != 0 {
+            Some(self.trade_fee_rate_override)
+        } else {
+            None
+        }
+    }
+
+    /// Sets the trade fee rate overriding the one of the amm config, none clears it
+    pub fn set_trade_fee_rate_override(&mut self, trade_fee_rate: Option<u32>) {
+        self.trade_fee_rate_override_enabled = trade_fee_rate.is_some().into();
+        self.trade_fee_rate_override = trade_fee_rate.unwrap_or(0);
+    }
+
+    /// Decays the volatility of past swaps, called at the start of a swap in dynamic fee mode.
+    /// Swaps in the same second keep measuring their volatility from the same reference.
+    pub fn update_volatility_reference(&mut self, decay_period: u32, block_timestamp: u32) {
+        let elapsed = block_timestamp.saturating_sub(self.volatility_update_time);
+        if elapsed == 0 {
+            return;
+        }
+        self.volatility_reference = if elapsed >= decay_period {
+            0
+        } else {
+            (u64::from(self.volatility_accumulator) * u64::from(decay_period - elapsed)
+                / u64::from(decay_period)) as u32
+        };
+        self.tick_reference = self.tick_current;
+        self.volatility_update_time = block_timestamp;
+    }
+
+    /// The volatility if the price moves to the given tick, in tick spacings
+    pub fn volatility_at(&self, tick: i32) -> u32 {
+        let ticks_moved = tick.abs_diff(self.tick_reference) / u32::from(self.tick_spacing);
+        self.volatility_reference.saturating_add(ticks_moved)
+    }
+
     // Calculates the next global reward growth variables based on the given timestamp.
     // The provided timestamp must be greater than or equal to the last updated timestamp.
     pub fn update_reward_infos(&mut self, curr_timestamp: u64) -> Result<[RewardInfo; REWARD_NUM]> {
@@ -551,11 +713,45 @@ impl PoolState {
         }
     }
 
+    /// Set the status until it is changed again, any scheduled resume is cancelled
     pub fn set_status(&mut self, status: u8) {
-        self.status = status
+        self.status = status;
+        self.status_resume_time = 0;
+    }
+
+    /// Set the status with the reason of the change, and the time the pool resumes if not zero
+    pub fn set_status_with_reason(&mut self, status: u8, reason: u8, resume_time: u64) {
+        self.status = status;
+        self.status_reason = reason;
+        self.status_resume_time = resume_time;
+    }
+
+    /// Whether the status has reached its scheduled resume at `block_timestamp`
+    pub fn is_status_expired(&self, block_timestamp: u64) -> bool {
+        self.status_resume_time != 0 && block_timestamp >= self.status_resume_time
+    }
+
+    /// The clock is only read when a resume is scheduled
+    fn is_status_expired_now(&self) -> Result<bool> {
+        if self.status_resume_time == 0 {
+            return Ok(false);
+        }
+        Ok(self.is_status_expired(Clock::get()?.unix_timestamp as u64))
     }
 
-    pub fn set_status_by_bit(&mut self, bit: PoolStatusBitIndex, flag: PoolStatusBitFlag) {
+    /// Automatic status changes are safety measures, so the status they leave is kept
+    /// until it is changed again
+    pub fn set_status_by_bit(
+        &mut self,
+        bit: PoolStatusBitIndex,
+        flag: PoolStatusBitFlag,
+    ) -> Result<()> {
+        if self.status_resume_time != 0 {
+            if self.is_status_expired_now()? {
+                self.status = 0;
+            }
+            self.status_resume_time = 0;
+        }
         let s = u8::from(1) << (bit as u8);
         if flag == PoolStatusBitFlag::Disable {
             self.status = self.status.bitor(s);
@@ -563,12 +759,95 @@ impl PoolState {
             let m = u8::from(255).bitxor(s);
             self.status = self.status.bitand(m);
         }
+        Ok(())
     }
 
     /// Get status by bit, if it is `noraml` status, return true
-    pub fn get_status_by_bit(&self, bit: PoolStatusBitIndex) -> bool {
+    pub fn get_status_by_bit(&self, bit: PoolStatusBitIndex) -> Result<bool> {
+        if self.is_emergency_exit()
+            && matches!(
+                bit,
+                PoolStatusBitIndex::Swap | PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity
+            )
+        {
+            return Ok(false);
+        }
+        // the exits paying in full would take the share of the others from a short vault
+        if self.is_emergency_shortfall()
+            && matches!(
+                bit,
+                PoolStatusBitIndex::DecreaseLiquidity | PoolStatusBitIndex::CollectFee
+            )
+        {
+            return Ok(false);
+        }
+        if self.is_flash_in_progress()
+            && matches!(
+                bit,
+                PoolStatusBitIndex::Swap
+                    | PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity
+                    | PoolStatusBitIndex::DecreaseLiquidity
+            )
+        {
+            return Ok(false);
+        }
         let status = u8::from(1) << (bit as u8);
-        self.status.bitand(status) == 0
+        Ok(self.status.bitand(status) == 0 || self.is_status_expired_now()?)
+    }
+
+    pub fn is_emergency_exit(&self) -> bool {
+        self.emergency_exit != 0
+    }
+
+    /// Whether the vaults could not pay all the positions in full when emergency exit was turned on
+    pub fn is_emergency_shortfall(&self) -> bool {
+        self.is_emergency_exit()
+            && (self.emergency_available_0 < self.emergency_owed_0
+                || self.emergency_available_1 < self.emergency_owed_1)
+    }
+
+    /// Records what the vaults hold for the positions and what the positions are owed, the
+    /// emergency withdrawals are paid in proportion
+    pub fn set_emergency_exit(
+        &mut self,
+        vault_amount_0: u64,
+        vault_amount_1: u64,
+        owed_0: u64,
+        owed_1: u64,
+    ) {
+        self.emergency_exit = 1;
+        self.emergency_available_0 = vault_amount_0
+            .saturating_sub(self.protocol_fees_token_0)
+            .saturating_sub(self.fund_fees_token_0);
+        self.emergency_available_1 = vault_amount_1
+            .saturating_sub(self.protocol_fees_token_1)
+            .saturating_sub(self.fund_fees_token_1);
+        self.emergency_owed_0 = owed_0;
+        self.emergency_owed_1 = owed_1;
+    }
+
+    /// The part of `amount` owed to a position paid by an emergency withdrawal, the vaults
+    /// short of what the positions are owed pay every position the same proportion
+    pub fn get_emergency_payout(&self, amount: u64, zero: bool) -> u64 {
+        let (available, owed) = if zero {
+            (self.emergency_available_0, self.emergency_owed_0)
+        } else {
+            (self.emergency_available_1, self.emergency_owed_1)
+        };
+        if available >= owed {
+            return amount;
+        }
+        u64::try_from(u128::from(amount) * u128::from(available) / u128::from(owed)).unwrap()
+    }
+
+    pub fn is_flash_in_progress(&self) -> bool {
+        self.flash_in_progress != 0
+    }
+
+    /// Get access by bit, if it is restricted to the allow list, return true
+    pub fn is_access_restricted(&self, bit: PoolAccessBitIndex) -> bool {
+        let access = u8::from(1) << (bit as u8);
+        self.access_mode.bitand(access) != 0
     }
 
     pub fn is_overflow_default_tickarray_bitmap(&self, tick_indexs: Vec<i32>) -> bool {
@@ -727,6 +1006,99 @@ pub struct CollectProtocolFeeEvent {
     pub amount_1: u64,
 }
 
+/// Emitted when the trade fee override of a pool is set or cleared
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolFeeChangeEvent {
+    /// The pool whose trade fee is changed
+    #[index]
+    pub pool_state: Pubkey,
+
+    /// The trade fee override before the change, none means the fee of the amm config was used
+    pub trade_fee_rate_override_before: Option<u32>,
+
+    /// The trade fee override after the change, none means the fee of the amm config is used
+    pub trade_fee_rate_override: Option<u32>,
+}
+
+/// Emitted when the accesses of a pool restricted to its allow list are changed
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolAccessModeChangeEvent {
+    /// The pool whose access mode is changed
+    #[index]
+    pub pool_state: Pubkey,
+
+    /// The access mode before the change
+    pub access_mode_before: u8,
+
+    /// The access mode after the change
+    pub access_mode: u8,
+}
+
+/// Emitted when the swap hook of a pool is set or cleared
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolSwapHookChangeEvent {
+    /// The pool whose swap hook is changed
+    #[index]
+    pub pool_state: Pubkey,
+
+    /// The hook program before the change, default means no hook
+    pub swap_hook_program_before: Pubkey,
+
+    /// The hook program after the change, default means no hook
+    pub swap_hook_program: Pubkey,
+
+    /// The account passed writable to the hook program
+    pub swap_hook_state: Pubkey,
+}
+
+/// Emitted when the status of a pool is changed by an admin
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolStatusChangeEvent {
+    /// The pool whose status is changed
+    #[index]
+    pub pool_state: Pubkey,
+
+    /// The admin or operation owner changing the status
+    pub authority: Pubkey,
+
+    /// The status before the change
+    pub status_before: u8,
+
+    /// The status after the change
+    pub status: u8,
+
+    /// The reason code of the change, zero means unspecified
+    pub reason: u8,
+
+    /// The time the pool resumes, zero means no scheduled resume
+    pub resume_time: u64,
+}
+
+/// Emitted when a pool is put in emergency exit
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolEmergencyExitEvent {
+    /// The pool whose emergency exit is changed
+    #[index]
+    pub pool_state: Pubkey,
+
+    /// Whether the pool is in emergency exit after the change
+    pub emergency_exit: bool,
+
+    /// The token_0 in the vault for the positions, protocol and fund fees excluded
+    pub emergency_available_0: u64,
+    /// The token_1 in the vault for the positions, protocol and fund fees excluded
+    pub emergency_available_1: u64,
+    /// The token_0 owed to the positions at the price of the emergency exit
+    pub emergency_owed_0: u64,
+    /// The token_1 owed to the positions at the price of the emergency exit
+    pub emergency_owed_1: u64,
+}
+
 /// Emitted by when a swap is performed for a pool
 #[event]
 #[cfg_attr(feature = "client", derive(Debug))]
@@ -772,6 +1144,44 @@ pub struct SwapEvent {
 
     /// The log base 1.0001 of price of the pool after the swap
     pub tick: i32,
+
+    /// The trade fee rate charged, denominated in hundredths of a bip (10^-6).
+    /// In dynamic fee mode it is the rate of the last swap step
+    pub trade_fee_rate: u32,
+}
+
+/// Emitted when tokens are borrowed from the vaults and paid back in a flash
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct FlashEvent {
+    /// The pool lending the tokens
+    #[index]
+    pub pool_state: Pubkey,
+
+    /// The borrower
+    #[index]
+    pub sender: Pubkey,
+
+    /// The program called back with the tokens
+    pub receiver_program: Pubkey,
+
+    /// The amount of token_0 lent
+    pub amount_0: u64,
+
+    /// The amount of token_1 lent
+    pub amount_1: u64,
+
+    /// The fee owed for token_0
+    pub fee_0: u64,
+
+    /// The fee owed for token_1
+    pub fee_1: u64,
+
+    /// The amount of token_0 paid to the vault above its balance before, charged as fee
+    pub paid_0: u64,
+
+    /// The amount of token_1 paid to the vault above its balance before, charged as fee
+    pub paid_1: u64,
 }
 
 /// Emitted pool liquidity change when increase and decrease liquidity
@@ -863,6 +1273,14 @@ pub mod pool_test {
         RefCell::new(new_pool)
     }
 
+    /// Builds a pool account which lives until the end of the test, to load with `AccountLoader`
+    pub fn build_pool_account_info(pool_state: &PoolState) -> &'static AccountInfo<'static> {
+        use anchor_lang::Discriminator;
+        let mut data = PoolState::discriminator().to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(pool_state));
+        crate::util::build_account_info(pool_state.key(), data)
+    }
+
     mod tick_array_bitmap_test {
 
         use super::*;
@@ -974,55 +1392,178 @@ pub mod pool_test {
             let mut pool_state = PoolState::default();
             pool_state.set_status(17); // 00010001
             assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::Swap),
+                pool_state
+                    .get_status_by_bit(PoolStatusBitIndex::Swap)
+                    .unwrap(),
                 false
             );
             assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity),
+                pool_state
+                    .get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)
+                    .unwrap(),
                 false
             );
             assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity),
+                pool_state
+                    .get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity)
+                    .unwrap(),
                 true
             );
             assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::CollectFee),
+                pool_state
+                    .get_status_by_bit(PoolStatusBitIndex::CollectFee)
+                    .unwrap(),
                 true
             );
             assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::CollectReward),
+                pool_state
+                    .get_status_by_bit(PoolStatusBitIndex::CollectReward)
+                    .unwrap(),
                 true
             );
 
             // disable -> disable, nothing to change
-            pool_state.set_status_by_bit(PoolStatusBitIndex::Swap, PoolStatusBitFlag::Disable);
+            pool_state
+                .set_status_by_bit(PoolStatusBitIndex::Swap, PoolStatusBitFlag::Disable)
+                .unwrap();
             assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::Swap),
+                pool_state
+                    .get_status_by_bit(PoolStatusBitIndex::Swap)
+                    .unwrap(),
                 false
             );
 
             // disable -> enable
-            pool_state.set_status_by_bit(PoolStatusBitIndex::Swap, PoolStatusBitFlag::Enable);
-            assert_eq!(pool_state.get_status_by_bit(PoolStatusBitIndex::Swap), true);
+            pool_state
+                .set_status_by_bit(PoolStatusBitIndex::Swap, PoolStatusBitFlag::Enable)
+                .unwrap();
+            assert_eq!(
+                pool_state
+                    .get_status_by_bit(PoolStatusBitIndex::Swap)
+                    .unwrap(),
+                true
+            );
 
             // enable -> enable, nothing to change
-            pool_state.set_status_by_bit(
-                PoolStatusBitIndex::DecreaseLiquidity,
-                PoolStatusBitFlag::Enable,
-            );
+            pool_state
+                .set_status_by_bit(
+                    PoolStatusBitIndex::DecreaseLiquidity,
+                    PoolStatusBitFlag::Enable,
+                )
+                .unwrap();
             assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity),
+                pool_state
+                    .get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity)
+                    .unwrap(),
                 true
             );
             // enable -> disable
-            pool_state.set_status_by_bit(
-                PoolStatusBitIndex::DecreaseLiquidity,
-                PoolStatusBitFlag::Disable,
+            pool_state
+                .set_status_by_bit(
+                    PoolStatusBitIndex::DecreaseLiquidity,
+                    PoolStatusBitFlag::Disable,
+                )
+                .unwrap();
+            assert_eq!(
+                pool_state
+                    .get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity)
+                    .unwrap(),
+                false
+            );
+        }
+
+        #[test]
+        fn check_open_test() {
+            let pool_state = PoolState {
+                open_time: 1000,
+                ..Default::default()
+            };
+            assert_eq!(
+                pool_state.check_open(999).unwrap_err(),
+                error!(ErrorCode::PoolNotOpen)
+            );
+            assert_eq!(
+                pool_state.check_open(1000).unwrap_err(),
+                error!(ErrorCode::PoolNotOpen)
+            );
+            assert!(pool_state.check_open(1001).is_ok());
+
+            // a pool created without open time is open at once
+            assert!(PoolState::default().check_open(1).is_ok());
+        }
+
+        #[test]
+        fn status_resume_time_test() {
+            let mut pool_state = PoolState::default();
+            pool_state.set_status_with_reason(16, 2, 1000);
+            assert_eq!(pool_state.status_reason, 2);
+            assert_eq!(pool_state.is_status_expired(999), false);
+            assert_eq!(pool_state.is_status_expired(1000), true);
+
+            // the status bits read the clock once a resume is scheduled
+            crate::util::set_clock_sysvar_stub();
+            assert!(pool_state
+                .get_status_by_bit(PoolStatusBitIndex::Swap)
+                .unwrap());
+            pool_state.set_status_with_reason(16, 2, u64::MAX);
+            assert!(!pool_state
+                .get_status_by_bit(PoolStatusBitIndex::Swap)
+                .unwrap());
+
+            // a status set without resume time never expires
+            pool_state.set_status(16);
+            assert_eq!({ pool_state.status_resume_time }, 0);
+            assert_eq!(pool_state.is_status_expired(u64::MAX), false);
+        }
+
+        #[test]
+        fn emergency_exit_status_test() {
+            let mut pool_state = PoolState::default();
+            pool_state.emergency_exit = 1;
+            assert_eq!(
+                pool_state
+                    .get_status_by_bit(PoolStatusBitIndex::Swap)
+                    .unwrap(),
+                false
             );
             assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity),
+                pool_state
+                    .get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)
+                    .unwrap(),
                 false
             );
+            assert_eq!(
+                pool_state
+                    .get_status_by_bit(PoolStatusBitIndex::CollectFee)
+                    .unwrap(),
+                true
+            );
+        }
+
+        #[test]
+        fn flash_in_progress_status_test() {
+            let mut pool_state = PoolState::default();
+            pool_state.flash_in_progress = 1;
+            for bit in [
+                PoolStatusBitIndex::Swap,
+                PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity,
+                PoolStatusBitIndex::DecreaseLiquidity,
+            ] {
+                assert_eq!(pool_state.get_status_by_bit(bit).unwrap(), false);
+            }
+            assert_eq!(
+                pool_state
+                    .get_status_by_bit(PoolStatusBitIndex::CollectFee)
+                    .unwrap(),
+                true
+            );
+            pool_state.flash_in_progress = 0;
+            assert_eq!(
+                pool_state
+                    .get_status_by_bit(PoolStatusBitIndex::Swap)
+                    .unwrap(),
+                true
+            );
         }
     }
 
@@ -1618,7 +2159,11 @@ pub mod pool_test {
             let swap_in_amount_token_1: u128 = 0x11223344556677008899aabbccddeeff;
             let swap_out_amount_token_0: u128 = 0x11223344556677880099aabbccddeeff;
             let status: u8 = 0x1b;
-            let padding: [u8; 7] = [0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18];
+            let access_mode: u8 = 0x03;
+            let status_reason: u8 = 0x07;
+            let emergency_exit: u8 = 0x01;
+            let flash_in_progress: u8 = 0x01;
+            let padding: [u8; 3] = [0x13, 0x14, 0x15];
             // RewardInfo
             let reward_state: u8 = 0x1c;
             let open_time: u64 = 0x123456789abc0def;
@@ -1689,10 +2234,26 @@ pub mod pool_test {
             let fund_fees_token_1: u64 = 0x1230456789abcdef;
             let pool_open_time: u64 = 0x1203456789abcdef;
             let recent_epoch: u64 = 0x1023456789abcdef;
-            let mut padding1: [u64; 24] = [0u64; 24];
-            let mut padding1_data = [0u8; 8 * 24];
+            let seconds_per_liquidity_global_x64: u128 = 0x11223344556677889900aabbccddeeff;
+            let seconds_per_liquidity_update_time: u64 = 0x0123456789abcdef;
+            let volatility_accumulator: u32 = 0x12345678;
+            let volatility_reference: u32 = 0x12345687;
+            let tick_reference: i32 = -0x12345678;
+            let volatility_update_time: u32 = 0x12345876;
+            let trade_fee_rate_override: u32 = 0x12348765;
+            let trade_fee_rate_override_enabled: u8 = 0x01;
+            let padding5: [u8; 3] = [0x12, 0x38, 0x76];
+            let swap_hook_program = Pubkey::new_unique();
+            let swap_hook_state = Pubkey::new_unique();
+            let status_resume_time: u64 = 0x1234567890abcdef;
+            let emergency_available_0: u64 = 0x1234567890abcdfe;
+            let emergency_available_1: u64 = 0x1234567890abcedf;
+            let emergency_owed_0: u64 = 0x1234567890acbdef;
+            let emergency_owed_1: u64 = 0x1234567890bacdef;
+            let mut padding1: [u64; 5] = [0u64; 5];
+            let mut padding1_data = [0u8; 8 * 5];
             let mut offset = 0;
-            for i in 0..24 {
+            for i in 0..5 {
                 padding1[i] = u64::MAX - i as u64;
                 padding1_data[offset..offset + 8].copy_from_slice(&padding1[i].to_le_bytes());
                 offset += 8;
@@ -1760,8 +2321,16 @@ pub mod pool_test {
             offset += 16;
             pool_data[offset..offset + 1].copy_from_slice(&status.to_le_bytes());
             offset += 1;
-            pool_data[offset..offset + 7].copy_from_slice(&padding);
-            offset += 7;
+            pool_data[offset..offset + 1].copy_from_slice(&access_mode.to_le_bytes());
+            offset += 1;
+            pool_data[offset..offset + 1].copy_from_slice(&status_reason.to_le_bytes());
+            offset += 1;
+            pool_data[offset..offset + 1].copy_from_slice(&emergency_exit.to_le_bytes());
+            offset += 1;
+            pool_data[offset..offset + 1].copy_from_slice(&flash_in_progress.to_le_bytes());
+            offset += 1;
+            pool_data[offset..offset + 3].copy_from_slice(&padding);
+            offset += 3;
             pool_data[offset..offset + RewardInfo::LEN * REWARD_NUM]
                 .copy_from_slice(&reward_info_datas);
             offset += RewardInfo::LEN * REWARD_NUM;
@@ -1785,8 +2354,43 @@ pub mod pool_test {
             offset += 8;
             pool_data[offset..offset + 8].copy_from_slice(&recent_epoch.to_le_bytes());
             offset += 8;
-            pool_data[offset..offset + 8 * 24].copy_from_slice(&padding1_data);
-            offset += 8 * 24;
+            pool_data[offset..offset + 16]
+                .copy_from_slice(&seconds_per_liquidity_global_x64.to_le_bytes());
+            offset += 16;
+            pool_data[offset..offset + 8]
+                .copy_from_slice(&seconds_per_liquidity_update_time.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 4].copy_from_slice(&volatility_accumulator.to_le_bytes());
+            offset += 4;
+            pool_data[offset..offset + 4].copy_from_slice(&volatility_reference.to_le_bytes());
+            offset += 4;
+            pool_data[offset..offset + 4].copy_from_slice(&tick_reference.to_le_bytes());
+            offset += 4;
+            pool_data[offset..offset + 4].copy_from_slice(&volatility_update_time.to_le_bytes());
+            offset += 4;
+            pool_data[offset..offset + 4].copy_from_slice(&trade_fee_rate_override.to_le_bytes());
+            offset += 4;
+            pool_data[offset..offset + 1]
+                .copy_from_slice(&trade_fee_rate_override_enabled.to_le_bytes());
+            offset += 1;
+            pool_data[offset..offset + 3].copy_from_slice(&padding5);
+            offset += 3;
+            pool_data[offset..offset + 32].copy_from_slice(&swap_hook_program.to_bytes());
+            offset += 32;
+            pool_data[offset..offset + 32].copy_from_slice(&swap_hook_state.to_bytes());
+            offset += 32;
+            pool_data[offset..offset + 8].copy_from_slice(&status_resume_time.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 8].copy_from_slice(&emergency_available_0.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 8].copy_from_slice(&emergency_available_1.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 8].copy_from_slice(&emergency_owed_0.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 8].copy_from_slice(&emergency_owed_1.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 8 * 5].copy_from_slice(&padding1_data);
+            offset += 8 * 5;
             pool_data[offset..offset + 8 * 32].copy_from_slice(&padding2_data);
             offset += 8 * 32;
 
@@ -1849,6 +2453,14 @@ pub mod pool_test {
             assert_eq!(unpack_swap_out_amount_token_0, swap_out_amount_token_0);
             let unpack_status = unpack_data.status;
             assert_eq!(unpack_status, status);
+            let unpack_access_mode = unpack_data.access_mode;
+            assert_eq!(unpack_access_mode, access_mode);
+            let unpack_status_reason = unpack_data.status_reason;
+            assert_eq!(unpack_status_reason, status_reason);
+            let unpack_emergency_exit = unpack_data.emergency_exit;
+            assert_eq!(unpack_emergency_exit, emergency_exit);
+            let unpack_flash_in_progress = unpack_data.flash_in_progress;
+            assert_eq!(unpack_flash_in_progress, flash_in_progress);
             let unpack_padding = unpack_data.padding;
             assert_eq!(unpack_padding, padding);
 
@@ -1899,6 +2511,50 @@ pub mod pool_test {
             assert_eq!(unpack_open_time, pool_open_time);
             let unpack_recent_epoch = unpack_data.recent_epoch;
             assert_eq!(unpack_recent_epoch, recent_epoch);
+            let unpack_seconds_per_liquidity_global_x64 =
+                unpack_data.seconds_per_liquidity_global_x64;
+            assert_eq!(
+                unpack_seconds_per_liquidity_global_x64,
+                seconds_per_liquidity_global_x64
+            );
+            let unpack_seconds_per_liquidity_update_time =
+                unpack_data.seconds_per_liquidity_update_time;
+            assert_eq!(
+                unpack_seconds_per_liquidity_update_time,
+                seconds_per_liquidity_update_time
+            );
+            let unpack_volatility_accumulator = unpack_data.volatility_accumulator;
+            assert_eq!(unpack_volatility_accumulator, volatility_accumulator);
+            let unpack_volatility_reference = unpack_data.volatility_reference;
+            assert_eq!(unpack_volatility_reference, volatility_reference);
+            let unpack_tick_reference = unpack_data.tick_reference;
+            assert_eq!(unpack_tick_reference, tick_reference);
+            let unpack_volatility_update_time = unpack_data.volatility_update_time;
+            assert_eq!(unpack_volatility_update_time, volatility_update_time);
+            let unpack_trade_fee_rate_override = unpack_data.trade_fee_rate_override;
+            assert_eq!(unpack_trade_fee_rate_override, trade_fee_rate_override);
+            let unpack_trade_fee_rate_override_enabled =
+                unpack_data.trade_fee_rate_override_enabled;
+            assert_eq!(
+                unpack_trade_fee_rate_override_enabled,
+                trade_fee_rate_override_enabled
+            );
+            let unpack_padding5 = unpack_data.padding5;
+            assert_eq!(unpack_padding5, padding5);
+            let unpack_swap_hook_program = unpack_data.swap_hook_program;
+            assert_eq!(unpack_swap_hook_program, swap_hook_program);
+            let unpack_swap_hook_state = unpack_data.swap_hook_state;
+            assert_eq!(unpack_swap_hook_state, swap_hook_state);
+            let unpack_status_resume_time = unpack_data.status_resume_time;
+            assert_eq!(unpack_status_resume_time, status_resume_time);
+            let unpack_emergency_available_0 = unpack_data.emergency_available_0;
+            assert_eq!(unpack_emergency_available_0, emergency_available_0);
+            let unpack_emergency_available_1 = unpack_data.emergency_available_1;
+            assert_eq!(unpack_emergency_available_1, emergency_available_1);
+            let unpack_emergency_owed_0 = unpack_data.emergency_owed_0;
+            assert_eq!(unpack_emergency_owed_0, emergency_owed_0);
+            let unpack_emergency_owed_1 = unpack_data.emergency_owed_1;
+            assert_eq!(unpack_emergency_owed_1, emergency_owed_1);
             let unpack_padding1 = unpack_data.padding1;
             assert_eq!(unpack_padding1, padding1);
             let unpack_padding2 = unpack_data.padding2;
@@ -0,0 +1,174 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use std::collections::HashSet;
+
+pub const POOL_ALLOW_LIST_SEED: &str = "pool_allow_list";
+pub const ALLOW_LIST_SIZE_USIZE: usize = 100;
+/// The bits of `PoolState::access_mode` in use
+pub const POOL_ACCESS_MODE_MASK: u8 = 0b11;
+
+/// The accesses of a pool which can be restricted to its allow list
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PoolAccessBitIndex {
+    Swap,
+    Liquidity,
+}
+
+/// Holds the wallets allowed in a permissioned pool
+///
+/// PDA of `[POOL_ALLOW_LIST_SEED, pool_id]`
+///
+#[account(zero_copy(unsafe))]
+#[repr(C, packed)]
+#[derive(Debug)]
+pub struct PoolAllowListState {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool the allow list belongs to
+    pub pool_id: Pubkey,
+    /// The wallets allowed to swap when swaps are restricted
+    pub traders: [Pubkey; ALLOW_LIST_SIZE_USIZE],
+    /// The wallets allowed to add liquidity when liquidity is restricted
+    pub liquidity_providers: [Pubkey; ALLOW_LIST_SIZE_USIZE],
+}
+
+impl PoolAllowListState {
+    pub const LEN: usize = 8 + 1 + 32 + 32 * ALLOW_LIST_SIZE_USIZE * 2;
+
+    pub fn initialize(&mut self, bump: u8, pool_id: Pubkey) {
+        self.bump = bump;
+        self.pool_id = pool_id;
+        self.traders = [Pubkey::default(); ALLOW_LIST_SIZE_USIZE];
+        self.liquidity_providers = [Pubkey::default(); ALLOW_LIST_SIZE_USIZE];
+    }
+
+    pub fn validate_trader(&self, trader: Pubkey) -> bool {
+        trader != Pubkey::default() && self.traders.contains(&trader)
+    }
+
+    pub fn validate_liquidity_provider(&self, liquidity_provider: Pubkey) -> bool {
+        liquidity_provider != Pubkey::default()
+            && self.liquidity_providers.contains(&liquidity_provider)
+    }
+
+    pub fn update_traders(&mut self, keys: Vec<Pubkey>) -> Result<()> {
+        update_allow_list(&mut self.traders, keys)
+    }
+
+    pub fn remove_traders(&mut self, keys: Vec<Pubkey>) {
+        remove_from_allow_list(&mut self.traders, keys)
+    }
+
+    pub fn update_liquidity_providers(&mut self, keys: Vec<Pubkey>) -> Result<()> {
+        update_allow_list(&mut self.liquidity_providers, keys)
+    }
+
+    pub fn remove_liquidity_providers(&mut self, keys: Vec<Pubkey>) {
+        remove_from_allow_list(&mut self.liquidity_providers, keys)
+    }
+
+    /// The allow list can be passed along with tick arrays, which skip it by its discriminator
+    pub fn is_allow_list_account(account_info: &AccountInfo) -> bool {
+        account_info.owner == &crate::id()
+            && account_info
+                .try_borrow_data()
+                .map(|data| data.len() >= 8 && data[..8] == PoolAllowListState::discriminator())
+                .unwrap_or(false)
+    }
+}
+
+fn update_allow_list(
+    allow_list: &mut [Pubkey; ALLOW_LIST_SIZE_USIZE],
+    keys: Vec<Pubkey>,
+) -> Result<()> {
+    let mut allowed = allow_list.to_vec();
+    allowed.extend(keys.as_slice().iter());
+    allowed.retain(|&item| item != Pubkey::default());
+    let allowed_set: HashSet<Pubkey> = HashSet::from_iter(allowed.iter().cloned());
+    let mut updated: Vec<Pubkey> = allowed_set.into_iter().collect();
+    require_gte!(
+        ALLOW_LIST_SIZE_USIZE,
+        updated.len(),
+        ErrorCode::AllowListFull
+    );
+    updated.sort();
+    // clear
+    *allow_list = [Pubkey::default(); ALLOW_LIST_SIZE_USIZE];
+    // update
+    allow_list[0..updated.len()].copy_from_slice(updated.as_slice());
+    Ok(())
+}
+
+fn remove_from_allow_list(allow_list: &mut [Pubkey; ALLOW_LIST_SIZE_USIZE], keys: Vec<Pubkey>) {
+    let mut allowed = allow_list.to_vec();
+    // remove keys from the allow list
+    allowed.retain(|x| *x != Pubkey::default() && !keys.contains(x));
+    // clear
+    *allow_list = [Pubkey::default(); ALLOW_LIST_SIZE_USIZE];
+    // update
+    allow_list[0..allowed.len()].copy_from_slice(allowed.as_slice());
+}
+
+/// Checks `user` is allowed the `access` of the pool. The allow list of the pool
+/// is only required in the remaining accounts when the access is restricted
+pub fn check_pool_access<'c: 'info, 'info>(
+    pool_state: &PoolState,
+    pool_id: Pubkey,
+    remaining_accounts: &'c [AccountInfo<'info>],
+    user: Pubkey,
+    access: PoolAccessBitIndex,
+) -> Result<()> {
+    if !pool_state.is_access_restricted(access) {
+        return Ok(());
+    }
+    for account_info in remaining_accounts {
+        if !PoolAllowListState::is_allow_list_account(account_info) {
+            continue;
+        }
+        let allow_list_loader = AccountLoader::<PoolAllowListState>::try_from(account_info)?;
+        let allow_list = allow_list_loader.load()?;
+        if allow_list.pool_id != pool_id {
+            continue;
+        }
+        let allowed = if access == PoolAccessBitIndex::Swap {
+            allow_list.validate_trader(user)
+        } else {
+            allow_list.validate_liquidity_provider(user)
+        };
+        require!(allowed, ErrorCode::NotAllowListed);
+        return Ok(());
+    }
+    err!(ErrorCode::PoolAllowListMissing)
+}
+
+#[cfg(test)]
+mod pool_allow_list_test {
+    use super::*;
+
+    #[test]
+    fn update_and_remove_allow_list_test() {
+        let mut allow_list = [Pubkey::default(); ALLOW_LIST_SIZE_USIZE];
+        let key_0 = Pubkey::new_unique();
+        let key_1 = Pubkey::new_unique();
+        update_allow_list(&mut allow_list, vec![key_1, key_0, key_1]).unwrap();
+        assert_eq!(allow_list[0], key_0.min(key_1));
+        assert_eq!(allow_list[1], key_0.max(key_1));
+        assert_eq!(allow_list[2], Pubkey::default());
+
+        remove_from_allow_list(&mut allow_list, vec![key_0]);
+        assert_eq!(allow_list[0], key_1);
+        assert_eq!(allow_list[1], Pubkey::default());
+    }
+
+    #[test]
+    fn update_full_allow_list_test() {
+        let mut allow_list = [Pubkey::default(); ALLOW_LIST_SIZE_USIZE];
+        let keys: Vec<Pubkey> = (0..ALLOW_LIST_SIZE_USIZE)
+            .map(|_| Pubkey::new_unique())
+            .collect();
+        update_allow_list(&mut allow_list, keys).unwrap();
+        assert!(update_allow_list(&mut allow_list, vec![Pubkey::new_unique()]).is_err());
+    }
+}
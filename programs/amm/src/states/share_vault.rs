@@ -0,0 +1,116 @@
+use crate::error::ErrorCode;
+use crate::pool::{PoolState, REWARD_NUM};
+use anchor_lang::prelude::*;
+
+pub const SHARE_VAULT_SEED: &str = "share_vault";
+pub const SHARE_VAULT_MINT_SEED: &str = "share_vault_mint";
+/// The shares of the first deposit locked in the vault, so the value of a share can not be inflated
+/// while the supply is tiny
+pub const SHARE_VAULT_LOCKED_SHARES: u64 = 1_000;
+
+/// Owns one position over a fixed range of a pool, and issues fungible shares of its liquidity
+///
+/// PDA of `[SHARE_VAULT_SEED, pool_id, tick_lower_index, tick_upper_index]`
+///
+#[account]
+#[derive(Default, Debug)]
+pub struct ShareVaultState {
+    /// Bump to identify PDA
+    pub bump: [u8; 1],
+    /// The pool of the vault position
+    pub pool_id: Pubkey,
+    /// The lower bound tick of the vault position
+    pub tick_lower_index: i32,
+    /// The upper bound tick of the vault position
+    pub tick_upper_index: i32,
+    /// The SPL share token mint, PDA of `[SHARE_VAULT_MINT_SEED, share_vault]`
+    pub share_mint: Pubkey,
+    /// The personal position owned by the vault, PDA of `[POSITION_SEED, share_vault]`
+    pub personal_position: Pubkey,
+    /// The shares locked by the first deposit, never minted and never withdrawn
+    pub locked_shares: u64,
+    pub padding: [u64; 7],
+}
+
+impl ShareVaultState {
+    pub const LEN: usize = 8 + 1 + 32 + 4 + 4 + 32 + 32 + 8 + 56;
+
+    /// The shares backing the liquidity of the vault position, the locked shares included
+    pub fn share_supply(&self, share_mint_supply: u64) -> u64 {
+        share_mint_supply.checked_add(self.locked_shares).unwrap()
+    }
+
+    /// The shares are fungible and can not remember since when they earn rewards, so a deposit
+    /// would take a part of the rewards owed to the current holders. The vault freezes once a
+    /// reward is initialized on its pool: deposits are rejected, and withdrawals keep paying the
+    /// rewards owed to the vault per share
+    pub fn check_deposit(pool_state: &PoolState) -> Result<()> {
+        require!(!pool_state.has_rewards(), ErrorCode::ShareVaultFrozen);
+        Ok(())
+    }
+}
+
+/// Emitted when a share vault is created
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct CreateShareVaultEvent {
+    /// The pool of the vault
+    #[index]
+    pub pool_state: Pubkey,
+    /// The share vault
+    pub share_vault: Pubkey,
+    /// The share token mint of the vault
+    pub share_mint: Pubkey,
+    /// The lower tick of the vault position
+    pub tick_lower_index: i32,
+    /// The upper tick of the vault position
+    pub tick_upper_index: i32,
+}
+
+/// Emitted when liquidity is deposited into a share vault
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct DepositShareVaultEvent {
+    /// The share vault
+    #[index]
+    pub share_vault: Pubkey,
+    /// The owner of the minted shares
+    pub owner: Pubkey,
+    /// The amount by which the liquidity of the vault position was increased
+    pub liquidity: u128,
+    /// The amount of shares minted
+    pub shares: u64,
+    /// The amount of token_0 paid for the liquidity
+    pub amount_0: u64,
+    /// The amount of token_1 paid for the liquidity
+    pub amount_1: u64,
+    /// The token transfer fee for amount_0
+    pub amount_0_transfer_fee: u64,
+    /// The token transfer fee for amount_1
+    pub amount_1_transfer_fee: u64,
+}
+
+/// Emitted when liquidity is withdrawn from a share vault
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct WithdrawShareVaultEvent {
+    /// The share vault
+    #[index]
+    pub share_vault: Pubkey,
+    /// The owner of the burned shares
+    pub owner: Pubkey,
+    /// The amount by which the liquidity of the vault position was decreased
+    pub liquidity: u128,
+    /// The amount of shares burned
+    pub shares: u64,
+    /// The amount of token_0 paid out, fees included
+    pub amount_0: u64,
+    /// The amount of token_1 paid out, fees included
+    pub amount_1: u64,
+    /// The token transfer fee for amount_0
+    pub transfer_fee_0: u64,
+    /// The token transfer fee for amount_1
+    pub transfer_fee_1: u64,
+    /// The rewards paid out for the shares
+    pub reward_amounts: [u64; REWARD_NUM],
+}
@@ -1,3 +1,4 @@
+use super::limit_order_fill::LimitOrderFill;
 use super::pool::PoolState;
 use crate::error::ErrorCode;
 use crate::libraries::{liquidity_math, tick_math};
@@ -5,6 +6,7 @@ use crate::pool::{RewardInfo, REWARD_NUM};
 use crate::util::*;
 use crate::Result;
 use anchor_lang::{prelude::*, system_program};
+use std::cell::{Ref, RefMut};
 #[cfg(feature = "enable-log")]
 use std::convert::identity;
 
@@ -121,6 +123,11 @@ impl TickArrayState {
         Ok(())
     }
 
+    pub fn get_tick_state(&self, tick_index: i32, tick_spacing: u16) -> Result<&TickState> {
+        let offset_in_array = self.get_tick_offset_in_array(tick_index, tick_spacing)?;
+        Ok(&self.ticks[offset_in_array])
+    }
+
     pub fn get_tick_state_mut(
         &mut self,
         tick_index: i32,
@@ -143,7 +150,7 @@ impl TickArrayState {
     }
 
     /// Get tick's offset in current tick array, tick must be include in tick array， otherwise throw an error
-    fn get_tick_offset_in_array(self, tick_index: i32, tick_spacing: u16) -> Result<usize> {
+    fn get_tick_offset_in_array(&self, tick_index: i32, tick_spacing: u16) -> Result<usize> {
         let start_tick_index = TickArrayState::get_array_start_index(tick_index, tick_spacing);
         require_eq!(
             start_tick_index,
@@ -156,12 +163,12 @@ impl TickArrayState {
     }
 
     /// Base on swap directioin, return the first initialized tick in the tick array.
-    pub fn first_initialized_tick(&mut self, zero_for_one: bool) -> Result<&mut TickState> {
+    pub fn first_initialized_tick(&self, zero_for_one: bool) -> Result<&TickState> {
         if zero_for_one {
             let mut i = TICK_ARRAY_SIZE - 1;
             while i >= 0 {
                 if self.ticks[i as usize].is_initialized() {
-                    return Ok(self.ticks.get_mut(i as usize).unwrap());
+                    return Ok(self.ticks.get(i as usize).unwrap());
                 }
                 i = i - 1;
             }
@@ -169,7 +176,7 @@ impl TickArrayState {
             let mut i = 0;
             while i < TICK_ARRAY_SIZE_USIZE {
                 if self.ticks[i].is_initialized() {
-                    return Ok(self.ticks.get_mut(i).unwrap());
+                    return Ok(self.ticks.get(i).unwrap());
                 }
                 i = i + 1;
             }
@@ -181,11 +188,11 @@ impl TickArrayState {
     /// and current_tick_index % tick_spacing maybe not equal zero.
     /// If price move to left tick <= current_tick_index, or to right tick > current_tick_index
     pub fn next_initialized_tick(
-        &mut self,
+        &self,
         current_tick_index: i32,
         tick_spacing: u16,
         zero_for_one: bool,
-    ) -> Result<Option<&mut TickState>> {
+    ) -> Result<Option<&TickState>> {
         let current_tick_array_start_index =
             TickArrayState::get_array_start_index(current_tick_index, tick_spacing);
         if current_tick_array_start_index != self.start_tick_index {
@@ -197,7 +204,7 @@ impl TickArrayState {
         if zero_for_one {
             while offset_in_array >= 0 {
                 if self.ticks[offset_in_array as usize].is_initialized() {
-                    return Ok(self.ticks.get_mut(offset_in_array as usize));
+                    return Ok(self.ticks.get(offset_in_array as usize));
                 }
                 offset_in_array = offset_in_array - 1;
             }
@@ -205,7 +212,7 @@ impl TickArrayState {
             offset_in_array = offset_in_array + 1;
             while offset_in_array < TICK_ARRAY_SIZE {
                 if self.ticks[offset_in_array as usize].is_initialized() {
-                    return Ok(self.ticks.get_mut(offset_in_array as usize));
+                    return Ok(self.ticks.get(offset_in_array as usize));
                 }
                 offset_in_array = offset_in_array + 1;
             }
@@ -250,6 +257,178 @@ impl TickArrayState {
     }
 }
 
+/// The tick array a swap runs over. A swap only writes the ticks it crosses, they are written
+/// to the account in place, or kept aside by a `TickArrayOverlay` when quoting.
+pub trait SwapTickArray {
+    /// The tick array as loaded, without the ticks kept aside
+    fn tick_array(&self) -> &TickArrayState;
+
+    /// The tick written by the swap, if it is kept aside
+    fn written_tick(&self, tick_index: i32) -> Option<TickState>;
+
+    fn update_tick_state(
+        &mut self,
+        tick_index: i32,
+        tick_spacing: u16,
+        tick_state: TickState,
+    ) -> Result<()>;
+
+    fn get_tick(&self, tick_index: i32, tick_spacing: u16) -> Result<TickState> {
+        let tick_state = *self.tick_array().get_tick_state(tick_index, tick_spacing)?;
+        Ok(self.written_tick(tick_index).unwrap_or(tick_state))
+    }
+
+    /// A swap does not change which ticks are initialized, the search runs over the tick array as loaded
+    fn first_initialized_tick(&self, zero_for_one: bool) -> Result<TickState> {
+        let tick_state = *self.tick_array().first_initialized_tick(zero_for_one)?;
+        Ok(self.written_tick(tick_state.tick).unwrap_or(tick_state))
+    }
+
+    fn next_initialized_tick(
+        &self,
+        current_tick_index: i32,
+        tick_spacing: u16,
+        zero_for_one: bool,
+    ) -> Result<Option<TickState>> {
+        Ok(self
+            .tick_array()
+            .next_initialized_tick(current_tick_index, tick_spacing, zero_for_one)?
+            .map(|tick_state| self.written_tick(tick_state.tick).unwrap_or(*tick_state)))
+    }
+
+    /// Withdraws the pending limit orders of a crossed tick from the active liquidity, so that they
+    /// are not converted back when the price returns. A limit order spans one tick spacing inside
+    /// one tick array, the other tick of the orders is always in this tick array too.
+    /// Returns the fee and reward growth inside the range of the filled orders, which they earn up to.
+    fn fill_limit_orders(
+        &mut self,
+        tick_index: i32,
+        tick_spacing: u16,
+        zero_for_one: bool,
+        fee_growth_global_0_x64: u128,
+        fee_growth_global_1_x64: u128,
+        reward_infos: &[RewardInfo; REWARD_NUM],
+    ) -> Result<Option<LimitOrderFill>> {
+        let mut tick_state = self.get_tick(tick_index, tick_spacing)?;
+        let order_liquidity = i128::try_from(tick_state.order_liquidity).unwrap();
+        if order_liquidity == 0 {
+            return Ok(None);
+        }
+        // The orders filled by a price moving left are above the tick, which is their lower tick,
+        // and the orders filled by a price moving right are below the tick, which is their upper tick.
+        let (liquidity_net_delta, other_tick_index) = if zero_for_one {
+            (-order_liquidity, tick_index + i32::from(tick_spacing))
+        } else {
+            (order_liquidity, tick_index - i32::from(tick_spacing))
+        };
+        tick_state.liquidity_net = tick_state
+            .liquidity_net
+            .checked_add(liquidity_net_delta)
+            .unwrap();
+        tick_state.order_liquidity = 0;
+        tick_state.order_fill_count = tick_state.order_fill_count.checked_add(1).unwrap();
+        let fill_count = tick_state.order_fill_count;
+        self.update_tick_state(tick_index, tick_spacing, tick_state)?;
+
+        let mut other_tick_state = self.get_tick(other_tick_index, tick_spacing)?;
+        other_tick_state.liquidity_net = other_tick_state
+            .liquidity_net
+            .checked_sub(liquidity_net_delta)
+            .unwrap();
+        self.update_tick_state(other_tick_index, tick_spacing, other_tick_state)?;
+
+        // the tick has been crossed, the current tick is below the lower tick when moving left
+        let (tick_lower, tick_upper, tick_current) = if zero_for_one {
+            (&tick_state, &other_tick_state, tick_index - 1)
+        } else {
+            (&other_tick_state, &tick_state, tick_index)
+        };
+        let (fee_growth_inside_0_x64, fee_growth_inside_1_x64) = get_fee_growth_inside(
+            tick_lower,
+            tick_upper,
+            tick_current,
+            fee_growth_global_0_x64,
+            fee_growth_global_1_x64,
+        );
+        let reward_growths_inside_x64 =
+            get_reward_growths_inside(tick_lower, tick_upper, tick_current, reward_infos);
+        Ok(Some(LimitOrderFill {
+            tick: tick_index,
+            fill_count,
+            fee_growth_inside_0_x64,
+            fee_growth_inside_1_x64,
+            reward_growths_inside_x64,
+        }))
+    }
+}
+
+impl SwapTickArray for RefMut<'_, TickArrayState> {
+    fn tick_array(&self) -> &TickArrayState {
+        self
+    }
+
+    fn written_tick(&self, _tick_index: i32) -> Option<TickState> {
+        None
+    }
+
+    fn update_tick_state(
+        &mut self,
+        tick_index: i32,
+        tick_spacing: u16,
+        tick_state: TickState,
+    ) -> Result<()> {
+        TickArrayState::update_tick_state(self, tick_index, tick_spacing, tick_state)
+    }
+}
+
+/// A tick array account borrowed read-only. The ticks written by a swap are kept aside, so that
+/// a quote runs the swap without copying the account or writing to it.
+pub struct TickArrayOverlay<'a> {
+    tick_array: Ref<'a, TickArrayState>,
+    written_ticks: Vec<TickState>,
+}
+
+impl<'a> TickArrayOverlay<'a> {
+    pub fn new(tick_array: Ref<'a, TickArrayState>) -> Self {
+        Self {
+            tick_array,
+            written_ticks: Vec::new(),
+        }
+    }
+}
+
+impl SwapTickArray for TickArrayOverlay<'_> {
+    fn tick_array(&self) -> &TickArrayState {
+        &self.tick_array
+    }
+
+    fn written_tick(&self, tick_index: i32) -> Option<TickState> {
+        self.written_ticks
+            .iter()
+            .find(|tick_state| { tick_state.tick } == tick_index)
+            .copied()
+    }
+
+    fn update_tick_state(
+        &mut self,
+        tick_index: i32,
+        tick_spacing: u16,
+        tick_state: TickState,
+    ) -> Result<()> {
+        // the tick must be in the tick array
+        self.tick_array.get_tick_state(tick_index, tick_spacing)?;
+        match self
+            .written_ticks
+            .iter_mut()
+            .find(|tick_state| { tick_state.tick } == tick_index)
+        {
+            Some(written_tick) => *written_tick = tick_state,
+            None => self.written_ticks.push(tick_state),
+        }
+        Ok(())
+    }
+}
+
 impl Default for TickArrayState {
     #[inline]
     fn default() -> TickArrayState {
@@ -281,12 +460,23 @@ pub struct TickState {
 
     // Reward growth per unit of liquidity like fee, array of Q64.64
     pub reward_growths_outside_x64: [u128; REWARD_NUM],
+
+    /// Seconds per unit of liquidity on the _other_ side of this tick (relative to the current tick), as a Q64.64
+    /// only has relative meaning like fee growth outside
+    pub seconds_per_liquidity_outside_x64: u128,
+    /// Seconds spent on the _other_ side of this tick (relative to the current tick)
+    /// only has relative meaning like fee growth outside
+    pub seconds_outside: u32,
+    /// Liquidity of the pending limit orders which are filled when the price crosses this tick
+    pub order_liquidity: u128,
+    /// The number of times the pending limit orders of this tick have been filled
+    pub order_fill_count: u64,
     // Unused bytes for future upgrades.
-    pub padding: [u32; 13],
+    pub padding: [u32; 2],
 }
 
 impl TickState {
-    pub const LEN: usize = 4 + 16 + 16 + 16 + 16 + 16 * REWARD_NUM + 16 + 16 + 8 + 8 + 4;
+    pub const LEN: usize = 4 + 16 + 16 + 16 + 16 + 16 * REWARD_NUM + 16 + 4 + 16 + 8 + 4 * 2;
 
     pub fn initialize(&mut self, tick: i32, tick_spacing: u16) -> Result<()> {
         if TickState::check_is_out_of_boundary(tick) {
@@ -308,6 +498,8 @@ impl TickState {
         fee_growth_global_1_x64: u128,
         upper: bool,
         reward_infos: &[RewardInfo; REWARD_NUM],
+        seconds_per_liquidity_global_x64: u128,
+        timestamp: u32,
     ) -> Result<bool> {
         let liquidity_gross_before = self.liquidity_gross;
         let liquidity_gross_after =
@@ -322,6 +514,8 @@ impl TickState {
                 self.fee_growth_outside_0_x64 = fee_growth_global_0_x64;
                 self.fee_growth_outside_1_x64 = fee_growth_global_1_x64;
                 self.reward_growths_outside_x64 = RewardInfo::get_reward_growths(reward_infos);
+                self.seconds_per_liquidity_outside_x64 = seconds_per_liquidity_global_x64;
+                self.seconds_outside = timestamp;
             }
         }
 
@@ -345,6 +539,8 @@ impl TickState {
         fee_growth_global_0_x64: u128,
         fee_growth_global_1_x64: u128,
         reward_infos: &[RewardInfo; REWARD_NUM],
+        seconds_per_liquidity_global_x64: u128,
+        timestamp: u32,
     ) -> i128 {
         self.fee_growth_outside_0_x64 = fee_growth_global_0_x64
             .checked_sub(self.fee_growth_outside_0_x64)
@@ -364,6 +560,10 @@ impl TickState {
                 .unwrap();
         }
 
+        self.seconds_per_liquidity_outside_x64 =
+            seconds_per_liquidity_global_x64.wrapping_sub(self.seconds_per_liquidity_outside_x64);
+        self.seconds_outside = timestamp.wrapping_sub(self.seconds_outside);
+
         self.liquidity_net
     }
 
@@ -373,6 +573,8 @@ impl TickState {
         self.fee_growth_outside_0_x64 = 0;
         self.fee_growth_outside_1_x64 = 0;
         self.reward_growths_outside_x64 = [0; REWARD_NUM];
+        self.seconds_per_liquidity_outside_x64 = 0;
+        self.seconds_outside = 0;
     }
 
     pub fn is_initialized(self) -> bool {
@@ -488,6 +690,46 @@ pub fn get_reward_growths_inside(
     reward_growths_inside
 }
 
+// Calculates the seconds per liquidity and the seconds spent inside of tick_lower and tick_upper.
+/// The values only have relative meaning, compare two snapshots taken while both ticks stay initialized.
+///
+pub fn get_seconds_inside(
+    tick_lower: &TickState,
+    tick_upper: &TickState,
+    tick_current: i32,
+    seconds_per_liquidity_global_x64: u128,
+    timestamp: u32,
+) -> (u128, u32) {
+    if tick_current < tick_lower.tick {
+        (
+            tick_lower
+                .seconds_per_liquidity_outside_x64
+                .wrapping_sub(tick_upper.seconds_per_liquidity_outside_x64),
+            tick_lower
+                .seconds_outside
+                .wrapping_sub(tick_upper.seconds_outside),
+        )
+    } else if tick_current < tick_upper.tick {
+        (
+            seconds_per_liquidity_global_x64
+                .wrapping_sub(tick_lower.seconds_per_liquidity_outside_x64)
+                .wrapping_sub(tick_upper.seconds_per_liquidity_outside_x64),
+            timestamp
+                .wrapping_sub(tick_lower.seconds_outside)
+                .wrapping_sub(tick_upper.seconds_outside),
+        )
+    } else {
+        (
+            tick_upper
+                .seconds_per_liquidity_outside_x64
+                .wrapping_sub(tick_lower.seconds_per_liquidity_outside_x64),
+            tick_upper
+                .seconds_outside
+                .wrapping_sub(tick_lower.seconds_outside),
+        )
+    }
+}
+
 pub fn check_tick_array_start_index(
     tick_array_start_index: i32,
     tick_index: i32,
@@ -568,6 +810,16 @@ pub mod tick_array_test {
         RefCell::new(new_tick_array)
     }
 
+    /// Builds a tick array account which lives until the end of the test, to load with `AccountLoader`
+    pub fn build_tick_array_account_info(
+        tick_array: &TickArrayState,
+    ) -> &'static AccountInfo<'static> {
+        use anchor_lang::Discriminator;
+        let mut data = TickArrayState::discriminator().to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(tick_array));
+        crate::util::build_account_info(Pubkey::new_unique(), data)
+    }
+
     pub fn build_tick(tick: i32, liquidity_gross: u128, liquidity_net: i128) -> RefCell<TickState> {
         let mut new_tick = TickState::default();
         new_tick.tick = tick;
@@ -697,7 +949,7 @@ pub mod tick_array_test {
             let tick_spacing = 15;
             // initialized ticks[-300,-15]
             let tick_array_ref = build_tick_array(-900, tick_spacing, vec![40, 59]);
-            let mut tick_array = tick_array_ref.borrow_mut();
+            let tick_array = tick_array_ref.borrow_mut();
             // one_for_zero, the price increase, tick from small to large
             let tick = tick_array.first_initialized_tick(false).unwrap().tick;
             assert_eq!(-300, tick);
@@ -710,7 +962,7 @@ pub mod tick_array_test {
         fn next_initialized_tick_when_tick_is_positive() {
             // init tick_index [0,30,105]
             let tick_array_ref = build_tick_array(0, 15, vec![0, 2, 7]);
-            let mut tick_array = tick_array_ref.borrow_mut();
+            let tick_array = tick_array_ref.borrow_mut();
 
             // test zero_for_one
             let mut next_tick_state = tick_array.next_initialized_tick(0, 15, true).unwrap();
@@ -749,7 +1001,7 @@ pub mod tick_array_test {
         fn next_initialized_tick_when_tick_is_negative() {
             // init tick_index [-900,-870,-795]
             let tick_array_ref = build_tick_array(-900, 15, vec![0, 2, 7]);
-            let mut tick_array = tick_array_ref.borrow_mut();
+            let tick_array = tick_array_ref.borrow_mut();
 
             // test zero for one
             let mut next_tick_state = tick_array.next_initialized_tick(-900, 15, true).unwrap();
@@ -783,6 +1035,55 @@ pub mod tick_array_test {
             next_tick_state = tick_array.next_initialized_tick(-10, 15, false).unwrap();
             assert!(next_tick_state.is_none());
         }
+
+        #[test]
+        fn fill_limit_orders_test() {
+            let tick_spacing = 15;
+            let mut lower_tick = build_tick(-870, 300, 200).take();
+            lower_tick.order_liquidity = 100;
+            lower_tick.fee_growth_outside_0_x64 = 30;
+            lower_tick.fee_growth_outside_1_x64 = 40;
+            let mut upper_tick = build_tick(-855, 300, -200).take();
+            upper_tick.fee_growth_outside_0_x64 = 10;
+            upper_tick.fee_growth_outside_1_x64 = 20;
+            let tick_array_ref = build_tick_array_with_tick_states(
+                Pubkey::default(),
+                -900,
+                tick_spacing,
+                vec![lower_tick, upper_tick],
+            );
+            let mut tick_array = tick_array_ref.borrow_mut();
+            let reward_infos = [RewardInfo::default(); REWARD_NUM];
+
+            // the orders above the tick are filled by a price moving left
+            let fill = tick_array
+                .fill_limit_orders(-870, tick_spacing, true, 100, 200, &reward_infos)
+                .unwrap()
+                .unwrap();
+            assert_eq!(identity(fill.tick), -870);
+            assert_eq!(identity(fill.fill_count), 1);
+            // the price is below the orders, their growth inside is the growth below the upper
+            // tick minus the growth below the lower tick
+            assert_eq!(identity(fill.fee_growth_inside_0_x64), 100 - 10 - 70);
+            assert_eq!(identity(fill.fee_growth_inside_1_x64), 200 - 20 - 160);
+            let lower_tick = *tick_array.get_tick_state(-870, tick_spacing).unwrap();
+            let upper_tick = *tick_array.get_tick_state(-855, tick_spacing).unwrap();
+            assert_eq!(identity(lower_tick.liquidity_net), 100);
+            assert_eq!(identity(lower_tick.liquidity_gross), 300);
+            assert_eq!(identity(lower_tick.order_liquidity), 0);
+            assert_eq!(identity(lower_tick.order_fill_count), 1);
+            assert_eq!(identity(upper_tick.liquidity_net), -100);
+            assert_eq!(identity(upper_tick.liquidity_gross), 300);
+
+            // nothing is left to fill
+            assert!(tick_array
+                .fill_limit_orders(-870, tick_spacing, false, 100, 200, &reward_infos)
+                .unwrap()
+                .is_none());
+            let lower_tick = *tick_array.get_tick_state(-870, tick_spacing).unwrap();
+            assert_eq!(identity(lower_tick.liquidity_net), 100);
+            assert_eq!(identity(lower_tick.order_fill_count), 1);
+        }
     }
 
     mod get_fee_growth_inside_test {
@@ -823,12 +1124,16 @@ pub mod tick_array_test {
                     fee_growth_global_0_x64,
                     fee_growth_global_1_x64,
                     &[RewardInfo::default(); 3],
+                    0,
+                    0,
                 );
             } else {
                 tick_upper.cross(
                     fee_growth_global_0_x64,
                     fee_growth_global_1_x64,
                     &[RewardInfo::default(); 3],
+                    0,
+                    0,
                 );
             }
 
@@ -1159,9 +1464,9 @@ pub mod tick_array_test {
 
             reward_growth_global_x64 = reward_growth_global_x64 + reward_growth_global_delta;
             if cross_tick_lower {
-                tick_lower.cross(0, 0, &build_reward_infos(reward_growth_global_x64));
+                tick_lower.cross(0, 0, &build_reward_infos(reward_growth_global_x64), 0, 0);
             } else {
-                tick_upper.cross(0, 0, &build_reward_infos(reward_growth_global_x64));
+                tick_upper.cross(0, 0, &build_reward_infos(reward_growth_global_x64), 0, 0);
             }
 
             tick_current = target_tick_current;
@@ -1307,6 +1612,91 @@ pub mod tick_array_test {
             assert_eq!(reward_frowth_inside_delta, 500);
         }
     }
+    mod get_seconds_inside_test {
+        use crate::states::{
+            pool::RewardInfo,
+            tick_array::{get_seconds_inside, TickState},
+        };
+
+        #[test]
+        fn price_in_tick_range_move_to_right_test() {
+            let reward_infos = [RewardInfo::default(); 3];
+            let mut tick_lower = TickState {
+                tick: -10,
+                ..Default::default()
+            };
+            let mut tick_upper = TickState {
+                tick: 10,
+                ..Default::default()
+            };
+            // both ticks initialized at timestamp 100 while the price is in range
+            tick_lower
+                .update(0, 10, 0, 0, false, &reward_infos, 1000, 100)
+                .unwrap();
+            tick_upper
+                .update(0, 10, 0, 0, true, &reward_infos, 1000, 100)
+                .unwrap();
+            assert_eq!(
+                get_seconds_inside(&tick_lower, &tick_upper, 0, 1000, 100),
+                (0, 0)
+            );
+            assert_eq!(
+                get_seconds_inside(&tick_lower, &tick_upper, 0, 1300, 130),
+                (300, 30)
+            );
+
+            // the price leaves the range at timestamp 150
+            tick_upper.cross(0, 0, &reward_infos, 1500, 150);
+            assert_eq!(
+                get_seconds_inside(&tick_lower, &tick_upper, 11, 2000, 200),
+                (500, 50)
+            );
+
+            // and comes back at timestamp 300
+            tick_upper.cross(0, 0, &reward_infos, 2000, 300);
+            assert_eq!(
+                get_seconds_inside(&tick_lower, &tick_upper, 0, 2100, 310),
+                (600, 60)
+            );
+        }
+
+        #[test]
+        fn tick_initialized_above_current_test() {
+            let reward_infos = [RewardInfo::default(); 3];
+            let mut tick_lower = TickState {
+                tick: -10,
+                ..Default::default()
+            };
+            let mut tick_upper = TickState {
+                tick: 10,
+                ..Default::default()
+            };
+            // the range is initialized above the current tick, nothing happened inside yet
+            tick_lower
+                .update(-20, 10, 0, 0, false, &reward_infos, 1000, 100)
+                .unwrap();
+            tick_upper
+                .update(-20, 10, 0, 0, true, &reward_infos, 1000, 100)
+                .unwrap();
+            assert_eq!(
+                get_seconds_inside(&tick_lower, &tick_upper, -20, 1200, 120),
+                (0, 0)
+            );
+
+            // the price enters the range at timestamp 150
+            tick_lower.cross(0, 0, &reward_infos, 1500, 150);
+            let (seconds_per_liquidity_before, seconds_before) =
+                get_seconds_inside(&tick_lower, &tick_upper, 0, 1500, 150);
+            let (seconds_per_liquidity_after, seconds_after) =
+                get_seconds_inside(&tick_lower, &tick_upper, 0, 1800, 180);
+            assert_eq!(
+                seconds_per_liquidity_after.wrapping_sub(seconds_per_liquidity_before),
+                300
+            );
+            assert_eq!(seconds_after.wrapping_sub(seconds_before), 30);
+        }
+    }
+
     mod tick_array_layout_test {
         use super::*;
         use anchor_lang::Discriminator;
@@ -1333,10 +1723,14 @@ pub mod tick_array_test {
                 0x11223344556600778899aabbccddeeff,
                 0x11223344556677008899aabbccddeeff,
             ];
-            let mut tick_padding: [u32; 13] = [0u32; 13];
-            let mut tick_padding_data = [0u8; 4 * 13];
+            let seconds_per_liquidity_outside_x64: u128 = 0x11223344556677880099aabbccddeeff;
+            let seconds_outside: u32 = 0x12345678;
+            let order_liquidity: u128 = 0x11223344556677889900aabbccddeeff;
+            let order_fill_count: u64 = 0x123456789abcde0f;
+            let mut tick_padding: [u32; 2] = [0u32; 2];
+            let mut tick_padding_data = [0u8; 4 * 2];
             let mut offset = 0;
-            for i in 0..13 {
+            for i in 0..2 {
                 tick_padding[i] = u32::MAX - 3 * i as u32;
                 tick_padding_data[offset..offset + 4]
                     .copy_from_slice(&tick_padding[i].to_le_bytes());
@@ -1360,8 +1754,17 @@ pub mod tick_array_test {
                     .copy_from_slice(&reward_growths_outside_x64[i].to_le_bytes());
                 offset += 16;
             }
-            tick_data[offset..offset + 4 * 13].copy_from_slice(&tick_padding_data);
-            offset += 4 * 13;
+            tick_data[offset..offset + 16]
+                .copy_from_slice(&seconds_per_liquidity_outside_x64.to_le_bytes());
+            offset += 16;
+            tick_data[offset..offset + 4].copy_from_slice(&seconds_outside.to_le_bytes());
+            offset += 4;
+            tick_data[offset..offset + 16].copy_from_slice(&order_liquidity.to_le_bytes());
+            offset += 16;
+            tick_data[offset..offset + 8].copy_from_slice(&order_fill_count.to_le_bytes());
+            offset += 8;
+            tick_data[offset..offset + 4 * 2].copy_from_slice(&tick_padding_data);
+            offset += 4 * 2;
             assert_eq!(offset, tick_data.len());
             assert_eq!(tick_data.len(), core::mem::size_of::<TickState>());
 
@@ -1419,6 +1822,18 @@ pub mod tick_array_test {
                     unpack_reward_growths_outside_x64,
                     reward_growths_outside_x64
                 );
+                let unpack_seconds_per_liquidity_outside_x64 =
+                    tick_item.seconds_per_liquidity_outside_x64;
+                assert_eq!(
+                    unpack_seconds_per_liquidity_outside_x64,
+                    seconds_per_liquidity_outside_x64
+                );
+                let unpack_seconds_outside = tick_item.seconds_outside;
+                assert_eq!(unpack_seconds_outside, seconds_outside);
+                let unpack_order_liquidity = tick_item.order_liquidity;
+                assert_eq!(unpack_order_liquidity, order_liquidity);
+                let unpack_order_fill_count = tick_item.order_fill_count;
+                assert_eq!(unpack_order_fill_count, order_fill_count);
                 let unpack_tick_padding = tick_item.padding;
                 assert_eq!(unpack_tick_padding, tick_padding);
             }
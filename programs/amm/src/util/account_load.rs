@@ -3,7 +3,7 @@ use anchor_lang::{Key, Owner, Result, ToAccountInfos, ZeroCopy};
 use arrayref::array_ref;
 use solana_program::account_info::AccountInfo;
 use solana_program::pubkey::Pubkey;
-use std::cell::{Ref, RefMut};
+use std::cell::{Ref, RefCell, RefMut};
 use std::marker::PhantomData;
 use std::mem;
 use std::ops::DerefMut;
@@ -109,6 +109,57 @@ impl<'info, T: ZeroCopy + Owner> AccountLoad<'info, T> {
         }))
     }
 
+    /// Returns a `Ref` to the account data structure for reading directly, the account doesn't
+    /// need to be writable. It is necessary to check the owner like `load_data_mut`.
+    pub fn load_data<'a>(acc_info: &'a AccountInfo) -> Result<Ref<'a, T>> {
+        if acc_info.owner != &T::owner() {
+            return Err(Error::from(ErrorCode::AccountOwnedByWrongProgram)
+                .with_pubkeys((*acc_info.owner, T::owner())));
+        }
+
+        let data = acc_info.try_borrow_data()?;
+        if data.len() < mem::size_of::<T>() + 8 {
+            return Err(ErrorCode::AccountDiscriminatorNotFound.into());
+        }
+
+        let disc_bytes = array_ref![data, 0, 8];
+        if disc_bytes != &T::discriminator() {
+            return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+
+        Ok(Ref::map(data, |data| {
+            bytemuck::from_bytes(&data[8..mem::size_of::<T>() + 8])
+        }))
+    }
+
+    /// Copies the account data to the heap, the copy can be modified through `load_copy_mut`
+    /// without writing anything back to the account. The account doesn't need to be writable.
+    pub fn load_data_copy(acc_info: &AccountInfo) -> Result<RefCell<Vec<u8>>> {
+        if acc_info.owner != &T::owner() {
+            return Err(Error::from(ErrorCode::AccountOwnedByWrongProgram)
+                .with_pubkeys((*acc_info.owner, T::owner())));
+        }
+
+        let data = acc_info.try_borrow_data()?;
+        if data.len() < mem::size_of::<T>() + 8 {
+            return Err(ErrorCode::AccountDiscriminatorNotFound.into());
+        }
+
+        let disc_bytes = array_ref![data, 0, 8];
+        if disc_bytes != &T::discriminator() {
+            return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+
+        Ok(RefCell::new(data[..mem::size_of::<T>() + 8].to_vec()))
+    }
+
+    /// Returns a `RefMut` to the data structure of a copy made by `load_data_copy`.
+    pub fn load_copy_mut(copy: &RefCell<Vec<u8>>) -> RefMut<'_, T> {
+        RefMut::map(copy.borrow_mut(), |data| {
+            bytemuck::from_bytes_mut(&mut data.deref_mut()[8..mem::size_of::<T>() + 8])
+        })
+    }
+
     /// Returns a Ref to the account data structure for reading.
     pub fn load(&self) -> Result<Ref<T>> {
         let data = self.acc_info.try_borrow_data()?;
@@ -6,3 +6,6 @@ pub use system::*;
 
 pub mod account_load;
 pub use account_load::*;
+
+pub mod swap_hook;
+pub use swap_hook::*;
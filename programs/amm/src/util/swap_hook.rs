@@ -0,0 +1,101 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{hash::hash, instruction::Instruction, program::invoke};
+
+/// The instruction of the hook program called before a swap, an error rejects the swap
+pub const BEFORE_SWAP_HOOK: &str = "before_swap";
+/// The instruction of the hook program called after a swap, an error reverts the swap
+pub const AFTER_SWAP_HOOK: &str = "after_swap";
+
+/// The data of the `before_swap` hook call
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BeforeSwapParams {
+    /// The direction of the swap, true for token_0 to token_1
+    pub zero_for_one: bool,
+    /// Whether `amount_specified` is the input or the output amount
+    pub is_base_input: bool,
+    /// The amount requested by the swapper
+    pub amount_specified: u64,
+    /// The Q64.64 sqrt price limit of the swap, zero means no limit
+    pub sqrt_price_limit_x64: u128,
+}
+
+/// The data of the `after_swap` hook call
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AfterSwapParams {
+    /// The direction of the swap, true for token_0 to token_1
+    pub zero_for_one: bool,
+    /// The amount of token_0 swapped by the pool
+    pub amount_0: u64,
+    /// The amount of token_1 swapped by the pool
+    pub amount_1: u64,
+    /// The sqrt price of the pool after the swap
+    pub sqrt_price_x64: u128,
+    /// The tick of the pool after the swap
+    pub tick: i32,
+    /// The trade fee rate charged
+    pub trade_fee_rate: u32,
+}
+
+/// Finds the hook program and state accounts of the pool in the remaining accounts.
+/// Returns None if the pool has no hook
+pub fn get_swap_hook_accounts<'c, 'info>(
+    pool_state: &PoolState,
+    remaining_accounts: &'c [AccountInfo<'info>],
+) -> Result<Option<(&'c AccountInfo<'info>, &'c AccountInfo<'info>)>> {
+    if !pool_state.has_swap_hook() {
+        return Ok(None);
+    }
+    let swap_hook_program = remaining_accounts
+        .iter()
+        .find(|account_info| account_info.key() == pool_state.swap_hook_program);
+    let swap_hook_state = remaining_accounts
+        .iter()
+        .find(|account_info| account_info.key() == pool_state.swap_hook_state);
+    match (swap_hook_program, swap_hook_state) {
+        (Some(swap_hook_program), Some(swap_hook_state)) => {
+            Ok(Some((swap_hook_program, swap_hook_state)))
+        }
+        _ => err!(ErrorCode::SwapHookAccountsMissing),
+    }
+}
+
+/// Calls `hook_name` of the hook program with the Anchor instruction layout.
+/// The accounts are, in order: swapper, pool state, input token account, output token account
+/// and the writable hook state. Only the hook state is writable and none is a signer
+pub fn invoke_swap_hook<'info, T: AnchorSerialize>(
+    swap_hook_program: &AccountInfo<'info>,
+    swap_hook_state: &AccountInfo<'info>,
+    swapper: &AccountInfo<'info>,
+    pool_state: &AccountInfo<'info>,
+    input_token_account: &AccountInfo<'info>,
+    output_token_account: &AccountInfo<'info>,
+    hook_name: &str,
+    params: &T,
+) -> Result<()> {
+    let mut data = hash(format!("global:{}", hook_name).as_bytes()).to_bytes()[..8].to_vec();
+    params.serialize(&mut data)?;
+    invoke(
+        &Instruction {
+            program_id: swap_hook_program.key(),
+            accounts: vec![
+                AccountMeta::new_readonly(swapper.key(), false),
+                AccountMeta::new_readonly(pool_state.key(), false),
+                AccountMeta::new_readonly(input_token_account.key(), false),
+                AccountMeta::new_readonly(output_token_account.key(), false),
+                AccountMeta::new(swap_hook_state.key(), false),
+            ],
+            data,
+        },
+        &[
+            swapper.clone(),
+            pool_state.clone(),
+            input_token_account.clone(),
+            output_token_account.clone(),
+            swap_hook_state.clone(),
+            swap_hook_program.clone(),
+        ],
+    )?;
+    Ok(())
+}
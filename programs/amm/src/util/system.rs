@@ -69,3 +69,49 @@ pub fn get_recent_epoch() -> Result<u64> {
         .as_secs()
         / (2 * 24 * 3600))
 }
+
+/// Serves the clock sysvar to `Clock::get` in tests, with the current system time
+#[cfg(test)]
+pub fn set_clock_sysvar_stub() {
+    use solana_program::{entrypoint::SUCCESS, program_stubs};
+    use std::sync::Once;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    struct ClockSysvarStub;
+    impl program_stubs::SyscallStubs for ClockSysvarStub {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            let unix_timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs();
+            unsafe {
+                *(var_addr as *mut Clock) = Clock {
+                    unix_timestamp: unix_timestamp as i64,
+                    epoch: get_recent_epoch().unwrap(),
+                    ..Default::default()
+                };
+            }
+            SUCCESS
+        }
+    }
+
+    static STUB: Once = Once::new();
+    STUB.call_once(|| {
+        program_stubs::set_syscall_stubs(Box::new(ClockSysvarStub));
+    });
+}
+
+/// Builds a program owned account with the given data that lives for the whole test
+#[cfg(test)]
+pub fn build_account_info(key: Pubkey, data: Vec<u8>) -> &'static AccountInfo<'static> {
+    Box::leak(Box::new(AccountInfo::new(
+        Box::leak(Box::new(key)),
+        false,
+        true,
+        Box::leak(Box::new(0)),
+        Box::leak(data.into_boxed_slice()),
+        Box::leak(Box::new(crate::id())),
+        false,
+        0,
+    )))
+}